@@ -11,8 +11,10 @@ pub fn key_segment_derive(input: TokenStream) -> TokenStream {
 
 fn impl_key_segment(ast: &syn::DeriveInput) -> TokenStream {
     let name = &ast.ident;
+    // Generic widgets get the same segment whatever their parameters are
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
     let gen = quote! {
-        impl KeySegment for #name {
+        impl #impl_generics KeySegment for #name #type_generics #where_clause {
             fn key_segment(&self) -> String {
                 return stringify!(#name).to_string();
             }
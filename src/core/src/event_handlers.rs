@@ -1,50 +1,632 @@
-use std::process::exit;
+use std::{any::Any, process::exit, rc::Rc};
 
-use crate::{graphics::Position, platform::Platform, ui_library::Widget, AppRunner};
+use crate::{
+    graphics::{Position, Rect, Transform},
+    platform::Platform,
+    ui_library::{constraints::BoxConstraints, theme::Theme, Key, Widget},
+    AppRunner,
+};
+
+/// How far the pointer has to move with a button held before it counts as a
+/// drag rather than a click
+pub const DRAG_THRESHOLD: f64 = 4.;
+
+/// How many pixels one notch of a mouse wheel scrolls, for backends which
+/// report the wheel in notches or lines rather than pixels
+pub const WHEEL_LINE_HEIGHT: f64 = 40.;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+    Other(u8),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+impl Modifiers {
+    pub fn none() -> Self {
+        return Self {
+            shift: false,
+            ctrl: false,
+            alt: false,
+            meta: false,
+        };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MouseButtonEventKind {
+    Down,
+    Up,
+    Click,
+    DoubleClick,
+    DragStart,
+    Drag,
+    DragEnd,
+}
+
+#[derive(Debug, Clone)]
+pub struct MouseButtonEvent {
+    pub kind: MouseButtonEventKind,
+    pub button: MouseButton,
+    pub position: Position,
+    pub modifiers: Modifiers,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyCode {
+    Tab,
+    Enter,
+    Escape,
+    Backspace,
+    Delete,
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Shift,
+    Control,
+    Alt,
+    Meta,
+    F(u8),
+    /// A printable key, always given in lower case. Use `Modifiers::shift`
+    /// to tell upper and lower case apart, or listen for `TextInput`.
+    Char(char),
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyEvent {
+    pub key: KeyCode,
+    pub modifiers: Modifiers,
+    pub is_repeat: bool,
+}
+
+/// A turn of the mouse wheel or a trackpad swipe. The deltas are in pixels,
+/// and positive values scroll towards the end of the content, i.e. they move
+/// the content up or to the left.
+#[derive(Debug, Clone)]
+pub struct WheelEvent {
+    pub position: Position,
+    pub delta_x: f64,
+    pub delta_y: f64,
+    pub modifiers: Modifiers,
+}
+
+/// Any event which is dispatched to the focused widget and then bubbles up
+/// through its ancestors until one of them captures it
+#[derive(Debug, Clone)]
+pub enum KeyboardEvent {
+    KeyDown(KeyEvent),
+    KeyUp(KeyEvent),
+    /// Text typed by the user, after the platform has applied the keyboard
+    /// layout, shift state, dead keys and so on
+    TextInput(String),
+}
+
+/// Any event which is dispatched to widgets according to where the pointer is
+#[derive(Debug, Clone)]
+pub enum PointerEvent {
+    Move(Position),
+    Button(MouseButtonEvent),
+    /// The pointer moved onto the widget
+    Enter(Position),
+    /// The pointer moved off the widget, or out of the window
+    Leave(Position),
+    Wheel(WheelEvent),
+}
+
+impl PointerEvent {
+    pub fn position(&self) -> &Position {
+        return match self {
+            PointerEvent::Move(position) => position,
+            PointerEvent::Button(event) => &event.position,
+            PointerEvent::Enter(position) => position,
+            PointerEvent::Leave(position) => position,
+            PointerEvent::Wheel(event) => &event.position,
+        };
+    }
+    /// The same event with its position mapped through `transform`
+    pub fn transformed(&self, transform: &Transform) -> PointerEvent {
+        return match self {
+            PointerEvent::Move(position) => PointerEvent::Move(transform.apply(position)),
+            PointerEvent::Button(event) => PointerEvent::Button(MouseButtonEvent {
+                position: transform.apply(&event.position),
+                ..event.clone()
+            }),
+            PointerEvent::Enter(position) => PointerEvent::Enter(transform.apply(position)),
+            PointerEvent::Leave(position) => PointerEvent::Leave(transform.apply(position)),
+            PointerEvent::Wheel(event) => PointerEvent::Wheel(WheelEvent {
+                position: transform.apply(&event.position),
+                delta_x: transform.apply_length(event.delta_x),
+                delta_y: transform.apply_length(event.delta_y),
+                modifiers: event.modifiers.clone(),
+            }),
+        };
+    }
+}
+
+struct PressedButton {
+    button: MouseButton,
+    press_position: Position,
+    modifiers: Modifiers,
+    is_dragging: bool,
+    drag_target: Option<Key>,
+    /// The widget which captured the press. Releasing the button over it
+    /// clicks it.
+    target: Option<Key>,
+}
+
+/// What the runner remembers about the pointer between events, so that it
+/// can turn presses, releases and moves into clicks and drags
+pub struct PointerState {
+    pressed: Option<PressedButton>,
+    position: Position,
+    /// Every widget which was under the pointer after the last move, from the
+    /// root downwards
+    hovered_keys: Vec<Key>,
+}
+
+impl PointerState {
+    pub fn new() -> Self {
+        return Self {
+            pressed: None,
+            position: Position::origin(),
+            hovered_keys: vec![],
+        };
+    }
+}
+
+impl Default for PointerState {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
 
 pub struct HandledEventInfo {
-    pub needs_relayout: bool,
+    /// The widgets whose size changed, which their ancestors need to make
+    /// room for. Only the widgets on the paths down to them are laid out
+    /// again.
+    pub relayout_keys: Vec<Key>,
     pub needs_redraw: bool,
+    /// Areas of the screen which need repainting. If `needs_redraw` is set
+    /// but this is empty then the whole screen is repainted.
+    pub dirty_regions: Vec<Rect>,
+    /// The key of the widget which captured the event, if any
+    pub captured_by: Option<Key>,
+    /// New values for `Provider`s, each with the key of the widget to look
+    /// for the nearest provider of its type from
+    pub context_updates: Vec<(Key, Rc<dyn Any>)>,
 }
 
 impl HandledEventInfo {
     pub fn new() -> Self {
         return Self {
-            needs_relayout: false,
+            relayout_keys: vec![],
             needs_redraw: false,
+            dirty_regions: vec![],
+            captured_by: None,
+            context_updates: vec![],
         };
     }
+
+    /// Requests that `region`, given in the handling widget's own coordinates,
+    /// is repainted. It is converted to screen coordinates by the dispatcher.
+    pub fn mark_dirty(&mut self, region: Rect) {
+        self.needs_redraw = true;
+        self.dirty_regions.push(region);
+    }
+
+    /// Requests that the widget with `key` and its ancestors are laid out
+    /// again, e.g. because its size changed
+    pub fn request_relayout(&mut self, key: Key) {
+        self.relayout_keys.push(key);
+    }
+
+    /// Replaces the value of the nearest `Provider` of a `T` at or above the
+    /// widget with key `from` once the event has been handled, rebuilding
+    /// the widgets which looked it up
+    pub fn update_context<T: 'static>(&mut self, from: &Key, value: T) {
+        self.context_updates.push((from.clone(), Rc::new(value)));
+    }
+
+    /// Switches the whole app to `theme` once the event has been handled
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.update_context(&Key::root(), theme);
+    }
+
+    pub fn needs_relayout(&self) -> bool {
+        return !self.relayout_keys.is_empty();
+    }
+
+    /// Combines the results of handling another event into this one
+    pub fn merge(&mut self, other: HandledEventInfo) {
+        self.relayout_keys.extend(other.relayout_keys);
+        self.needs_redraw |= other.needs_redraw;
+        self.dirty_regions.extend(other.dirty_regions);
+        self.context_updates.extend(other.context_updates);
+    }
+
+    /// Applies `transform` to every dirty region from index `first` onwards,
+    /// i.e. to those marked since `dirty_regions` had that length.
+    pub fn transform_dirty_regions_from(&mut self, first: usize, transform: &Transform) {
+        for region in &mut self.dirty_regions[first..] {
+            *region = transform.apply_rect(region);
+        }
+    }
 }
 
 impl<PlatformType: Platform, AppType: Widget> AppRunner<PlatformType, AppType> {
     pub fn on_window_resize(&mut self) {
         self.app.set_layout(
-            Position::origin(),
-            self.platform.graphics().get_screen_dimensions(),
+            &BoxConstraints::tight(&self.platform.graphics().get_screen_dimensions()),
+            &self.platform,
         );
         self.draw_frame();
     }
 
+    /// Switches the whole app to `theme`
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.update_contexts(vec![(Key::root(), Rc::new(theme))], &[]);
+    }
+
     pub fn on_quit(&self) {
         exit(0);
     }
 
     pub fn on_mouse_move(&mut self, position: Position) {
         let mut handled_event_info = HandledEventInfo::new();
-        self.app.on_mouse_move_anywhere(
+        self.pointer_state.position = position.clone();
+
+        let mut hovered_keys: Vec<Key> = vec![];
+        self.app.hit_test(
             Position::origin(),
             &position,
             &self.platform,
+            &mut hovered_keys,
+        );
+        self.update_hovered_keys(hovered_keys, &position, &mut handled_event_info);
+
+        self.app.on_pointer_event_anywhere(
+            Position::origin(),
+            &PointerEvent::Move(position.clone()),
+            &self.platform,
             &mut handled_event_info,
         );
-        if handled_event_info.needs_relayout {
-            self.app.set_layout(
+
+        if let Some(pressed) = &mut self.pointer_state.pressed {
+            let dx = position.x - pressed.press_position.x;
+            let dy = position.y - pressed.press_position.y;
+            if !pressed.is_dragging && (dx * dx + dy * dy).sqrt() >= DRAG_THRESHOLD {
+                pressed.is_dragging = true;
+                let mut drag_start_info = HandledEventInfo::new();
+                self.app.on_pointer_event_anywhere(
+                    Position::origin(),
+                    &PointerEvent::Button(MouseButtonEvent {
+                        kind: MouseButtonEventKind::DragStart,
+                        button: pressed.button.clone(),
+                        position: pressed.press_position.clone(),
+                        modifiers: pressed.modifiers.clone(),
+                    }),
+                    &self.platform,
+                    &mut drag_start_info,
+                );
+                pressed.drag_target = drag_start_info.captured_by.clone();
+                handled_event_info.merge(drag_start_info);
+            }
+            if let Some(drag_target) = &pressed.drag_target {
+                self.app.on_pointer_event_for_key(
+                    drag_target,
+                    Position::origin(),
+                    &PointerEvent::Button(MouseButtonEvent {
+                        kind: MouseButtonEventKind::Drag,
+                        button: pressed.button.clone(),
+                        position: position.clone(),
+                        modifiers: pressed.modifiers.clone(),
+                    }),
+                    &self.platform,
+                    &mut handled_event_info,
+                );
+            }
+        }
+
+        self.apply_handled_event_info(handled_event_info);
+    }
+
+    pub fn on_mouse_down(&mut self, position: Position, button: MouseButton, modifiers: Modifiers) {
+        let mut handled_event_info = HandledEventInfo::new();
+        self.focus_at(&position, &mut handled_event_info);
+        let mut down_info = HandledEventInfo::new();
+        self.app.on_pointer_event_anywhere(
+            Position::origin(),
+            &PointerEvent::Button(MouseButtonEvent {
+                kind: MouseButtonEventKind::Down,
+                button: button.clone(),
+                position: position.clone(),
+                modifiers: modifiers.clone(),
+            }),
+            &self.platform,
+            &mut down_info,
+        );
+        let target = down_info.captured_by.clone();
+        handled_event_info.merge(down_info);
+
+        if self.pointer_state.pressed.is_none() {
+            self.pointer_state.pressed = Some(PressedButton {
+                button,
+                press_position: position,
+                modifiers,
+                is_dragging: false,
+                drag_target: None,
+                target,
+            });
+        }
+
+        self.apply_handled_event_info(handled_event_info);
+    }
+
+    /// `click_count` is the number of clicks in quick succession as reported
+    /// by the backend, so 2 means that this release completes a double click.
+    pub fn on_mouse_up(
+        &mut self,
+        position: Position,
+        button: MouseButton,
+        modifiers: Modifiers,
+        click_count: u32,
+    ) {
+        let mut handled_event_info = HandledEventInfo::new();
+
+        let mut drag_target: Option<Key> = None;
+        let mut click_target: Option<Key> = None;
+        let is_release_of_pressed_button = match &self.pointer_state.pressed {
+            Some(pressed) => pressed.button == button,
+            None => false,
+        };
+        if is_release_of_pressed_button {
+            let pressed = self.pointer_state.pressed.take().unwrap();
+            if pressed.is_dragging {
+                drag_target = pressed.drag_target;
+            } else if let Some(target) = pressed.target {
+                // Pressing one widget and releasing over another isn't a
+                // click on either of them
+                let mut hits: Vec<Key> = vec![];
+                self.app
+                    .hit_test(Position::origin(), &position, &self.platform, &mut hits);
+                if hits.contains(&target) {
+                    click_target = Some(target);
+                }
+            }
+        }
+
+        self.app.on_pointer_event_anywhere(
+            Position::origin(),
+            &PointerEvent::Button(MouseButtonEvent {
+                kind: MouseButtonEventKind::Up,
+                button: button.clone(),
+                position: position.clone(),
+                modifiers: modifiers.clone(),
+            }),
+            &self.platform,
+            &mut handled_event_info,
+        );
+
+        if let Some(click_target) = click_target {
+            let mut kinds = vec![MouseButtonEventKind::Click];
+            if click_count == 2 {
+                kinds.push(MouseButtonEventKind::DoubleClick);
+            }
+            for kind in kinds {
+                self.app.on_pointer_event_for_key(
+                    &click_target,
+                    Position::origin(),
+                    &PointerEvent::Button(MouseButtonEvent {
+                        kind,
+                        button: button.clone(),
+                        position: position.clone(),
+                        modifiers: modifiers.clone(),
+                    }),
+                    &self.platform,
+                    &mut handled_event_info,
+                );
+            }
+        }
+
+        if let Some(drag_target) = drag_target {
+            self.app.on_pointer_event_for_key(
+                &drag_target,
                 Position::origin(),
-                self.platform.graphics().get_screen_dimensions(),
+                &PointerEvent::Button(MouseButtonEvent {
+                    kind: MouseButtonEventKind::DragEnd,
+                    button,
+                    position,
+                    modifiers,
+                }),
+                &self.platform,
+                &mut handled_event_info,
             );
         }
-        if handled_event_info.needs_redraw {
+
+        self.apply_handled_event_info(handled_event_info);
+    }
+
+    pub fn on_mouse_wheel(
+        &mut self,
+        position: Position,
+        delta_x: f64,
+        delta_y: f64,
+        modifiers: Modifiers,
+    ) {
+        let mut handled_event_info = HandledEventInfo::new();
+        self.app.on_pointer_event_anywhere(
+            Position::origin(),
+            &PointerEvent::Wheel(WheelEvent {
+                position,
+                delta_x,
+                delta_y,
+                modifiers,
+            }),
+            &self.platform,
+            &mut handled_event_info,
+        );
+        self.apply_handled_event_info(handled_event_info);
+    }
+
+    pub fn on_key_down(&mut self, key: KeyCode, modifiers: Modifiers, is_repeat: bool) {
+        let mut handled_event_info = HandledEventInfo::new();
+        let event = KeyboardEvent::KeyDown(KeyEvent {
+            key: key.clone(),
+            modifiers: modifiers.clone(),
+            is_repeat,
+        });
+        let captured = self.dispatch_keyboard_event(&event, &mut handled_event_info);
+        if !captured && key == KeyCode::Tab {
+            if modifiers.shift {
+                self.focus_previous(&mut handled_event_info);
+            } else {
+                self.focus_next(&mut handled_event_info);
+            }
+        }
+        self.apply_handled_event_info(handled_event_info);
+    }
+
+    pub fn on_key_up(&mut self, key: KeyCode, modifiers: Modifiers) {
+        let mut handled_event_info = HandledEventInfo::new();
+        let event = KeyboardEvent::KeyUp(KeyEvent {
+            key,
+            modifiers,
+            is_repeat: false,
+        });
+        self.dispatch_keyboard_event(&event, &mut handled_event_info);
+        self.apply_handled_event_info(handled_event_info);
+    }
+
+    pub fn on_text_input(&mut self, text: String) {
+        let mut handled_event_info = HandledEventInfo::new();
+        self.dispatch_keyboard_event(&KeyboardEvent::TextInput(text), &mut handled_event_info);
+        self.apply_handled_event_info(handled_event_info);
+    }
+
+    /// Sends the event to the focused widget, or to the root if nothing has
+    /// focus, from where it bubbles up until captured
+    fn dispatch_keyboard_event(
+        &mut self,
+        event: &KeyboardEvent,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        let target = match self.focus_manager.focused() {
+            Some(x) => x.clone(),
+            None => self.app.get_key().clone(),
+        };
+        return self.app.on_keyboard_event_for_key(
+            &target,
+            Position::origin(),
+            event,
+            &self.platform,
+            handled_event_info,
+        );
+    }
+
+    /// Called when the pointer leaves the window entirely
+    pub fn on_mouse_leave_window(&mut self) {
+        let mut handled_event_info = HandledEventInfo::new();
+        let position = self.pointer_state.position.clone();
+        self.update_hovered_keys(vec![], &position, &mut handled_event_info);
+        self.apply_handled_event_info(handled_event_info);
+    }
+
+    /// Sends leave events to the widgets which are no longer under the
+    /// pointer, deepest first, and then enter events to the ones which now
+    /// are, outermost first
+    fn update_hovered_keys(
+        &mut self,
+        hovered_keys: Vec<Key>,
+        position: &Position,
+        handled_event_info: &mut HandledEventInfo,
+    ) {
+        for key in self.pointer_state.hovered_keys.iter().rev() {
+            if !hovered_keys.contains(key) {
+                self.app.on_pointer_event_for_key(
+                    key,
+                    Position::origin(),
+                    &PointerEvent::Leave(position.clone()),
+                    &self.platform,
+                    handled_event_info,
+                );
+            }
+        }
+        for key in &hovered_keys {
+            if !self.pointer_state.hovered_keys.contains(key) {
+                self.app.on_pointer_event_for_key(
+                    key,
+                    Position::origin(),
+                    &PointerEvent::Enter(position.clone()),
+                    &self.platform,
+                    handled_event_info,
+                );
+            }
+        }
+        self.pointer_state.hovered_keys = hovered_keys;
+    }
+
+    pub(crate) fn apply_handled_event_info(&mut self, handled_event_info: HandledEventInfo) {
+        // Handlers may have rebuilt the focused widget away
+        self.forget_removed_focus();
+        if !handled_event_info.context_updates.is_empty() {
+            self.update_contexts(
+                handled_event_info.context_updates,
+                &handled_event_info.relayout_keys,
+            );
+        } else if handled_event_info.needs_relayout() {
+            for key in &handled_event_info.relayout_keys {
+                self.app.invalidate_layout_for_key(key);
+            }
+            self.app.set_layout(
+                &BoxConstraints::tight(&self.platform.graphics().get_screen_dimensions()),
+                &self.platform,
+            );
             self.draw_frame();
+        } else if handled_event_info.needs_redraw {
+            self.redraw_dirty_regions(&handled_event_info.dirty_regions);
         }
     }
+
+    /// Replaces provided values and rebuilds the widgets which depend on
+    /// them, then lays out everything that changed, along with the widgets
+    /// with `relayout_keys`, and redraws the whole screen
+    fn update_contexts(&mut self, updates: Vec<(Key, Rc<dyn Any>)>, relayout_keys: &[Key]) {
+        let mut dependents: Vec<Key> = vec![];
+        for (from, value) in updates {
+            dependents.extend(self.state_manager.borrow_mut().update_context(&from, value));
+        }
+        for key in &dependents {
+            // Rebuilding an ancestor rebuilds this one too
+            if dependents.iter().any(|other| other.is_ancestor_of(key)) {
+                continue;
+            }
+            if self.app.rebuild_key(key) {
+                self.app.invalidate_layout_for_key(key);
+            }
+        }
+        for key in relayout_keys {
+            self.app.invalidate_layout_for_key(key);
+        }
+        self.app.set_layout(
+            &BoxConstraints::tight(&self.platform.graphics().get_screen_dimensions()),
+            &self.platform,
+        );
+        self.forget_removed_focus();
+        self.draw_frame();
+    }
 }
@@ -0,0 +1,133 @@
+use crate::{
+    event_handlers::HandledEventInfo,
+    graphics::Position,
+    platform::Platform,
+    ui_library::{Key, Widget},
+    AppRunner,
+};
+
+/// Keeps track of which widget, if any, receives keyboard events. Widgets are
+/// identified by their key, so focus survives rebuilds as long as the focused
+/// widget keeps its place in the tree.
+#[derive(Debug, Clone)]
+pub struct FocusManager {
+    focused: Option<Key>,
+}
+
+impl FocusManager {
+    pub fn new() -> Self {
+        return Self { focused: None };
+    }
+
+    pub fn focused(&self) -> Option<&Key> {
+        return self.focused.as_ref();
+    }
+
+    pub fn is_focused(&self, key: &Key) -> bool {
+        return self.focused.as_ref() == Some(key);
+    }
+
+    /// The key after the focused one in `focusable_keys`, wrapping around at
+    /// the end. Starts from the first key if nothing focusable is focused.
+    pub fn next(&self, focusable_keys: &[Key]) -> Option<Key> {
+        let index = match self.focused_index(focusable_keys) {
+            Some(x) => (x + 1) % focusable_keys.len(),
+            None => 0,
+        };
+        return focusable_keys.get(index).cloned();
+    }
+
+    /// The key before the focused one in `focusable_keys`, wrapping around at
+    /// the start. Starts from the last key if nothing focusable is focused.
+    pub fn previous(&self, focusable_keys: &[Key]) -> Option<Key> {
+        let index = match self.focused_index(focusable_keys) {
+            Some(0) | None => focusable_keys.len().checked_sub(1)?,
+            Some(x) => x - 1,
+        };
+        return focusable_keys.get(index).cloned();
+    }
+
+    fn focused_index(&self, focusable_keys: &[Key]) -> Option<usize> {
+        let focused = self.focused.as_ref()?;
+        return focusable_keys.iter().position(|x| x == focused);
+    }
+}
+
+impl Default for FocusManager {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl<PlatformType: Platform, AppType: Widget> AppRunner<PlatformType, AppType> {
+    /// Moves focus to the widget with the given key, or clears it if `key` is
+    /// `None`, telling both the old and new widgets about the change
+    pub fn set_focus(&mut self, key: Option<Key>, handled_event_info: &mut HandledEventInfo) {
+        if self.focus_manager.focused == key {
+            return;
+        }
+        if let Some(old_key) = self.focus_manager.focused.take() {
+            self.app.set_focus_for_key(
+                &old_key,
+                false,
+                Position::origin(),
+                &self.platform,
+                handled_event_info,
+            );
+        }
+        if let Some(new_key) = &key {
+            self.app.set_focus_for_key(
+                new_key,
+                true,
+                Position::origin(),
+                &self.platform,
+                handled_event_info,
+            );
+        }
+        self.focus_manager.focused = key;
+    }
+
+    pub fn focus_next(&mut self, handled_event_info: &mut HandledEventInfo) {
+        let next = self.focus_manager.next(&self.focusable_keys());
+        self.set_focus(next, handled_event_info);
+    }
+
+    pub fn focus_previous(&mut self, handled_event_info: &mut HandledEventInfo) {
+        let previous = self.focus_manager.previous(&self.focusable_keys());
+        self.set_focus(previous, handled_event_info);
+    }
+
+    /// Focuses the deepest focusable widget under `position`, or clears focus
+    /// if there isn't one
+    pub(crate) fn focus_at(
+        &mut self,
+        position: &Position,
+        handled_event_info: &mut HandledEventInfo,
+    ) {
+        let mut hits: Vec<Key> = vec![];
+        self.app
+            .hit_test(Position::origin(), position, &self.platform, &mut hits);
+        let focusable_keys = self.focusable_keys();
+        let target = hits.into_iter().rev().find(|x| focusable_keys.contains(x));
+        self.set_focus(target, handled_event_info);
+    }
+
+    /// Clears focus without telling anyone if the focused widget was
+    /// removed by a rebuild, or can no longer be focused, so that keyboard
+    /// events go to the root again rather than to a key which isn't there
+    pub(crate) fn forget_removed_focus(&mut self) {
+        let is_removed = match self.focus_manager.focused() {
+            Some(key) => !self.focusable_keys().contains(key),
+            None => false,
+        };
+        if is_removed {
+            self.focus_manager.focused = None;
+        }
+    }
+
+    fn focusable_keys(&self) -> Vec<Key> {
+        let mut keys: Vec<Key> = vec![];
+        self.app.collect_focusable_keys(&mut keys);
+        return keys;
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    fmt,
+    io::Cursor,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use super::{Color, Size};
+
+static NEXT_BITMAP_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Called with a bitmap's id once every clone of it has been dropped
+pub type ReleaseHandler = Box<dyn FnOnce(usize) + Send>;
+
+/// What the clones of a bitmap share
+struct Pixels {
+    id: usize,
+    data: Vec<u8>,
+    on_release: Mutex<Option<ReleaseHandler>>,
+}
+
+impl Drop for Pixels {
+    fn drop(&mut self) {
+        let on_release = match self.on_release.get_mut() {
+            Ok(x) => x.take(),
+            Err(x) => x.into_inner().take(),
+        };
+        if let Some(on_release) = on_release {
+            on_release(self.id);
+        }
+    }
+}
+
+/// An image in memory, as 8-bit RGBA pixels with straight alpha in rows from
+/// the top. Cloning one is cheap and the clone shares its pixels and its id,
+/// which backends use to find the texture they uploaded it to.
+#[derive(Clone)]
+pub struct Bitmap {
+    width: usize,
+    height: usize,
+    pixels: Arc<Pixels>,
+}
+
+impl Bitmap {
+    pub fn new(width: usize, height: usize, pixels: Vec<u8>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height * 4,
+            "A {}x{} bitmap needs {} bytes of RGBA pixels",
+            width,
+            height,
+            width * height * 4
+        );
+        return Self {
+            width,
+            height,
+            pixels: Arc::new(Pixels {
+                id: NEXT_BITMAP_ID.fetch_add(1, Ordering::Relaxed),
+                data: pixels,
+                on_release: Mutex::new(None),
+            }),
+        };
+    }
+
+    /// Decodes a PNG of any colour type and bit depth
+    pub fn decode_png(bytes: &[u8]) -> std::io::Result<Self> {
+        let mut decoder = png::Decoder::new(Cursor::new(bytes));
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        let channels = info.color_type.samples();
+        let mut pixels: Vec<u8> =
+            Vec::with_capacity(info.width as usize * info.height as usize * 4);
+        for pixel in buffer[..info.buffer_size()].chunks_exact(channels) {
+            match channels {
+                1 => pixels.extend([pixel[0], pixel[0], pixel[0], 255]),
+                2 => pixels.extend([pixel[0], pixel[0], pixel[0], pixel[1]]),
+                3 => pixels.extend([pixel[0], pixel[1], pixel[2], 255]),
+                _ => pixels.extend([pixel[0], pixel[1], pixel[2], pixel[3]]),
+            }
+        }
+        return Ok(Self::new(info.width as usize, info.height as usize, pixels));
+    }
+
+    pub fn id(&self) -> usize {
+        return self.pixels.id;
+    }
+
+    /// Has `on_release` called once the last clone of the bitmap is dropped,
+    /// so that a backend can free the texture it uploaded the bitmap to.
+    /// Only the first handler is kept, since a bitmap is only uploaded to
+    /// one backend.
+    pub fn set_release_handler(&self, on_release: ReleaseHandler) {
+        let mut handler = match self.pixels.on_release.lock() {
+            Ok(x) => x,
+            Err(x) => x.into_inner(),
+        };
+        if handler.is_none() {
+            *handler = Some(on_release);
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        return self.width;
+    }
+
+    pub fn height(&self) -> usize {
+        return self.height;
+    }
+
+    pub fn size(&self) -> Size {
+        return Size {
+            width: self.width as f64,
+            height: self.height as f64,
+        };
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        return &self.pixels.data;
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> Color {
+        let i = (y * self.width + x) * 4;
+        return Color::rgba(
+            self.pixels.data[i],
+            self.pixels.data[i + 1],
+            self.pixels.data[i + 2],
+            self.pixels.data[i + 3],
+        );
+    }
+}
+
+/// Bitmaps are the same if they share pixels, without comparing them
+impl PartialEq for Bitmap {
+    fn eq(&self, other: &Self) -> bool {
+        return self.id() == other.id();
+    }
+}
+
+impl fmt::Debug for Bitmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "Bitmap #{} ({}x{})", self.id(), self.width, self.height);
+    }
+}
@@ -0,0 +1,282 @@
+use std::fmt;
+
+use super::{
+    bitmap::Bitmap,
+    gradient::LinearGradient,
+    path::{Path, StrokeStyle},
+    text::TextStyle,
+    Color, Position, Rect, Size, Transform,
+};
+
+/// A single recorded drawing operation. Positions and clip rects are in the
+/// coordinate space set up by the enclosing `PushTransform` entries.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawOp {
+    Fill {
+        color: Color,
+    },
+    FillRect {
+        rect: Rect,
+        color: Color,
+    },
+    FillPath {
+        path: Path,
+        color: Color,
+    },
+    FillPathGradient {
+        path: Path,
+        gradient: LinearGradient,
+    },
+    StrokePath {
+        path: Path,
+        style: StrokeStyle,
+    },
+    FillText {
+        position: Position,
+        text: String,
+        style: TextStyle,
+    },
+    DrawBitmap {
+        bitmap: Bitmap,
+        rect: Rect,
+    },
+    PushClip {
+        rect: Rect,
+    },
+    PopClip,
+    PushTransform {
+        transform: Transform,
+    },
+    PopTransform,
+}
+
+/// A retained recording of everything drawn in a frame. Widgets append to it
+/// in `Widget::draw` and the whole list is then handed to
+/// `GraphicsLibrary::draw_display_list` in one go.
+#[derive(Debug, Clone)]
+pub struct DisplayList {
+    pub ops: Vec<DrawOp>,
+    /// When only part of the screen is being repainted, that part, in screen
+    /// coordinates. Widgets entirely outside of it aren't recorded.
+    visible_region: Option<Rect>,
+    /// The transforms pushed so far, each combined with the ones before it,
+    /// so that what's recorded can be compared with `visible_region`
+    transforms: Vec<Transform>,
+}
+
+impl DisplayList {
+    pub fn new() -> Self {
+        return Self {
+            ops: vec![],
+            visible_region: None,
+            transforms: vec![Transform::identity()],
+        };
+    }
+
+    /// Limits what `Widget::paint` records to widgets which draw within
+    /// `region`, or lifts the limit if it's `None`. Anything recorded is
+    /// still drawn in full, so `region` should be clipped to as well.
+    pub fn set_visible_region(&mut self, region: Option<Rect>) {
+        self.visible_region = region;
+    }
+
+    /// Whether anything drawn within `rect`, in the coordinates currently
+    /// being recorded in, could end up in the visible region
+    pub fn is_visible(&self, rect: &Rect) -> bool {
+        return match &self.visible_region {
+            Some(region) => !self
+                .transforms
+                .last()
+                .unwrap()
+                .apply_rect(rect)
+                .intersection(region)
+                .is_empty(),
+            None => true,
+        };
+    }
+
+    /// Whether only part of the screen is being recorded
+    pub fn has_visible_region(&self) -> bool {
+        return self.visible_region.is_some();
+    }
+
+    pub fn push(&mut self, op: DrawOp) {
+        self.ops.push(op);
+    }
+
+    pub fn fill(&mut self, color: &Color) {
+        self.push(DrawOp::Fill {
+            color: color.clone(),
+        });
+    }
+
+    pub fn fill_rect(&mut self, position: &Position, size: &Size, color: &Color) {
+        self.push(DrawOp::FillRect {
+            rect: Rect::new(position.clone(), size.clone()),
+            color: color.clone(),
+        });
+    }
+
+    pub fn fill_path(&mut self, path: &Path, color: &Color) {
+        self.push(DrawOp::FillPath {
+            path: path.clone(),
+            color: color.clone(),
+        });
+    }
+
+    pub fn fill_path_gradient(&mut self, path: &Path, gradient: &LinearGradient) {
+        self.push(DrawOp::FillPathGradient {
+            path: path.clone(),
+            gradient: gradient.clone(),
+        });
+    }
+
+    pub fn stroke_path(&mut self, path: &Path, style: &StrokeStyle) {
+        self.push(DrawOp::StrokePath {
+            path: path.clone(),
+            style: style.clone(),
+        });
+    }
+
+    pub fn fill_text(&mut self, position: &Position, text: &str, style: &TextStyle) {
+        self.push(DrawOp::FillText {
+            position: position.clone(),
+            text: text.into(),
+            style: style.clone(),
+        });
+    }
+
+    pub fn draw_bitmap(&mut self, bitmap: &Bitmap, position: &Position, size: &Size) {
+        self.push(DrawOp::DrawBitmap {
+            bitmap: bitmap.clone(),
+            rect: Rect::new(position.clone(), size.clone()),
+        });
+    }
+
+    pub fn push_clip(&mut self, rect: &Rect) {
+        self.push(DrawOp::PushClip { rect: rect.clone() });
+    }
+
+    pub fn pop_clip(&mut self) {
+        self.push(DrawOp::PopClip);
+    }
+
+    pub fn push_transform(&mut self, transform: &Transform) {
+        let combined = transform.then(self.transforms.last().unwrap());
+        self.transforms.push(combined);
+        self.push(DrawOp::PushTransform {
+            transform: transform.clone(),
+        });
+    }
+
+    pub fn pop_transform(&mut self) {
+        if self.transforms.len() > 1 {
+            self.transforms.pop();
+        }
+        self.push(DrawOp::PopTransform);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.ops.is_empty();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.ops.len();
+    }
+}
+
+impl Default for DisplayList {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// Lists are the same if they draw the same things
+impl PartialEq for DisplayList {
+    fn eq(&self, other: &Self) -> bool {
+        return self.ops == other.ops;
+    }
+}
+
+impl fmt::Display for DrawOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            DrawOp::Fill { color } => write!(f, "fill {}", color),
+            DrawOp::FillRect { rect, color } => write!(
+                f,
+                "fill_rect ({}, {}) {}x{} {}",
+                rect.position.x, rect.position.y, rect.size.width, rect.size.height, color
+            ),
+            DrawOp::FillPath { path, color } => {
+                write!(f, "fill_path {} segments {}", path.segments.len(), color)
+            }
+            DrawOp::FillPathGradient { path, gradient } => write!(
+                f,
+                "fill_path_gradient {} segments ({}, {}) {} -> ({}, {}) {}",
+                path.segments.len(),
+                gradient.start.x,
+                gradient.start.y,
+                gradient.start_color,
+                gradient.end.x,
+                gradient.end.y,
+                gradient.end_color
+            ),
+            DrawOp::StrokePath { path, style } => write!(
+                f,
+                "stroke_path {} segments {}px {:?} {:?} {}",
+                path.segments.len(),
+                style.width,
+                style.cap,
+                style.join,
+                style.color
+            ),
+            DrawOp::FillText {
+                position,
+                text,
+                style,
+            } => write!(
+                f,
+                "fill_text ({}, {}) {:?} {}px {} {}",
+                position.x, position.y, text, style.font_size, style.font_family, style.color
+            ),
+            DrawOp::DrawBitmap { bitmap, rect } => write!(
+                f,
+                "draw_bitmap #{} ({}, {}) {}x{}",
+                bitmap.id(),
+                rect.position.x,
+                rect.position.y,
+                rect.size.width,
+                rect.size.height
+            ),
+            DrawOp::PushClip { rect } => write!(
+                f,
+                "push_clip ({}, {}) {}x{}",
+                rect.position.x, rect.position.y, rect.size.width, rect.size.height
+            ),
+            DrawOp::PopClip => write!(f, "pop_clip"),
+            DrawOp::PushTransform { transform } => write!(
+                f,
+                "push_transform translate({}, {}) scale({})",
+                transform.translation.x, transform.translation.y, transform.scale
+            ),
+            DrawOp::PopTransform => write!(f, "pop_transform"),
+        };
+    }
+}
+
+/// One op per line, indented by clip and transform depth
+impl fmt::Display for DisplayList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut depth: usize = 0;
+        for op in &self.ops {
+            if let DrawOp::PopClip | DrawOp::PopTransform = op {
+                depth = depth.saturating_sub(1);
+            }
+            writeln!(f, "{}{}", "  ".repeat(depth), op)?;
+            if let DrawOp::PushClip { .. } | DrawOp::PushTransform { .. } = op {
+                depth += 1;
+            }
+        }
+        return Ok(());
+    }
+}
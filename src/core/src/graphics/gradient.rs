@@ -0,0 +1,45 @@
+use super::{Color, Position, Transform};
+
+/// A fill which blends from `start_color` at `start` to `end_color` at `end`.
+/// The colour is constant along lines perpendicular to the one from `start`
+/// to `end`, and points beyond either end take that end's colour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearGradient {
+    pub start: Position,
+    pub end: Position,
+    pub start_color: Color,
+    pub end_color: Color,
+}
+
+impl LinearGradient {
+    pub fn new(start: Position, end: Position, start_color: Color, end_color: Color) -> Self {
+        return Self {
+            start,
+            end,
+            start_color,
+            end_color,
+        };
+    }
+
+    /// The colour at `position`, found by projecting it onto the line from
+    /// `start` to `end`
+    pub fn color_at(&self, position: &Position) -> Color {
+        let dx = self.end.x - self.start.x;
+        let dy = self.end.y - self.start.y;
+        let length_squared = dx * dx + dy * dy;
+        if length_squared == 0. {
+            return self.end_color.clone();
+        }
+        let t =
+            ((position.x - self.start.x) * dx + (position.y - self.start.y) * dy) / length_squared;
+        return self.start_color.lerp(&self.end_color, t);
+    }
+
+    pub fn transformed(&self, transform: &Transform) -> Self {
+        return Self {
+            start: transform.apply(&self.start),
+            end: transform.apply(&self.end),
+            ..self.clone()
+        };
+    }
+}
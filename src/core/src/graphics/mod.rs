@@ -0,0 +1,411 @@
+pub mod bitmap;
+pub mod display_list;
+pub mod gradient;
+pub mod path;
+pub mod raster;
+pub mod tessellation;
+pub mod text;
+
+use bitmap::Bitmap;
+use display_list::DisplayList;
+use gradient::LinearGradient;
+use path::{Path, StrokeStyle};
+use raster::{rasterize, GraphicsLibraryRasterTarget};
+use text::{TextMetrics, TextStyle};
+
+/// An sRGB colour with straight (not premultiplied) alpha, where an `a` of
+/// 255 is fully opaque
+#[derive(Debug, Clone, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
+        return Self { r, g, b, a: 255 };
+    }
+
+    pub fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        return Self { r, g, b, a };
+    }
+
+    pub fn transparent() -> Self {
+        return Self::rgba(0, 0, 0, 0);
+    }
+
+    /// Parses `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`. The `#` is optional.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !digits.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize, width: usize| -> Option<u8> {
+            let value = u8::from_str_radix(&digits[i * width..(i + 1) * width], 16).ok()?;
+            // A single digit is repeated, so "f" means "ff"
+            return Some(if width == 1 { value * 17 } else { value });
+        };
+        return match digits.len() {
+            3 => Some(Self::rgb(channel(0, 1)?, channel(1, 1)?, channel(2, 1)?)),
+            4 => Some(Self::rgba(
+                channel(0, 1)?,
+                channel(1, 1)?,
+                channel(2, 1)?,
+                channel(3, 1)?,
+            )),
+            6 => Some(Self::rgb(channel(0, 2)?, channel(1, 2)?, channel(2, 2)?)),
+            8 => Some(Self::rgba(
+                channel(0, 2)?,
+                channel(1, 2)?,
+                channel(2, 2)?,
+                channel(3, 2)?,
+            )),
+            _ => None,
+        };
+    }
+
+    /// Formats as `#rrggbb`, or `#rrggbbaa` if the colour isn't opaque
+    pub fn to_hex(&self) -> String {
+        if self.is_opaque() {
+            return format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b);
+        }
+        return format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a);
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        return self.a == 255;
+    }
+
+    pub fn with_alpha(&self, a: u8) -> Self {
+        return Self { a, ..self.clone() };
+    }
+
+    /// Interpolates every channel, including alpha, from `self` at `t = 0`
+    /// to `other` at `t = 1`
+    pub fn lerp(&self, other: &Color, t: f64) -> Self {
+        let t = t.clamp(0., 1.);
+        let mix = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * t).round() as u8;
+        return Self {
+            r: mix(self.r, other.r),
+            g: mix(self.g, other.g),
+            b: mix(self.b, other.b),
+            a: mix(self.a, other.a),
+        };
+    }
+
+    /// Mixes in `amount` of white, from 0 (unchanged) to 1 (white), keeping
+    /// the alpha
+    pub fn lighten(&self, amount: f64) -> Self {
+        return self.lerp(&Self::rgba(255, 255, 255, self.a), amount);
+    }
+
+    /// Mixes in `amount` of black, from 0 (unchanged) to 1 (black), keeping
+    /// the alpha
+    pub fn darken(&self, amount: f64) -> Self {
+        return self.lerp(&Self::rgba(0, 0, 0, self.a), amount);
+    }
+
+    /// Composites this colour over `background` with the source-over operator
+    pub fn blend_over(&self, background: &Color) -> Self {
+        if self.a == 255 || background.a == 0 {
+            return self.clone();
+        }
+        if self.a == 0 {
+            return background.clone();
+        }
+        let source_alpha = self.a as f64 / 255.;
+        let background_alpha = background.a as f64 / 255. * (1. - source_alpha);
+        let alpha = source_alpha + background_alpha;
+        let mix = |source: u8, background: u8| {
+            ((source as f64 * source_alpha + background as f64 * background_alpha) / alpha).round()
+                as u8
+        };
+        return Self {
+            r: mix(self.r, background.r),
+            g: mix(self.g, background.g),
+            b: mix(self.b, background.b),
+            a: (alpha * 255.).round() as u8,
+        };
+    }
+}
+
+/// Formats as a CSS colour, e.g. `rgb(255,0,0)` or `rgba(255,0,0,0.5)`
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_opaque() {
+            return write!(f, "rgb({},{},{})", self.r, self.g, self.b);
+        }
+        return write!(
+            f,
+            "rgba({},{},{},{:.3})",
+            self.r,
+            self.g,
+            self.b,
+            self.a as f64 / 255.
+        );
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Position {
+    pub fn origin() -> Self {
+        return Self { x: 0., y: 0. };
+    }
+}
+
+impl std::ops::Add for Position {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Size {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Size {
+    pub fn zero() -> Self {
+        return Self {
+            width: 0.,
+            height: 0.,
+        };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rect {
+    pub position: Position,
+    pub size: Size,
+}
+
+impl Rect {
+    pub fn new(position: Position, size: Size) -> Self {
+        return Self { position, size };
+    }
+
+    pub fn from_size(size: Size) -> Self {
+        return Self {
+            position: Position::origin(),
+            size,
+        };
+    }
+
+    pub fn right(&self) -> f64 {
+        return self.position.x + self.size.width;
+    }
+
+    pub fn bottom(&self) -> f64 {
+        return self.position.y + self.size.height;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.size.width <= 0. || self.size.height <= 0.;
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        return self.position.x <= position.x
+            && position.x <= self.right()
+            && self.position.y <= position.y
+            && position.y <= self.bottom();
+    }
+
+    /// The smallest rect which contains both rects
+    pub fn union(&self, other: &Rect) -> Rect {
+        let x = self.position.x.min(other.position.x);
+        let y = self.position.y.min(other.position.y);
+        return Rect {
+            position: Position { x, y },
+            size: Size {
+                width: self.right().max(other.right()) - x,
+                height: self.bottom().max(other.bottom()) - y,
+            },
+        };
+    }
+
+    /// Grows the rect by `amount` on every side, or shrinks it if `amount` is
+    /// negative
+    pub fn inflate(&self, amount: f64) -> Rect {
+        return Rect {
+            position: Position {
+                x: self.position.x - amount,
+                y: self.position.y - amount,
+            },
+            size: Size {
+                width: (self.size.width + 2. * amount).max(0.),
+                height: (self.size.height + 2. * amount).max(0.),
+            },
+        };
+    }
+
+    /// Combines overlapping rects into their unions, so that no area is
+    /// covered twice, and drops empty ones. Rects which don't overlap are
+    /// kept separate.
+    pub fn merge_overlapping(rects: &[Rect]) -> Vec<Rect> {
+        let mut merged: Vec<Rect> = vec![];
+        for rect in rects.iter().filter(|x| !x.is_empty()) {
+            let mut rect = rect.clone();
+            // Growing the rect can make it overlap ones it didn't before
+            while let Some(i) = merged
+                .iter()
+                .position(|other| !other.intersection(&rect).is_empty())
+            {
+                rect = rect.union(&merged.swap_remove(i));
+            }
+            merged.push(rect);
+        }
+        return merged;
+    }
+
+    /// The overlapping area of the two rects, which is empty if they don't
+    /// overlap
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        let x = self.position.x.max(other.position.x);
+        let y = self.position.y.max(other.position.y);
+        return Rect {
+            position: Position { x, y },
+            size: Size {
+                width: (self.right().min(other.right()) - x).max(0.),
+                height: (self.bottom().min(other.bottom()) - y).max(0.),
+            },
+        };
+    }
+}
+
+/// A transformation from a widget's local coordinates into its parent's.
+/// Points are scaled about the origin and then translated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transform {
+    pub translation: Position,
+    /// The same in both axes, so rects stay axis-aligned and circles stay
+    /// circles
+    pub scale: f64,
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        return Self {
+            translation: Position::origin(),
+            scale: 1.,
+        };
+    }
+
+    pub fn translate(translation: Position) -> Self {
+        return Self {
+            translation,
+            scale: 1.,
+        };
+    }
+
+    pub fn scale(scale: f64) -> Self {
+        return Self {
+            translation: Position::origin(),
+            scale,
+        };
+    }
+
+    /// The transform which applies `self` first and then `outer`
+    pub fn then(&self, outer: &Transform) -> Transform {
+        return Transform {
+            translation: outer.apply(&self.translation),
+            scale: self.scale * outer.scale,
+        };
+    }
+
+    /// The transform which undoes this one. A scale of zero can't be undone,
+    /// so it maps everything to infinity.
+    pub fn inverse(&self) -> Transform {
+        let scale = 1. / self.scale;
+        return Transform {
+            translation: Position {
+                x: -self.translation.x * scale,
+                y: -self.translation.y * scale,
+            },
+            scale,
+        };
+    }
+
+    pub fn apply(&self, position: &Position) -> Position {
+        return Position {
+            x: position.x * self.scale + self.translation.x,
+            y: position.y * self.scale + self.translation.y,
+        };
+    }
+
+    /// Scales a distance, such as a line width or font size
+    pub fn apply_length(&self, length: f64) -> f64 {
+        return length * self.scale;
+    }
+
+    pub fn apply_size(&self, size: &Size) -> Size {
+        return Size {
+            width: self.apply_length(size.width),
+            height: self.apply_length(size.height),
+        };
+    }
+
+    pub fn apply_rect(&self, rect: &Rect) -> Rect {
+        return Rect {
+            position: self.apply(&rect.position),
+            size: self.apply_size(&rect.size),
+        };
+    }
+}
+
+pub trait GraphicsLibrary {
+    fn get_screen_dimensions(&self) -> Size;
+    fn fill_rect(&self, position: &Position, size: &Size, color: &Color);
+    fn fill(&self, color: &Color);
+    /// Fills the inside of `path` according to the non-zero winding rule
+    fn fill_path(&self, path: &Path, color: &Color);
+    fn fill_path_gradient(&self, path: &Path, gradient: &LinearGradient);
+    fn stroke_path(&self, path: &Path, style: &StrokeStyle);
+    /// Draws `text` on a single line with the top-left corner of its line
+    /// box at `position`
+    fn fill_text(&self, position: &Position, text: &str, style: &TextStyle);
+    fn measure_text(&self, text: &str, style: &TextStyle) -> TextMetrics;
+    /// Decodes a PNG and uploads it with `upload_bitmap`
+    fn load_bitmap(&self, png: &[u8]) -> std::io::Result<Bitmap> {
+        let bitmap = Bitmap::decode_png(png)?;
+        self.upload_bitmap(&bitmap);
+        return Ok(bitmap);
+    }
+    /// Gets a bitmap ready to be drawn, e.g. by copying it to a texture, so
+    /// that the first frame it's drawn in isn't slowed down. Backends upload
+    /// bitmaps which haven't been uploaded when they're first drawn anyway,
+    /// and free the copy with `Bitmap::set_release_handler` once the bitmap
+    /// is dropped.
+    fn upload_bitmap(&self, _bitmap: &Bitmap) {}
+    /// Draws the whole bitmap scaled to fill the rect
+    fn draw_bitmap(&self, bitmap: &Bitmap, position: &Position, size: &Size);
+    /// Restricts drawing to the intersection of `rect`, in screen
+    /// coordinates, and the current clip until the matching `pop_clip`
+    fn push_clip(&self, rect: &Rect);
+    fn pop_clip(&self);
+    fn update(&self);
+
+    /// Draws a whole recorded frame. By default this resolves the clips and
+    /// transforms in software and issues one `fill_rect` per visible rect,
+    /// but backends which can accept a batch of commands should override it.
+    fn draw_display_list(&self, display_list: &DisplayList) {
+        let screen = Rect::from_size(self.get_screen_dimensions());
+        rasterize(
+            display_list,
+            &screen,
+            &mut GraphicsLibraryRasterTarget { graphics: self },
+        );
+    }
+}
@@ -0,0 +1,334 @@
+use std::f64::consts::PI;
+
+use super::{Color, Position, Size, Transform};
+
+/// How far, in pixels, a flattened curve may stray from the real one
+pub const FLATTENING_TOLERANCE: f64 = 0.25;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    /// Starts a new subpath
+    MoveTo(Position),
+    LineTo(Position),
+    CubicTo {
+        control_1: Position,
+        control_2: Position,
+        to: Position,
+    },
+    /// An elliptical arc from `start_angle` to `end_angle`, in radians
+    /// measured clockwise from the positive x axis. It sweeps clockwise if
+    /// `end_angle` is greater than `start_angle` and anticlockwise otherwise.
+    /// A straight line joins the current point to the start of the arc.
+    Arc {
+        center: Position,
+        radii: Size,
+        start_angle: f64,
+        end_angle: f64,
+    },
+    /// Joins the current point back to the start of the subpath
+    Close,
+}
+
+/// A shape made of straight and curved segments, which can be filled or
+/// stroked. Coordinates are in the same space as `DrawOp::FillRect`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    pub segments: Vec<PathSegment>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        return Self { segments: vec![] };
+    }
+
+    pub fn move_to(mut self, position: Position) -> Self {
+        self.segments.push(PathSegment::MoveTo(position));
+        return self;
+    }
+
+    pub fn line_to(mut self, position: Position) -> Self {
+        self.segments.push(PathSegment::LineTo(position));
+        return self;
+    }
+
+    pub fn cubic_to(mut self, control_1: Position, control_2: Position, to: Position) -> Self {
+        self.segments.push(PathSegment::CubicTo {
+            control_1,
+            control_2,
+            to,
+        });
+        return self;
+    }
+
+    pub fn arc_to(
+        mut self,
+        center: Position,
+        radii: Size,
+        start_angle: f64,
+        end_angle: f64,
+    ) -> Self {
+        self.segments.push(PathSegment::Arc {
+            center,
+            radii,
+            start_angle,
+            end_angle,
+        });
+        return self;
+    }
+
+    pub fn close(mut self) -> Self {
+        self.segments.push(PathSegment::Close);
+        return self;
+    }
+
+    pub fn line(from: Position, to: Position) -> Self {
+        return Self::new().move_to(from).line_to(to);
+    }
+
+    pub fn polyline(points: &[Position]) -> Self {
+        let mut path = Self::new();
+        for (i, point) in points.iter().enumerate() {
+            path = if i == 0 {
+                path.move_to(point.clone())
+            } else {
+                path.line_to(point.clone())
+            };
+        }
+        return path;
+    }
+
+    pub fn polygon(points: &[Position]) -> Self {
+        return Self::polyline(points).close();
+    }
+
+    pub fn arc(center: Position, radius: f64, start_angle: f64, end_angle: f64) -> Self {
+        return Self::new().arc_to(
+            center,
+            Size {
+                width: radius,
+                height: radius,
+            },
+            start_angle,
+            end_angle,
+        );
+    }
+
+    pub fn ellipse(center: Position, radii: Size) -> Self {
+        return Self::new().arc_to(center, radii, 0., 2. * PI).close();
+    }
+
+    pub fn circle(center: Position, radius: f64) -> Self {
+        return Self::ellipse(
+            center,
+            Size {
+                width: radius,
+                height: radius,
+            },
+        );
+    }
+
+    pub fn transformed(&self, transform: &Transform) -> Self {
+        let segments = self
+            .segments
+            .iter()
+            .map(|segment| match segment {
+                PathSegment::MoveTo(x) => PathSegment::MoveTo(transform.apply(x)),
+                PathSegment::LineTo(x) => PathSegment::LineTo(transform.apply(x)),
+                PathSegment::CubicTo {
+                    control_1,
+                    control_2,
+                    to,
+                } => PathSegment::CubicTo {
+                    control_1: transform.apply(control_1),
+                    control_2: transform.apply(control_2),
+                    to: transform.apply(to),
+                },
+                PathSegment::Arc {
+                    center,
+                    radii,
+                    start_angle,
+                    end_angle,
+                } => PathSegment::Arc {
+                    center: transform.apply(center),
+                    radii: transform.apply_size(radii),
+                    start_angle: *start_angle,
+                    end_angle: *end_angle,
+                },
+                PathSegment::Close => PathSegment::Close,
+            })
+            .collect();
+        return Self { segments };
+    }
+
+    /// Approximates the path with straight lines, returning the points of
+    /// each subpath and whether it was closed
+    pub fn flatten(&self, tolerance: f64) -> Vec<(Vec<Position>, bool)> {
+        let mut subpaths: Vec<(Vec<Position>, bool)> = vec![];
+        let mut current: Vec<Position> = vec![];
+
+        for segment in &self.segments {
+            match segment {
+                PathSegment::MoveTo(position) => {
+                    if current.len() > 1 {
+                        subpaths.push((current, false));
+                    }
+                    current = vec![position.clone()];
+                }
+                PathSegment::LineTo(position) => {
+                    current.push(position.clone());
+                }
+                PathSegment::CubicTo {
+                    control_1,
+                    control_2,
+                    to,
+                } => {
+                    let from = match current.last() {
+                        Some(x) => x.clone(),
+                        None => control_1.clone(),
+                    };
+                    flatten_cubic(&from, control_1, control_2, to, tolerance, &mut current);
+                }
+                PathSegment::Arc {
+                    center,
+                    radii,
+                    start_angle,
+                    end_angle,
+                } => {
+                    flatten_arc(
+                        center,
+                        radii,
+                        *start_angle,
+                        *end_angle,
+                        tolerance,
+                        &mut current,
+                    );
+                }
+                PathSegment::Close => {
+                    if !current.is_empty() {
+                        let start = current[0].clone();
+                        subpaths.push((current, true));
+                        // Anything drawn after a close continues from the
+                        // start of the closed subpath
+                        current = vec![start];
+                    }
+                }
+            }
+        }
+        if current.len() > 1 {
+            subpaths.push((current, false));
+        }
+        return subpaths;
+    }
+}
+
+impl Default for Path {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+fn flatten_cubic(
+    from: &Position,
+    control_1: &Position,
+    control_2: &Position,
+    to: &Position,
+    tolerance: f64,
+    points: &mut Vec<Position>,
+) {
+    // Wang's formula for the number of line segments needed
+    let dd = f64::max(
+        ((from.x - 2. * control_1.x + control_2.x).powi(2)
+            + (from.y - 2. * control_1.y + control_2.y).powi(2))
+        .sqrt(),
+        ((control_1.x - 2. * control_2.x + to.x).powi(2)
+            + (control_1.y - 2. * control_2.y + to.y).powi(2))
+        .sqrt(),
+    );
+    let steps = ((0.75 * dd / tolerance).sqrt().ceil() as usize).clamp(1, 1000);
+    for i in 1..=steps {
+        let t = i as f64 / steps as f64;
+        let u = 1. - t;
+        let a = u * u * u;
+        let b = 3. * u * u * t;
+        let c = 3. * u * t * t;
+        let d = t * t * t;
+        points.push(Position {
+            x: a * from.x + b * control_1.x + c * control_2.x + d * to.x,
+            y: a * from.y + b * control_1.y + c * control_2.y + d * to.y,
+        });
+    }
+}
+
+fn flatten_arc(
+    center: &Position,
+    radii: &Size,
+    start_angle: f64,
+    end_angle: f64,
+    tolerance: f64,
+    points: &mut Vec<Position>,
+) {
+    let sweep = (end_angle - start_angle).clamp(-2. * PI, 2. * PI);
+    let radius = radii.width.max(radii.height);
+    let max_step = if radius > tolerance {
+        2. * (1. - tolerance / radius).acos()
+    } else {
+        PI / 2.
+    };
+    let steps = ((sweep.abs() / max_step).ceil() as usize).clamp(1, 1000);
+    for i in 0..=steps {
+        let angle = start_angle + sweep * i as f64 / steps as f64;
+        points.push(Position {
+            x: center.x + radii.width * angle.cos(),
+            y: center.y + radii.height * angle.sin(),
+        });
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineCap {
+    /// The stroke stops exactly at the end points
+    Butt,
+    Round,
+    /// The stroke extends past the end points by half its width
+    Square,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineJoin {
+    /// Falls back to `Bevel` where the corner is sharper than `MITER_LIMIT`
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// The longest a miter may be, as a multiple of half the line width
+pub const MITER_LIMIT: f64 = 4.;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f64,
+    pub color: Color,
+    pub cap: LineCap,
+    pub join: LineJoin,
+}
+
+impl StrokeStyle {
+    pub fn new(width: f64, color: Color) -> Self {
+        return Self {
+            width,
+            color,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+        };
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        return self;
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        return self;
+    }
+}
@@ -0,0 +1,279 @@
+use super::{
+    bitmap::Bitmap,
+    display_list::{DisplayList, DrawOp},
+    gradient::LinearGradient,
+    path::{Path, StrokeStyle},
+    tessellation::{for_each_path_fill_rect, for_each_path_stroke_rect},
+    text::{for_each_bitmap_text_rect, TextStyle},
+    Color, GraphicsLibrary, Position, Rect, Size, Transform,
+};
+
+/// Something which can fill axis-aligned rects given in screen coordinates.
+/// The rects passed to it have already been transformed and clipped.
+pub trait RasterTarget {
+    fn fill_rect(&mut self, rect: &Rect, color: &Color);
+
+    /// Called whenever the clip changes, with the new clip rect already
+    /// intersected with the enclosing ones. Targets which draw everything
+    /// through `fill_rect` can ignore this, but ones which draw paths or text
+    /// natively need it to clip them.
+    fn push_clip(&mut self, _rect: &Rect) {}
+
+    fn pop_clip(&mut self) {}
+
+    /// Fills a path given in screen coordinates, clipped to `clip`. By
+    /// default the path is tessellated into rects in software.
+    fn fill_path(&mut self, path: &Path, color: &Color, clip: &Rect) {
+        for_each_path_fill_rect(path, clip, |rect| self.fill_rect(&rect, color));
+    }
+
+    /// By default each span of the tessellated path is filled one pixel at a
+    /// time, with the colour at that pixel's centre
+    fn fill_path_gradient(&mut self, path: &Path, gradient: &LinearGradient, clip: &Rect) {
+        for_each_path_fill_rect(path, clip, |rect| {
+            let mut x = rect.position.x;
+            while x < rect.right() {
+                let width = (x.floor() + 1.).min(rect.right()) - x;
+                let color = gradient.color_at(&Position {
+                    x: x + width / 2.,
+                    y: rect.position.y + rect.size.height / 2.,
+                });
+                self.fill_rect(
+                    &Rect::new(
+                        Position {
+                            x,
+                            y: rect.position.y,
+                        },
+                        Size {
+                            width,
+                            height: rect.size.height,
+                        },
+                    ),
+                    &color,
+                );
+                x += width;
+            }
+        });
+    }
+
+    fn stroke_path(&mut self, path: &Path, style: &StrokeStyle, clip: &Rect) {
+        for_each_path_stroke_rect(path, style, clip, |rect| {
+            self.fill_rect(&rect, &style.color)
+        });
+    }
+
+    /// Draws `bitmap` stretched over `rect`, clipped to `clip`. By default
+    /// it's sampled in software with no smoothing.
+    fn draw_bitmap(&mut self, bitmap: &Bitmap, rect: &Rect, clip: &Rect) {
+        for_each_bitmap_rect(bitmap, rect, clip, |rect, color| {
+            self.fill_rect(&rect, color)
+        });
+    }
+
+    /// Draws text with its top-left corner at `position`, clipped to `clip`.
+    /// By default the text is drawn with the embedded bitmap font.
+    fn fill_text(&mut self, position: &Position, text: &str, style: &TextStyle, clip: &Rect) {
+        for_each_bitmap_text_rect(position, text, style, |rect| {
+            let visible = rect.intersection(clip);
+            if !visible.is_empty() {
+                self.fill_rect(&visible, &style.color);
+            }
+        });
+    }
+}
+
+/// Finds the pixel of `bitmap` under the centre of each screen pixel when
+/// it's stretched over `rect`, and passes runs of the same colour along each
+/// row to `fill_rect`. Only the part inside `clip` is covered.
+pub fn for_each_bitmap_rect(
+    bitmap: &Bitmap,
+    rect: &Rect,
+    clip: &Rect,
+    mut fill_rect: impl FnMut(Rect, &Color),
+) {
+    let visible = rect.intersection(clip);
+    if visible.is_empty() || bitmap.width() == 0 || bitmap.height() == 0 {
+        return;
+    }
+    let source_index = |screen: f64, start: f64, length: f64, count: usize| {
+        return (((screen - start) / length * count as f64).floor().max(0.) as usize)
+            .min(count - 1);
+    };
+
+    let mut y = visible.position.y.floor();
+    while y < visible.bottom() {
+        let top = y.max(visible.position.y);
+        let bottom = (y + 1.).min(visible.bottom());
+        let source_y = source_index(
+            (top + bottom) / 2.,
+            rect.position.y,
+            rect.size.height,
+            bitmap.height(),
+        );
+
+        let mut run: Option<(f64, Color)> = None;
+        let mut x = visible.position.x.floor();
+        while x < visible.right() {
+            let left = x.max(visible.position.x);
+            let right = (x + 1.).min(visible.right());
+            let source_x = source_index(
+                (left + right) / 2.,
+                rect.position.x,
+                rect.size.width,
+                bitmap.width(),
+            );
+            let color = bitmap.get_pixel(source_x, source_y);
+            run = match run {
+                Some((start, run_color)) if run_color == color => Some((start, run_color)),
+                Some((start, run_color)) => {
+                    fill_rect(span(start, left, top, bottom), &run_color);
+                    Some((left, color))
+                }
+                None => Some((left, color)),
+            };
+            x += 1.;
+        }
+        if let Some((start, run_color)) = run {
+            fill_rect(span(start, visible.right(), top, bottom), &run_color);
+        }
+        y += 1.;
+    }
+}
+
+fn span(left: f64, right: f64, top: f64, bottom: f64) -> Rect {
+    return Rect::new(
+        Position { x: left, y: top },
+        Size {
+            width: right - left,
+            height: bottom - top,
+        },
+    );
+}
+
+/// Replays a display list onto a `RasterTarget`, resolving the transform and
+/// clip stacks in software. `screen` is the initial clip rect.
+pub fn rasterize(display_list: &DisplayList, screen: &Rect, target: &mut dyn RasterTarget) {
+    let mut transforms: Vec<Transform> = vec![Transform::identity()];
+    let mut clips: Vec<Rect> = vec![screen.clone()];
+
+    for op in &display_list.ops {
+        let transform = transforms.last().unwrap();
+        let clip = clips.last().unwrap();
+        match op {
+            DrawOp::Fill { color } => {
+                if !clip.is_empty() {
+                    target.fill_rect(clip, color);
+                }
+            }
+            DrawOp::FillRect { rect, color } => {
+                let visible = transform.apply_rect(rect).intersection(clip);
+                if !visible.is_empty() {
+                    target.fill_rect(&visible, color);
+                }
+            }
+            DrawOp::FillPath { path, color } => {
+                if !clip.is_empty() {
+                    target.fill_path(&path.transformed(transform), color, clip);
+                }
+            }
+            DrawOp::FillPathGradient { path, gradient } => {
+                if !clip.is_empty() {
+                    target.fill_path_gradient(
+                        &path.transformed(transform),
+                        &gradient.transformed(transform),
+                        clip,
+                    );
+                }
+            }
+            DrawOp::StrokePath { path, style } => {
+                if !clip.is_empty() {
+                    let style = StrokeStyle {
+                        width: transform.apply_length(style.width),
+                        ..style.clone()
+                    };
+                    target.stroke_path(&path.transformed(transform), &style, clip);
+                }
+            }
+            DrawOp::FillText {
+                position,
+                text,
+                style,
+            } => {
+                if !clip.is_empty() {
+                    let style = TextStyle {
+                        font_size: transform.apply_length(style.font_size),
+                        ..style.clone()
+                    };
+                    target.fill_text(&transform.apply(position), text, &style, clip);
+                }
+            }
+            DrawOp::DrawBitmap { bitmap, rect } => {
+                if !clip.is_empty() {
+                    target.draw_bitmap(bitmap, &transform.apply_rect(rect), clip);
+                }
+            }
+            DrawOp::PushClip { rect } => {
+                let new_clip = transform.apply_rect(rect).intersection(clip);
+                target.push_clip(&new_clip);
+                clips.push(new_clip);
+            }
+            DrawOp::PopClip => {
+                if clips.len() > 1 {
+                    clips.pop();
+                    target.pop_clip();
+                }
+            }
+            DrawOp::PushTransform {
+                transform: new_transform,
+            } => {
+                let combined = new_transform.then(transform);
+                transforms.push(combined);
+            }
+            DrawOp::PopTransform => {
+                if transforms.len() > 1 {
+                    transforms.pop();
+                }
+            }
+        }
+    }
+}
+
+pub struct GraphicsLibraryRasterTarget<'a, G: GraphicsLibrary + ?Sized> {
+    pub graphics: &'a G,
+}
+
+impl<'a, G: GraphicsLibrary + ?Sized> RasterTarget for GraphicsLibraryRasterTarget<'a, G> {
+    fn fill_rect(&mut self, rect: &Rect, color: &Color) {
+        self.graphics.fill_rect(&rect.position, &rect.size, color);
+    }
+
+    fn push_clip(&mut self, rect: &Rect) {
+        self.graphics.push_clip(rect);
+    }
+
+    fn pop_clip(&mut self) {
+        self.graphics.pop_clip();
+    }
+
+    // Paths and text are clipped by the clip pushed onto `graphics`
+    fn fill_path(&mut self, path: &Path, color: &Color, _clip: &Rect) {
+        self.graphics.fill_path(path, color);
+    }
+
+    fn fill_path_gradient(&mut self, path: &Path, gradient: &LinearGradient, _clip: &Rect) {
+        self.graphics.fill_path_gradient(path, gradient);
+    }
+
+    fn stroke_path(&mut self, path: &Path, style: &StrokeStyle, _clip: &Rect) {
+        self.graphics.stroke_path(path, style);
+    }
+
+    fn fill_text(&mut self, position: &Position, text: &str, style: &TextStyle, _clip: &Rect) {
+        self.graphics.fill_text(position, text, style);
+    }
+
+    fn draw_bitmap(&mut self, bitmap: &Bitmap, rect: &Rect, _clip: &Rect) {
+        self.graphics
+            .draw_bitmap(bitmap, &rect.position, &rect.size);
+    }
+}
@@ -0,0 +1,305 @@
+use super::{
+    path::{LineCap, LineJoin, Path, StrokeStyle, FLATTENING_TOLERANCE, MITER_LIMIT},
+    Position, Rect, Size,
+};
+
+/// Converts a path into the horizontal, one pixel tall spans which cover it,
+/// using the non-zero winding rule. Only rows inside `clip` are produced and
+/// every span is clipped to it.
+pub fn for_each_path_fill_rect(path: &Path, clip: &Rect, fill_rect: impl FnMut(Rect)) {
+    let polygons: Vec<Vec<Position>> = path
+        .flatten(FLATTENING_TOLERANCE)
+        .into_iter()
+        .map(|(points, _)| points)
+        .collect();
+    fill_polygons(&polygons, clip, fill_rect);
+}
+
+/// Converts the outline of a stroked path into spans, as with
+/// `for_each_path_fill_rect`
+pub fn for_each_path_stroke_rect(
+    path: &Path,
+    style: &StrokeStyle,
+    clip: &Rect,
+    fill_rect: impl FnMut(Rect),
+) {
+    let mut polygons: Vec<Vec<Position>> = vec![];
+    for (points, is_closed) in path.flatten(FLATTENING_TOLERANCE) {
+        stroke_polyline(&points, is_closed, style, &mut polygons);
+    }
+    // The pieces overlap, so they all need the same orientation for the
+    // non-zero rule to fill their union exactly once
+    for polygon in &mut polygons {
+        if signed_area(polygon) < 0. {
+            polygon.reverse();
+        }
+    }
+    fill_polygons(&polygons, clip, fill_rect);
+}
+
+fn fill_polygons(polygons: &[Vec<Position>], clip: &Rect, mut fill_rect: impl FnMut(Rect)) {
+    let mut edges: Vec<(Position, Position)> = vec![];
+    for polygon in polygons {
+        if polygon.len() < 2 {
+            continue;
+        }
+        for i in 0..polygon.len() {
+            let a = &polygon[i];
+            let b = &polygon[(i + 1) % polygon.len()];
+            if a.y != b.y {
+                edges.push((a.clone(), b.clone()));
+            }
+        }
+    }
+    if edges.is_empty() || clip.is_empty() {
+        return;
+    }
+
+    let min_y = edges
+        .iter()
+        .map(|(a, b)| a.y.min(b.y))
+        .fold(f64::INFINITY, f64::min)
+        .max(clip.position.y);
+    let max_y = edges
+        .iter()
+        .map(|(a, b)| a.y.max(b.y))
+        .fold(f64::NEG_INFINITY, f64::max)
+        .min(clip.bottom());
+
+    let mut crossings: Vec<(f64, i32)> = vec![];
+    let mut row = min_y.floor();
+    while row < max_y {
+        // Sample each row at its pixel centres
+        let y = row + 0.5;
+        crossings.clear();
+        for (a, b) in &edges {
+            let (top, bottom, winding) = if a.y < b.y { (a, b, 1) } else { (b, a, -1) };
+            if y >= top.y && y < bottom.y {
+                let t = (y - top.y) / (bottom.y - top.y);
+                crossings.push((top.x + t * (bottom.x - top.x), winding));
+            }
+        }
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut winding = 0;
+        let mut span_start = 0.;
+        for (x, direction) in &crossings {
+            let was_inside = winding != 0;
+            winding += direction;
+            let is_inside = winding != 0;
+            if !was_inside && is_inside {
+                span_start = *x;
+            } else if was_inside && !is_inside {
+                let span = Rect::new(
+                    Position {
+                        x: span_start.round(),
+                        y: row,
+                    },
+                    Size {
+                        width: x.round() - span_start.round(),
+                        height: 1.,
+                    },
+                )
+                .intersection(clip);
+                if !span.is_empty() {
+                    fill_rect(span);
+                }
+            }
+        }
+        row += 1.;
+    }
+}
+
+fn signed_area(polygon: &[Position]) -> f64 {
+    let mut area = 0.;
+    for i in 0..polygon.len() {
+        let a = &polygon[i];
+        let b = &polygon[(i + 1) % polygon.len()];
+        area += a.x * b.y - b.x * a.y;
+    }
+    return area / 2.;
+}
+
+fn direction(from: &Position, to: &Position) -> Position {
+    let dx = to.x - from.x;
+    let dy = to.y - from.y;
+    let length = (dx * dx + dy * dy).sqrt();
+    return Position {
+        x: dx / length,
+        y: dy / length,
+    };
+}
+
+fn offset(point: &Position, direction: &Position, distance: f64) -> Position {
+    return Position {
+        x: point.x + direction.x * distance,
+        y: point.y + direction.y * distance,
+    };
+}
+
+fn normal(direction: &Position) -> Position {
+    return Position {
+        x: -direction.y,
+        y: direction.x,
+    };
+}
+
+fn circle_polygon(center: &Position, radius: f64) -> Vec<Position> {
+    let mut points: Vec<Position> = vec![];
+    Path::circle(center.clone(), radius)
+        .flatten(FLATTENING_TOLERANCE)
+        .into_iter()
+        .for_each(|(x, _)| points.extend(x));
+    return points;
+}
+
+/// Breaks the stroke of a polyline into overlapping polygons: one quad per
+/// segment, plus one polygon per join and cap
+fn stroke_polyline(
+    points: &[Position],
+    is_closed: bool,
+    style: &StrokeStyle,
+    polygons: &mut Vec<Vec<Position>>,
+) {
+    let half_width = style.width / 2.;
+    let mut points: Vec<Position> = points.to_vec();
+    points.dedup();
+    if is_closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.len() < 2 || half_width <= 0. {
+        return;
+    }
+
+    let segment_count = if is_closed {
+        points.len()
+    } else {
+        points.len() - 1
+    };
+    let directions: Vec<Position> = (0..segment_count)
+        .map(|i| direction(&points[i], &points[(i + 1) % points.len()]))
+        .collect();
+
+    for (i, d) in directions.iter().enumerate() {
+        let n = normal(d);
+        let a = &points[i];
+        let b = &points[(i + 1) % points.len()];
+        polygons.push(vec![
+            offset(a, &n, half_width),
+            offset(b, &n, half_width),
+            offset(b, &n, -half_width),
+            offset(a, &n, -half_width),
+        ]);
+    }
+
+    let join_count = if is_closed {
+        segment_count
+    } else {
+        segment_count - 1
+    };
+    for i in 0..join_count {
+        let incoming = &directions[i];
+        let outgoing = &directions[(i + 1) % segment_count];
+        let vertex = &points[(i + 1) % points.len()];
+        stroke_join(
+            vertex,
+            incoming,
+            outgoing,
+            half_width,
+            &style.join,
+            polygons,
+        );
+    }
+
+    if !is_closed {
+        let last = points.len() - 1;
+        stroke_cap(
+            &points[0],
+            &directions[0],
+            -1.,
+            half_width,
+            &style.cap,
+            polygons,
+        );
+        stroke_cap(
+            &points[last],
+            &directions[segment_count - 1],
+            1.,
+            half_width,
+            &style.cap,
+            polygons,
+        );
+    }
+}
+
+fn stroke_join(
+    vertex: &Position,
+    incoming: &Position,
+    outgoing: &Position,
+    half_width: f64,
+    join: &LineJoin,
+    polygons: &mut Vec<Vec<Position>>,
+) {
+    if let LineJoin::Round = join {
+        polygons.push(circle_polygon(vertex, half_width));
+        return;
+    }
+    let cross = incoming.x * outgoing.y - incoming.y * outgoing.x;
+    if cross.abs() < 1e-9 {
+        return;
+    }
+
+    // The gap to fill is on the outside of the turn
+    let side = if cross > 0. { -1. } else { 1. };
+    let n0 = normal(incoming);
+    let n1 = normal(outgoing);
+    let outer_0 = offset(vertex, &n0, side * half_width);
+    let outer_1 = offset(vertex, &n1, side * half_width);
+
+    let cos_half_angle = ((1. + n0.x * n1.x + n0.y * n1.y) / 2.).sqrt();
+    if let LineJoin::Miter = join {
+        if cos_half_angle > 1. / MITER_LIMIT {
+            let bisector = Position {
+                x: n0.x + n1.x,
+                y: n0.y + n1.y,
+            };
+            let length = (bisector.x * bisector.x + bisector.y * bisector.y).sqrt();
+            let tip = offset(
+                vertex,
+                &Position {
+                    x: bisector.x / length,
+                    y: bisector.y / length,
+                },
+                side * half_width / cos_half_angle,
+            );
+            polygons.push(vec![vertex.clone(), outer_0, tip, outer_1]);
+            return;
+        }
+    }
+    polygons.push(vec![vertex.clone(), outer_0, outer_1]);
+}
+
+/// `sign` is -1 for the start of the line and 1 for its end
+fn stroke_cap(
+    end: &Position,
+    direction: &Position,
+    sign: f64,
+    half_width: f64,
+    cap: &LineCap,
+    polygons: &mut Vec<Vec<Position>>,
+) {
+    match cap {
+        LineCap::Butt => (),
+        LineCap::Round => polygons.push(circle_polygon(end, half_width)),
+        LineCap::Square => {
+            let n = normal(direction);
+            let extended = offset(end, direction, sign * half_width);
+            polygons.push(vec![
+                offset(end, &n, half_width),
+                offset(&extended, &n, half_width),
+                offset(&extended, &n, -half_width),
+                offset(end, &n, -half_width),
+            ]);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use font8x8::{UnicodeFonts, BASIC_FONTS, BOX_FONTS, GREEK_FONTS, LATIN_FONTS};
+
+use super::{Color, Position, Rect, Size};
+
+pub const DEFAULT_FONT_FAMILY: &str = "sans-serif";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextStyle {
+    pub font_family: String,
+    /// The height of a line of text in pixels
+    pub font_size: f64,
+    pub color: Color,
+}
+
+impl TextStyle {
+    pub fn new(font_size: f64, color: Color) -> Self {
+        return Self {
+            font_family: DEFAULT_FONT_FAMILY.into(),
+            font_size,
+            color,
+        };
+    }
+
+    pub fn with_font_family(mut self, font_family: &str) -> Self {
+        self.font_family = font_family.into();
+        return self;
+    }
+}
+
+/// The extent of a piece of text when drawn with its top-left corner at the
+/// origin. `baseline` is the distance from the top down to the baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextMetrics {
+    pub width: f64,
+    pub height: f64,
+    pub baseline: f64,
+}
+
+impl TextMetrics {
+    pub fn size(&self) -> Size {
+        return Size {
+            width: self.width,
+            height: self.height,
+        };
+    }
+}
+
+// The embedded font is an 8x8 pixel monospace bitmap font, scaled up so that
+// one glyph cell is `font_size` pixels square. It is used by the backends
+// which rasterise in software, which is also why it ignores the font family.
+const GLYPH_CELL_SIZE: usize = 8;
+const GLYPH_BASELINE_ROW: usize = 7;
+
+fn glyph(character: char) -> [u8; 8] {
+    return BASIC_FONTS
+        .get(character)
+        .or_else(|| LATIN_FONTS.get(character))
+        .or_else(|| GREEK_FONTS.get(character))
+        .or_else(|| BOX_FONTS.get(character))
+        .or_else(|| BASIC_FONTS.get('?'))
+        .unwrap();
+}
+
+/// Measures text as drawn by the embedded bitmap font
+pub fn measure_bitmap_text(text: &str, style: &TextStyle) -> TextMetrics {
+    let scale = style.font_size / GLYPH_CELL_SIZE as f64;
+    return TextMetrics {
+        width: text.chars().count() as f64 * style.font_size,
+        height: style.font_size,
+        baseline: GLYPH_BASELINE_ROW as f64 * scale,
+    };
+}
+
+/// Calls `fill_rect` with the rects which make up `text` as drawn by the
+/// embedded bitmap font with its top-left corner at `position`. Runs of set
+/// pixels within a row are merged into a single rect.
+pub fn for_each_bitmap_text_rect(
+    position: &Position,
+    text: &str,
+    style: &TextStyle,
+    mut fill_rect: impl FnMut(Rect),
+) {
+    let scale = style.font_size / GLYPH_CELL_SIZE as f64;
+    for (i, character) in text.chars().enumerate() {
+        let glyph_x = position.x + i as f64 * style.font_size;
+        for (row, bits) in glyph(character).iter().enumerate() {
+            let mut column = 0;
+            while column < GLYPH_CELL_SIZE {
+                if bits & (1 << column) == 0 {
+                    column += 1;
+                    continue;
+                }
+                let run_start = column;
+                while column < GLYPH_CELL_SIZE && bits & (1 << column) != 0 {
+                    column += 1;
+                }
+                fill_rect(Rect::new(
+                    Position {
+                        x: glyph_x + run_start as f64 * scale,
+                        y: position.y + row as f64 * scale,
+                    },
+                    Size {
+                        width: (column - run_start) as f64 * scale,
+                        height: scale,
+                    },
+                ));
+            }
+        }
+    }
+}
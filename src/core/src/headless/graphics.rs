@@ -0,0 +1,251 @@
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+use crate::graphics::{
+    bitmap::Bitmap,
+    display_list::DisplayList,
+    gradient::LinearGradient,
+    path::{Path as GraphicsPath, StrokeStyle},
+    raster::{rasterize, RasterTarget},
+    text::{measure_bitmap_text, TextMetrics, TextStyle},
+    Color, GraphicsLibrary, Position, Rect, Size,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        return Self {
+            width,
+            height,
+            pixels: vec![0; width * height * 3],
+        };
+    }
+
+    pub fn width(&self) -> usize {
+        return self.width;
+    }
+
+    pub fn height(&self) -> usize {
+        return self.height;
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        return &self.pixels;
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> Color {
+        let i = (y * self.width + x) * 3;
+        return Color::rgb(self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]);
+    }
+
+    /// Overwrites the pixel, ignoring the colour's alpha
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: &Color) {
+        let i = (y * self.width + x) * 3;
+        self.pixels[i] = color.r;
+        self.pixels[i + 1] = color.g;
+        self.pixels[i + 2] = color.b;
+    }
+
+    /// Composites `color` over the rect with the source-over operator
+    pub fn fill_rect(&mut self, position: &Position, size: &Size, color: &Color) {
+        // Round the edges rather than the origin and size separately so that
+        // rects which share an edge never leave a gap or overlap
+        let x0 = Self::clamp_edge(position.x, self.width);
+        let y0 = Self::clamp_edge(position.y, self.height);
+        let x1 = Self::clamp_edge(position.x + size.width, self.width);
+        let y1 = Self::clamp_edge(position.y + size.height, self.height);
+        if color.a == 0 {
+            return;
+        }
+        for y in y0..y1 {
+            for x in x0..x1 {
+                if color.is_opaque() {
+                    self.set_pixel(x, y, color);
+                } else {
+                    let blended = color.blend_over(&self.get_pixel(x, y));
+                    self.set_pixel(x, y, &blended);
+                }
+            }
+        }
+    }
+
+    pub fn save_png(&self, path: &Path) -> std::io::Result<()> {
+        let file = File::create(path)?;
+        let mut encoder =
+            png::Encoder::new(BufWriter::new(file), self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        return Ok(());
+    }
+
+    /// Loads an 8-bit RGB or RGBA PNG, discarding the alpha channel
+    pub fn load_png(path: &Path) -> std::io::Result<Self> {
+        let mut decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        let channels = info.color_type.samples();
+        let mut framebuffer = Self::new(info.width as usize, info.height as usize);
+        for (i, pixel) in buffer[..info.buffer_size()]
+            .chunks_exact(channels)
+            .enumerate()
+        {
+            let color = match channels {
+                1 | 2 => Color::rgb(pixel[0], pixel[0], pixel[0]),
+                _ => Color::rgb(pixel[0], pixel[1], pixel[2]),
+            };
+            framebuffer.set_pixel(i % framebuffer.width, i / framebuffer.width, &color);
+        }
+        return Ok(framebuffer);
+    }
+
+    fn clamp_edge(edge: f64, limit: usize) -> usize {
+        return edge.round().max(0.).min(limit as f64) as usize;
+    }
+}
+
+impl RasterTarget for Framebuffer {
+    fn fill_rect(&mut self, rect: &Rect, color: &Color) {
+        Framebuffer::fill_rect(self, &rect.position, &rect.size, color);
+    }
+}
+
+pub struct HeadlessGraphicsLibrary {
+    framebuffer: RefCell<Framebuffer>,
+    frame_count: RefCell<usize>,
+    clips: RefCell<Vec<Rect>>,
+}
+
+impl HeadlessGraphicsLibrary {
+    pub fn new(screen_size: Size) -> Self {
+        return Self {
+            framebuffer: RefCell::new(Framebuffer::new(
+                screen_size.width as usize,
+                screen_size.height as usize,
+            )),
+            frame_count: RefCell::new(0),
+            clips: RefCell::new(vec![]),
+        };
+    }
+
+    /// Simulates the window being resized. The framebuffer is cleared, just
+    /// like the SDL2 texture is recreated on resize, so callers should follow
+    /// this with `AppRunner::on_window_resize`.
+    pub fn set_screen_dimensions(&self, screen_size: Size) {
+        *self.framebuffer.borrow_mut() =
+            Framebuffer::new(screen_size.width as usize, screen_size.height as usize);
+    }
+
+    pub fn framebuffer(&self) -> Framebuffer {
+        return self.framebuffer.borrow().clone();
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> Color {
+        return self.framebuffer.borrow().get_pixel(x, y);
+    }
+
+    /// The number of times `update` has been called, i.e. the number of
+    /// frames which would have been presented on a real display.
+    pub fn frame_count(&self) -> usize {
+        return *self.frame_count.borrow();
+    }
+
+    pub fn save_png(&self, path: &Path) -> std::io::Result<()> {
+        return self.framebuffer.borrow().save_png(path);
+    }
+
+    fn current_clip(&self) -> Rect {
+        return match self.clips.borrow().last() {
+            Some(x) => x.clone(),
+            None => Rect::from_size(self.get_screen_dimensions()),
+        };
+    }
+}
+
+impl GraphicsLibrary for HeadlessGraphicsLibrary {
+    fn get_screen_dimensions(&self) -> Size {
+        let framebuffer = self.framebuffer.borrow();
+        return Size {
+            width: framebuffer.width() as f64,
+            height: framebuffer.height() as f64,
+        };
+    }
+
+    fn fill_rect(&self, position: &Position, size: &Size, color: &Color) {
+        let visible = Rect::new(position.clone(), size.clone()).intersection(&self.current_clip());
+        if !visible.is_empty() {
+            self.framebuffer
+                .borrow_mut()
+                .fill_rect(&visible.position, &visible.size, color);
+        }
+    }
+
+    fn fill(&self, color: &Color) {
+        self.fill_rect(&Position::origin(), &self.get_screen_dimensions(), color);
+    }
+
+    fn fill_path(&self, path: &GraphicsPath, color: &Color) {
+        let mut display_list = DisplayList::new();
+        display_list.fill_path(path, color);
+        self.draw_display_list(&display_list);
+    }
+
+    fn fill_path_gradient(&self, path: &GraphicsPath, gradient: &LinearGradient) {
+        let mut display_list = DisplayList::new();
+        display_list.fill_path_gradient(path, gradient);
+        self.draw_display_list(&display_list);
+    }
+
+    fn stroke_path(&self, path: &GraphicsPath, style: &StrokeStyle) {
+        let mut display_list = DisplayList::new();
+        display_list.stroke_path(path, style);
+        self.draw_display_list(&display_list);
+    }
+
+    fn fill_text(&self, position: &Position, text: &str, style: &TextStyle) {
+        let mut display_list = DisplayList::new();
+        display_list.fill_text(position, text, style);
+        self.draw_display_list(&display_list);
+    }
+
+    fn measure_text(&self, text: &str, style: &TextStyle) -> TextMetrics {
+        return measure_bitmap_text(text, style);
+    }
+
+    fn draw_bitmap(&self, bitmap: &Bitmap, position: &Position, size: &Size) {
+        let mut display_list = DisplayList::new();
+        display_list.draw_bitmap(bitmap, position, size);
+        self.draw_display_list(&display_list);
+    }
+
+    fn push_clip(&self, rect: &Rect) {
+        let clip = rect.intersection(&self.current_clip());
+        self.clips.borrow_mut().push(clip);
+    }
+
+    fn pop_clip(&self) {
+        self.clips.borrow_mut().pop();
+    }
+
+    fn draw_display_list(&self, display_list: &DisplayList) {
+        let clip = self.current_clip();
+        rasterize(display_list, &clip, &mut *self.framebuffer.borrow_mut());
+    }
+
+    fn update(&self) {
+        *self.frame_count.borrow_mut() += 1;
+    }
+}
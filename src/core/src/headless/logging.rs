@@ -0,0 +1,35 @@
+use std::cell::RefCell;
+
+use crate::logging::Logger;
+
+pub struct HeadlessLogger {
+    messages: RefCell<Vec<String>>,
+}
+
+impl HeadlessLogger {
+    pub fn new() -> Self {
+        return Self {
+            messages: RefCell::new(vec![]),
+        };
+    }
+
+    pub fn messages(&self) -> Vec<String> {
+        return self.messages.borrow().clone();
+    }
+
+    pub fn clear(&self) {
+        self.messages.borrow_mut().clear();
+    }
+}
+
+impl Default for HeadlessLogger {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl Logger for HeadlessLogger {
+    fn log(&self, msg: &str) {
+        self.messages.borrow_mut().push(msg.to_string());
+    }
+}
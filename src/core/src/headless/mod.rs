@@ -0,0 +1,36 @@
+pub mod graphics;
+pub mod logging;
+pub mod snapshot;
+
+use crate::graphics::{GraphicsLibrary, Size};
+use crate::logging::Logger;
+use crate::platform::Platform;
+
+use graphics::HeadlessGraphicsLibrary;
+use logging::HeadlessLogger;
+
+/// A `Platform` which needs neither a window nor a browser. Drawing is
+/// rasterised into an in-memory RGB framebuffer and log messages are kept in
+/// memory, so layouts and event handling can be checked deterministically.
+pub struct HeadlessPlatform {
+    pub graphics: HeadlessGraphicsLibrary,
+    pub logger: HeadlessLogger,
+}
+
+impl HeadlessPlatform {
+    pub fn new(screen_size: Size) -> Self {
+        return Self {
+            graphics: HeadlessGraphicsLibrary::new(screen_size),
+            logger: HeadlessLogger::new(),
+        };
+    }
+}
+
+impl Platform for HeadlessPlatform {
+    fn graphics(&self) -> &dyn GraphicsLibrary {
+        return &self.graphics;
+    }
+    fn logger(&self) -> &dyn Logger {
+        return &self.logger;
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    cell::RefCell,
+    env,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{
+    graphics::{display_list::DisplayList, Color, GraphicsLibrary, Position, Size},
+    state::StateManager,
+    ui_library::{constraints::BoxConstraints, Widget},
+};
+
+use super::{graphics::Framebuffer, HeadlessPlatform};
+
+/// Set this environment variable to overwrite the reference images with the
+/// current output instead of comparing against them.
+pub const UPDATE_SNAPSHOTS_ENV_VAR: &str = "CASSEROLE_UPDATE_SNAPSHOTS";
+
+/// Builds, lays out and draws `widget` on a blank screen of the given size
+/// and returns what ends up in the framebuffer.
+pub fn render_widget(mut widget: Box<dyn Widget>, screen_size: Size) -> Framebuffer {
+    let platform = HeadlessPlatform::new(screen_size.clone());
+    let state_manager = Rc::new(RefCell::new(StateManager::new()));
+
+    widget.rebuild_with_key("".into(), Rc::downgrade(&state_manager));
+    widget.set_layout(&BoxConstraints::tight(&screen_size), &platform);
+    let mut display_list = DisplayList::new();
+    widget.draw(Position::origin(), &mut display_list);
+    platform.graphics.draw_display_list(&display_list);
+
+    return platform.graphics.framebuffer();
+}
+
+/// Renders `widget` and compares the result against the PNG at
+/// `reference_path`, panicking if they differ.
+///
+/// On a mismatch the rendered image is written next to the reference as
+/// `<name>.actual.png`, along with `<name>.diff.png` in which matching pixels
+/// are faded out and differing pixels are highlighted in magenta.
+pub fn assert_snapshot(widget: Box<dyn Widget>, screen_size: Size, reference_path: &Path) {
+    let actual = render_widget(widget, screen_size);
+
+    if env::var_os(UPDATE_SNAPSHOTS_ENV_VAR).is_some() {
+        actual.save_png(reference_path).unwrap();
+        return;
+    }
+
+    let actual_path = sibling_path(reference_path, "actual");
+    let expected = match Framebuffer::load_png(reference_path) {
+        Ok(x) => x,
+        Err(e) => {
+            actual.save_png(&actual_path).unwrap();
+            panic!(
+                "Could not load reference image {}: {}. The rendered image was written to {}; \
+                 run with {} set to accept it.",
+                reference_path.display(),
+                e,
+                actual_path.display(),
+                UPDATE_SNAPSHOTS_ENV_VAR
+            );
+        }
+    };
+
+    if expected.width() != actual.width() || expected.height() != actual.height() {
+        actual.save_png(&actual_path).unwrap();
+        panic!(
+            "Snapshot {} is {}x{} but the rendered image is {}x{}. The rendered image was written to {}",
+            reference_path.display(),
+            expected.width(),
+            expected.height(),
+            actual.width(),
+            actual.height(),
+            actual_path.display()
+        );
+    }
+
+    let (mismatched_pixels, diff) = diff_framebuffers(&expected, &actual);
+    if mismatched_pixels > 0 {
+        let diff_path = sibling_path(reference_path, "diff");
+        actual.save_png(&actual_path).unwrap();
+        diff.save_png(&diff_path).unwrap();
+        panic!(
+            "Snapshot {} differs from the rendered image in {} pixels. See {} and {}",
+            reference_path.display(),
+            mismatched_pixels,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+fn diff_framebuffers(expected: &Framebuffer, actual: &Framebuffer) -> (usize, Framebuffer) {
+    let highlight = Color::rgb(255, 0, 255);
+    let mut mismatched_pixels = 0;
+    let mut diff = Framebuffer::new(expected.width(), expected.height());
+    for y in 0..expected.height() {
+        for x in 0..expected.width() {
+            let expected_pixel = expected.get_pixel(x, y);
+            if expected_pixel == actual.get_pixel(x, y) {
+                let luma =
+                    ((expected_pixel.r as u32 + expected_pixel.g as u32 + expected_pixel.b as u32)
+                        / 3) as u8;
+                let faded = 192 + luma / 4;
+                diff.set_pixel(x, y, &Color::rgb(faded, faded, faded));
+            } else {
+                mismatched_pixels += 1;
+                diff.set_pixel(x, y, &highlight);
+            }
+        }
+    }
+    return (mismatched_pixels, diff);
+}
+
+fn sibling_path(reference_path: &Path, suffix: &str) -> PathBuf {
+    let stem = reference_path
+        .file_stem()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or_default();
+    return reference_path.with_file_name(format!("{}.{}.png", stem, suffix));
+}
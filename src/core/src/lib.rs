@@ -1,5 +1,7 @@
 pub mod event_handlers;
+pub mod focus;
 pub mod graphics;
+pub mod headless;
 pub mod logging;
 pub mod platform;
 pub mod state;
@@ -8,13 +10,17 @@ pub mod ui_library;
 use std::cell::RefCell;
 use std::rc::{Rc, Weak};
 
-use graphics::{Color, Position, Size};
+use event_handlers::PointerState;
+use focus::FocusManager;
+use graphics::{display_list::DisplayList, Position, Rect, Size};
 use state::StateManager;
+use ui_library::constraints::BoxConstraints;
 use ui_library::compound::button::Button;
 use ui_library::container::Container;
 use ui_library::expanded::Expanded;
 use ui_library::padding::{Inset, Padding};
-use ui_library::sized_box::SizedBox;
+use ui_library::text::Text;
+use ui_library::theme::Theme;
 use ui_library::{CompoundWidget, CompoundWidgetData, Key, Widget};
 
 use key_segment::KeySegment;
@@ -40,6 +46,16 @@ impl CompoundWidget for App {
     compound_widget_default_methods!();
 
     fn build(&self) -> Box<dyn Widget> {
+        let theme = Theme::of(self);
+        let is_dark = *theme == Theme::dark();
+        let theme_button = Button::label(if is_dark { "Light theme" } else { "Dark theme" })
+            .with_on_pressed(Box::new(move |_key, handled_event_info| {
+                handled_event_info.set_theme(if is_dark {
+                    Theme::light()
+                } else {
+                    Theme::dark()
+                });
+            }));
         return List::new(
             ListDirection::Column,
             MainAxisAlignment::Start,
@@ -48,29 +64,20 @@ impl CompoundWidget for App {
             CrossAxisSize::Max,
             vec![
                 Container::new(
-                    Color { r: 255, g: 0, b: 0 },
+                    theme.palette.surface.clone(),
                     Padding::new(
-                        Inset::symmetric(5., 20.),
+                        Inset::symmetric(theme.spacing.small, theme.spacing.large),
                         Some(List::new(
                             ListDirection::Row,
                             MainAxisAlignment::SpaceBetween,
                             CrossAxisAlignment::Center,
                             MainAxisSize::Max,
                             CrossAxisSize::Min,
-                            vec![
-                                SizedBox::new(
-                                    Size {
-                                        width: 100.,
-                                        height: 20.,
-                                    },
-                                    Container::new(Color { r: 0, g: 0, b: 255 }, Expanded::new()),
-                                ),
-                                Button::new(),
-                            ],
+                            vec![Text::new("Casserole", theme.title_style()), theme_button],
                         )),
                     ),
                 ),
-                Container::new(Color { r: 0, g: 255, b: 0 }, Expanded::new()),
+                Container::new(theme.palette.background.clone(), Expanded::new()),
             ],
         );
     }
@@ -83,9 +90,33 @@ where
     pub platform: PlatformType,
     pub app: AppType,
     pub state_manager: Rc<RefCell<StateManager>>,
+    pub pointer_state: PointerState,
+    pub focus_manager: FocusManager,
 }
 
 impl<PlatformType: Platform, AppType: Widget> AppRunner<PlatformType, AppType> {
+    /// The root of the tree provides `theme` until it's switched with
+    /// `set_theme`
+    pub fn new(platform: PlatformType, mut app: AppType, theme: Theme) -> Self {
+        let state_manager = Rc::new(RefCell::new(StateManager::new()));
+        state_manager
+            .borrow_mut()
+            .provide_context(Key::root(), Rc::new(theme));
+
+        app.rebuild_with_key("".into(), Rc::downgrade(&state_manager));
+        app.set_layout(
+            &BoxConstraints::tight(&platform.graphics().get_screen_dimensions()),
+            &platform,
+        );
+        return Self {
+            platform,
+            app,
+            state_manager,
+            pointer_state: PointerState::new(),
+            focus_manager: FocusManager::new(),
+        };
+    }
+
     pub fn draw_frame(&self) {
         let g = self.platform.graphics();
         let l = self.platform.logger();
@@ -95,25 +126,36 @@ impl<PlatformType: Platform, AppType: Widget> AppRunner<PlatformType, AppType> {
             screen_size.width, screen_size.height
         ));
 
-        self.app.draw(Position::origin(), &self.platform);
+        let mut display_list = DisplayList::new();
+        self.app.draw(Position::origin(), &mut display_list);
+        g.draw_display_list(&display_list);
+
+        g.update();
+    }
+
+    /// Repaints only `dirty_regions`, which are in screen coordinates. Each
+    /// separate region is clipped to on its own, and only the widgets which
+    /// draw within it are recorded. Falls back to a full frame if there are
+    /// none.
+    pub fn redraw_dirty_regions(&self, dirty_regions: &[Rect]) {
+        if dirty_regions.is_empty() {
+            return self.draw_frame();
+        }
+
+        let g = self.platform.graphics();
+        let mut display_list = DisplayList::new();
+        for region in Rect::merge_overlapping(dirty_regions) {
+            display_list.set_visible_region(Some(region.clone()));
+            display_list.push_clip(&region);
+            self.app.paint(Position::origin(), &mut display_list);
+            display_list.pop_clip();
+        }
+        g.draw_display_list(&display_list);
 
         g.update();
     }
 }
 
 pub fn entry_point<PlatformType: Platform>(platform: PlatformType) -> AppRunner<PlatformType, App> {
-    let state_manager = Rc::new(RefCell::new(StateManager::new()));
-
-    let mut app = App::new();
-    app.rebuild_with_key("".into(), Rc::downgrade(&state_manager));
-    app.set_layout(
-        Position::origin(),
-        platform.graphics().get_screen_dimensions(),
-    );
-    let app_runner = AppRunner {
-        platform,
-        app,
-        state_manager,
-    };
-    return app_runner;
+    return AppRunner::new(platform, App::new(), Theme::light());
 }
@@ -1,15 +1,26 @@
-use std::{any::Any, collections::HashMap};
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
 use crate::ui_library::Key;
 
 pub struct StateManager {
     states: HashMap<Key, Box<dyn Any>>,
+    /// The value each `Provider` holds, by the provider's key
+    contexts: HashMap<Key, Rc<dyn Any>>,
+    /// The widgets which looked each provider's value up, by the provider's
+    /// key
+    dependents: HashMap<Key, HashSet<Key>>,
 }
 
 impl StateManager {
     pub fn new() -> Self {
         return Self {
             states: HashMap::new(),
+            contexts: HashMap::new(),
+            dependents: HashMap::new(),
         };
     }
 
@@ -17,6 +28,102 @@ impl StateManager {
         let x = self.states.entry(key.clone()).or_insert_with(|| factory());
         return x.as_mut().downcast_mut::<T>().unwrap();
     }
+
+    /// Removes the state for `key` from the state manager, creating it with
+    /// `factory` if there isn't one yet, so that the state manager needn't
+    /// stay borrowed while the state is in use. It must be handed back with
+    /// `return_state` afterwards.
+    pub fn take_state<T: State>(&mut self, key: &Key, factory: &dyn Fn() -> Box<T>) -> Box<T> {
+        return match self.states.remove(key) {
+            Some(x) => x.downcast::<T>().unwrap(),
+            None => factory(),
+        };
+    }
+
+    pub fn return_state<T: State>(&mut self, key: &Key, state: Box<T>) {
+        self.states.insert(key.clone(), state);
+    }
+
+    /// Makes `value` available below the widget with key `provider`,
+    /// unless that widget already provides something
+    pub fn provide_context(&mut self, provider: Key, value: Rc<dyn Any>) {
+        self.contexts.entry(provider).or_insert(value);
+    }
+
+    /// Finds the value of type `T` provided nearest above (or at) `key`,
+    /// and remembers that the widget with `key` depends on it
+    pub fn lookup_context<T: 'static>(&mut self, key: &Key) -> Option<Rc<T>> {
+        let provider = self.find_provider::<T>(key)?;
+        let value = self.contexts[&provider].clone().downcast::<T>().ok()?;
+        self.dependents
+            .entry(provider)
+            .or_default()
+            .insert(key.clone());
+        return Some(value);
+    }
+
+    /// Replaces the value of type `T` provided nearest above (or at) `key`.
+    /// Returns the keys of the widgets which depend on it and so need
+    /// rebuilding, or nothing if there's no such provider.
+    pub fn update_context(&mut self, key: &Key, value: Rc<dyn Any>) -> Vec<Key> {
+        let provider = match self.find_provider_of(key, value.as_ref()) {
+            Some(x) => x,
+            None => return vec![],
+        };
+        self.contexts.insert(provider.clone(), value);
+        return match self.dependents.remove(&provider) {
+            Some(dependents) => dependents.into_iter().collect(),
+            None => vec![],
+        };
+    }
+
+    fn find_provider<T: 'static>(&self, key: &Key) -> Option<Key> {
+        return self.nearest_provider(key, |value| value.is::<T>());
+    }
+
+    fn find_provider_of(&self, key: &Key, value: &dyn Any) -> Option<Key> {
+        let type_id = value.type_id();
+        return self.nearest_provider(key, |provided| provided.type_id() == type_id);
+    }
+
+    /// Walks up from `key` to the root, so a lookup costs one probe per
+    /// level of the tree however many providers there are
+    fn nearest_provider(&self, key: &Key, matches: impl Fn(&dyn Any) -> bool) -> Option<Key> {
+        let mut candidate = key.clone();
+        loop {
+            if let Some(value) = self.contexts.get(&candidate) {
+                if matches(value.as_ref()) {
+                    return Some(candidate);
+                }
+            }
+            let parent_length = candidate.value.rfind('/')?;
+            candidate.value.truncate(parent_length);
+        }
+    }
+
+    /// Forgets that the widget with `key`, or anything below it, depends on
+    /// any provider, because it's about to be built again and will look up
+    /// whatever it still needs
+    pub fn forget_dependencies_from(&mut self, key: &Key) {
+        let is_forgotten = |x: &Key| x == key || key.is_ancestor_of(x);
+        self.dependents.retain(|_, dependents| {
+            dependents.retain(|x| !is_forgotten(x));
+            return !dependents.is_empty();
+        });
+    }
+
+    /// Forgets the state, provided values and dependencies of every widget
+    /// below the one with `key` whose key isn't in `live_keys`, i.e. which
+    /// was removed when it was rebuilt
+    pub fn forget_removed_below(&mut self, key: &Key, live_keys: &HashSet<Key>) {
+        let is_removed = |x: &Key| key.is_ancestor_of(x) && !live_keys.contains(x);
+        self.states.retain(|x, _| !is_removed(x));
+        self.contexts.retain(|x, _| !is_removed(x));
+        self.dependents.retain(|provider, dependents| {
+            dependents.retain(|x| !is_removed(x));
+            return !is_removed(provider) && !dependents.is_empty();
+        });
+    }
 }
 
 pub trait State: Any {}
@@ -0,0 +1,78 @@
+use std::{cell::RefCell, rc::Weak};
+
+use crate::{
+    graphics::{display_list::DisplayList, Position, Size},
+    platform::Platform,
+    state::StateManager,
+    widget_default_methods,
+};
+
+use super::{constraints::BoxConstraints, Alignment, Key, Widget, WidgetData};
+
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+/// Fills the space it's given, where it can, and places its child within it
+/// at `alignment`. Along an unbounded axis it just fits the child.
+#[derive(Debug, KeySegment)]
+pub struct Align {
+    widget_data: WidgetData,
+    alignment: Alignment,
+    child: Box<dyn Widget>,
+}
+
+impl Align {
+    pub fn new(alignment: Alignment, child: Box<dyn Widget>) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            alignment,
+            child,
+        });
+    }
+}
+
+impl Widget for Align {
+    widget_default_methods!();
+
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        let child_size = self.child.set_layout(&constraints.loosen(), platform);
+        let size = constraints.constrain(&Size {
+            width: if constraints.max_width.is_finite() {
+                constraints.max_width
+            } else {
+                child_size.width
+            },
+            height: if constraints.max_height.is_finite() {
+                constraints.max_height
+            } else {
+                child_size.height
+            },
+        });
+        self.child
+            .set_position(self.alignment.offset(&size, &child_size));
+        return size;
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        self.child.paint(
+            parent_position + self.widget_data.position.clone(),
+            display_list,
+        );
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        return vec![self.child.as_mut()];
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        return vec![self.child.as_ref()];
+    }
+}
+
+/// Shorthand for an `Align` which centres its child
+pub struct Center;
+
+impl Center {
+    pub fn new(child: Box<dyn Widget>) -> Box<Align> {
+        return Align::new(Alignment::center(), child);
+    }
+}
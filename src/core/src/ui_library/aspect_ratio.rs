@@ -0,0 +1,98 @@
+use std::{cell::RefCell, rc::Weak};
+
+use crate::{
+    graphics::{display_list::DisplayList, Position, Size},
+    platform::Platform,
+    state::StateManager,
+    widget_default_methods,
+};
+
+use super::{constraints::BoxConstraints, Key, Widget, WidgetData};
+
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+/// Sizes its child to the given ratio of width to height, as large as the
+/// constraints allow. It starts from the widest it can be and shrinks to fit
+/// the maximum height, so it needs a bound along at least one axis.
+#[derive(Debug, KeySegment)]
+pub struct AspectRatio {
+    widget_data: WidgetData,
+    aspect_ratio: f64,
+    child: Box<dyn Widget>,
+}
+
+impl AspectRatio {
+    pub fn new(aspect_ratio: f64, child: Box<dyn Widget>) -> Box<Self> {
+        assert!(
+            aspect_ratio > 0. && aspect_ratio.is_finite(),
+            "Aspect ratio must be positive, not {}",
+            aspect_ratio
+        );
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            aspect_ratio,
+            child,
+        });
+    }
+
+    fn get_size_for(&self, constraints: &BoxConstraints) -> Size {
+        if constraints.is_tight() {
+            return constraints.smallest();
+        }
+        let mut width = if constraints.max_width.is_finite() {
+            constraints.max_width
+        } else {
+            constraints.max_height * self.aspect_ratio
+        };
+        let mut height = width / self.aspect_ratio;
+        // Shrink to fit the maximums first, then grow to meet the minimums,
+        // keeping to the ratio for as long as possible
+        if width > constraints.max_width {
+            width = constraints.max_width;
+            height = width / self.aspect_ratio;
+        }
+        if height > constraints.max_height {
+            height = constraints.max_height;
+            width = height * self.aspect_ratio;
+        }
+        if width < constraints.min_width {
+            width = constraints.min_width;
+            height = width / self.aspect_ratio;
+        }
+        if height < constraints.min_height {
+            height = constraints.min_height;
+            width = height * self.aspect_ratio;
+        }
+        if !width.is_finite() || !height.is_finite() {
+            return constraints.smallest();
+        }
+        return constraints.constrain(&Size { width, height });
+    }
+}
+
+impl Widget for AspectRatio {
+    widget_default_methods!();
+
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        let size = self.get_size_for(constraints);
+        self.child
+            .set_layout(&BoxConstraints::tight(&size), platform);
+        self.child.set_position(Position::origin());
+        return size;
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        self.child.paint(
+            parent_position + self.widget_data.position.clone(),
+            display_list,
+        );
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        return vec![self.child.as_mut()];
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        return vec![self.child.as_ref()];
+    }
+}
@@ -1,61 +1,208 @@
-use std::{rc::Weak, cell::RefCell};
+use std::{cell::RefCell, rc::Rc, rc::Weak};
 
 use crate::{
-    graphics::{Color, Position, Size},
+    compound_widget_default_methods,
+    event_handlers::{HandledEventInfo, MouseButton, MouseButtonEvent, MouseButtonEventKind},
+    graphics::{Position, Size},
+    platform::Platform,
+    state::{State, StateManager},
     ui_library::{
-        container::Container, expanded::Expanded, sized_box::SizedBox, CompoundWidget, Widget, Key, CompoundWidgetData,
-    }, state::StateManager, widget_default_methods, compound_widget_default_methods,
+        constraints::BoxConstraints,
+        container::Container,
+        decoration::BoxDecoration,
+        padding::{Inset, Padding},
+        text::Text,
+        theme::Theme,
+        CompoundWidgetData, Key, StatefulWidget, StatefulWidgetEventHandlerInfo, Widget,
+    },
+    widget_default_methods,
 };
 
 use super::hoverable::Hoverable;
+use derivative::Derivative;
 use key_segment::KeySegment;
 use key_segment_derive::KeySegment;
 
+/// Called when the button is pressed. It's given the button's key so that it
+/// can replace the values of the `Provider`s above the button with
+/// `HandledEventInfo::update_context`.
+pub type PressedHandler = Box<dyn FnMut(&Key, &mut HandledEventInfo)>;
 
-#[derive(Debug, KeySegment)]
+enum ButtonContent {
+    Label(String),
+    Child(Rc<dyn Fn() -> Box<dyn Widget>>),
+}
+
+pub struct ButtonState {
+    is_pressed: bool,
+}
+
+impl ButtonState {
+    pub fn new() -> Box<Self> {
+        return Box::new(Self { is_pressed: false });
+    }
+}
+
+impl State for ButtonState {}
+
+/// A button coloured from the theme's palette, sized to its content plus
+/// padding. It's pressed by pushing the left mouse button down on it and
+/// releasing it over it again without dragging, which calls `on_pressed`
+/// unless the button is disabled. Drags are left to its ancestors.
+#[derive(Derivative, KeySegment)]
+#[derivative(Debug)]
 pub struct Button {
     widget_data: CompoundWidgetData,
 
-    background_color: Color,
-    hovered_background_color: Color,
+    #[derivative(Debug = "ignore")]
+    content: ButtonContent,
+
+    #[derivative(Debug = "ignore")]
+    on_pressed: Option<PressedHandler>,
+
+    is_disabled: bool,
+    padding: Option<Inset>,
 }
 
 impl Button {
-    pub fn new() -> Box<Self> {
+    /// A button showing whatever `child` builds. It's called again each time
+    /// the button is rebuilt, e.g. when it's hovered.
+    pub fn new(child: Box<dyn Fn() -> Box<dyn Widget>>) -> Box<Self> {
+        return Self::with_content(ButtonContent::Child(Rc::from(child)));
+    }
+
+    /// A button showing `text` in the theme's label style
+    pub fn label(text: &str) -> Box<Self> {
+        return Self::with_content(ButtonContent::Label(text.into()));
+    }
+
+    fn with_content(content: ButtonContent) -> Box<Self> {
         return Box::new(Self {
             widget_data: CompoundWidgetData::new(),
-            background_color: Color { r: 0, g: 0, b: 255 },
-            hovered_background_color: Color {
-                r: 255,
-                g: 255,
-                b: 0,
-            },
+            content,
+            on_pressed: None,
+            is_disabled: false,
+            padding: None,
         });
     }
+
+    pub fn with_on_pressed(mut self: Box<Self>, on_pressed: PressedHandler) -> Box<Self> {
+        self.on_pressed = Some(on_pressed);
+        return self;
+    }
+
+    /// A disabled button is greyed out and ignores the mouse
+    pub fn with_disabled(mut self: Box<Self>, is_disabled: bool) -> Box<Self> {
+        self.is_disabled = is_disabled;
+        return self;
+    }
+
+    /// The space around the content. By default this comes from the theme's
+    /// spacing.
+    pub fn with_padding(mut self: Box<Self>, padding: Inset) -> Box<Self> {
+        self.padding = Some(padding);
+        return self;
+    }
 }
 
-impl CompoundWidget for Button {
-    
+impl StatefulWidget for Button {
+    type T = ButtonState;
+
     compound_widget_default_methods!();
 
-    fn build(&self) -> Box<dyn Widget> {
-        let background_color_clone = self.background_color.clone();
-        let hovered_background_color_clone = self.hovered_background_color.clone();
+    fn state_factory(&self) -> Box<Self::T> {
+        return ButtonState::new();
+    }
+
+    fn build(&self, state: &mut ButtonState) -> Box<dyn Widget> {
+        let theme = Theme::of(self);
+        let palette = theme.palette.clone();
+        let padding = match &self.padding {
+            Some(x) => x.clone(),
+            None => Inset::symmetric(theme.spacing.medium, theme.spacing.large),
+        };
+        let border_radius = theme.spacing.small;
+        let content: Rc<dyn Fn() -> Box<dyn Widget>> = match &self.content {
+            ButtonContent::Label(text) => {
+                let text = text.clone();
+                let mut style = theme.label_style();
+                if self.is_disabled {
+                    style.color = palette.on_disabled.clone();
+                }
+                Rc::new(move || Text::new(&text, style.clone()))
+            }
+            ButtonContent::Child(child) => child.clone(),
+        };
+        let is_disabled = self.is_disabled;
+        let is_pressed = state.is_pressed;
         return Hoverable::new(Box::new(move |is_hovering| {
-            SizedBox::new(
-                Size {
-                    width: 100.,
-                    height: 40.,
-                },
-                Container::new(
-                    if is_hovering {
-                        hovered_background_color_clone.clone()
-                    } else {
-                        background_color_clone.clone()
-                    },
-                    Expanded::new(),
-                ),
+            let background_color = if is_disabled {
+                &palette.disabled
+            } else if is_pressed {
+                &palette.primary_pressed
+            } else if is_hovering {
+                &palette.primary_hovered
+            } else {
+                &palette.primary
+            };
+            Container::decorated(
+                BoxDecoration::new(background_color.clone()).with_border_radius(border_radius),
+                Padding::new(padding.clone(), Some(content())),
             )
         }));
     }
+
+    fn on_mouse_button(
+        &mut self,
+        state: &mut ButtonState,
+        event: &MouseButtonEvent,
+        _platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> StatefulWidgetEventHandlerInfo {
+        if self.is_disabled || event.button != MouseButton::Left {
+            return StatefulWidgetEventHandlerInfo::new();
+        }
+
+        let was_pressed = state.is_pressed;
+        let was_captured = match event.kind {
+            MouseButtonEventKind::Down => {
+                state.is_pressed = true;
+                true
+            }
+            MouseButtonEventKind::Up => {
+                state.is_pressed = false;
+                true
+            }
+            // Only sent if the button wasn't dragged after being pushed down
+            MouseButtonEventKind::Click => {
+                let key = StatefulWidget::get_key(self).clone();
+                if let Some(on_pressed) = &mut self.on_pressed {
+                    on_pressed(&key, handled_event_info);
+                }
+                true
+            }
+            // Drags are left to the button's ancestors, e.g. a scroll view
+            _ => false,
+        };
+
+        return StatefulWidgetEventHandlerInfo {
+            was_captured,
+            needs_rebuild: was_pressed != state.is_pressed,
+        };
+    }
+
+    fn on_mouse_leave(
+        &mut self,
+        state: &mut ButtonState,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> StatefulWidgetEventHandlerInfo {
+        let was_pressed = state.is_pressed;
+        state.is_pressed = false;
+
+        return StatefulWidgetEventHandlerInfo {
+            was_captured: false,
+            needs_rebuild: was_pressed,
+        };
+    }
 }
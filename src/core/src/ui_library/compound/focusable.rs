@@ -0,0 +1,125 @@
+use std::{cell::RefCell, rc::Weak};
+
+use crate::{
+    compound_widget_default_methods,
+    event_handlers::{HandledEventInfo, KeyboardEvent},
+    graphics::{Position, Size},
+    state::{State, StateManager},
+    ui_library::{
+        constraints::BoxConstraints, CompoundWidgetData, Key, StatefulWidget,
+        StatefulWidgetEventHandlerInfo, Widget,
+    },
+    widget_default_methods,
+};
+use derivative::Derivative;
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+/// Returns whether the event was captured. Like `PressedHandler`, it's given
+/// the focusable widget's key and the `HandledEventInfo`, so that it can mark
+/// areas dirty, request a relayout or update the `Provider`s above it.
+pub type KeyboardEventHandler = Box<dyn FnMut(&Key, &KeyboardEvent, &mut HandledEventInfo) -> bool>;
+
+pub struct FocusableState {
+    is_focused: bool,
+}
+
+impl FocusableState {
+    pub fn new() -> Box<Self> {
+        return Box::new(Self { is_focused: false });
+    }
+}
+
+impl State for FocusableState {}
+
+/// Makes its child reachable with Tab and Shift-Tab and by clicking on it.
+/// While focused, keyboard events are offered to `on_keyboard_event`, which
+/// returns whether it captured them; uncaptured events bubble up to the
+/// widget's ancestors.
+#[derive(Derivative, KeySegment)]
+#[derivative(Debug)]
+pub struct Focusable {
+    widget_data: CompoundWidgetData,
+
+    #[derivative(Debug = "ignore")]
+    child: Box<dyn Fn(bool) -> Box<dyn Widget>>,
+
+    #[derivative(Debug = "ignore")]
+    on_keyboard_event: Option<KeyboardEventHandler>,
+}
+
+impl Focusable {
+    pub fn new(
+        child: Box<dyn Fn(bool) -> Box<dyn Widget>>,
+        on_keyboard_event: Option<KeyboardEventHandler>,
+    ) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: CompoundWidgetData::new(),
+            child,
+            on_keyboard_event,
+        });
+    }
+}
+
+impl StatefulWidget for Focusable {
+    type T = FocusableState;
+
+    compound_widget_default_methods!();
+
+    fn state_factory(&self) -> Box<Self::T> {
+        return FocusableState::new();
+    }
+
+    fn build(&self, state: &mut FocusableState) -> Box<dyn Widget> {
+        return (self.child)(state.is_focused);
+    }
+
+    fn is_focusable(&self) -> bool {
+        return true;
+    }
+
+    fn on_focus_change(
+        &mut self,
+        state: &mut FocusableState,
+        is_focused: bool,
+        platform: &dyn crate::platform::Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> StatefulWidgetEventHandlerInfo {
+        platform.logger().log(&format!(
+            "Focusable {}: {:?}",
+            if is_focused {
+                "gained focus"
+            } else {
+                "lost focus"
+            },
+            StatefulWidget::get_key(self)
+        ));
+
+        let was_focused = state.is_focused;
+        state.is_focused = is_focused;
+
+        return StatefulWidgetEventHandlerInfo {
+            was_captured: false,
+            needs_rebuild: was_focused != is_focused,
+        };
+    }
+
+    fn on_keyboard_event(
+        &mut self,
+        _state: &mut FocusableState,
+        event: &KeyboardEvent,
+        _platform: &dyn crate::platform::Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> StatefulWidgetEventHandlerInfo {
+        let key = StatefulWidget::get_key(self).clone();
+        let was_captured = match &mut self.on_keyboard_event {
+            Some(x) => x(&key, event, handled_event_info),
+            None => false,
+        };
+
+        return StatefulWidgetEventHandlerInfo {
+            was_captured,
+            needs_rebuild: false,
+        };
+    }
+}
@@ -4,7 +4,7 @@ use crate::{
     event_handlers::HandledEventInfo,
     graphics::{Position, Size},
     state::{State, StateManager},
-    ui_library::{Key, StatefulWidget, StatefulWidgetEventHandlerInfo, Widget, CompoundWidgetData}, widget_default_methods, compound_widget_default_methods,
+    ui_library::{constraints::BoxConstraints, Key, StatefulWidget, StatefulWidgetEventHandlerInfo, Widget, CompoundWidgetData}, widget_default_methods, compound_widget_default_methods,
 };
 use derivative::Derivative;
 use key_segment::KeySegment;
@@ -53,26 +53,43 @@ impl StatefulWidget for Hoverable {
         return (self.child)(state.is_hovered);
     }
 
-    fn on_mouse_move(
+    fn on_mouse_enter(
         &mut self,
         state: &mut HoverableState,
-        mouse_pos: &Position,
         platform: &dyn crate::platform::Platform,
-        handled_event_info: &mut HandledEventInfo,
+        _handled_event_info: &mut HandledEventInfo,
     ) -> StatefulWidgetEventHandlerInfo {
         platform.logger().log(&format!(
-            "Mouse moved on hoverable: {:?}, {:?}",
-            mouse_pos,
+            "Mouse entered hoverable: {:?}",
             StatefulWidget::get_key(self)
         ));
 
+        let was_hovered = state.is_hovered;
         state.is_hovered = true;
 
-        handled_event_info.needs_relayout = true;
-        handled_event_info.needs_redraw = true;
         return StatefulWidgetEventHandlerInfo {
-            was_captured: true,
-            needs_rebuild: true,
+            was_captured: false,
+            needs_rebuild: !was_hovered,
+        };
+    }
+
+    fn on_mouse_leave(
+        &mut self,
+        state: &mut HoverableState,
+        platform: &dyn crate::platform::Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> StatefulWidgetEventHandlerInfo {
+        platform.logger().log(&format!(
+            "Mouse left hoverable: {:?}",
+            StatefulWidget::get_key(self)
+        ));
+
+        let was_hovered = state.is_hovered;
+        state.is_hovered = false;
+
+        return StatefulWidgetEventHandlerInfo {
+            was_captured: false,
+            needs_rebuild: was_hovered,
         };
     }
 }
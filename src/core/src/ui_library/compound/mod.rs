@@ -1,2 +1,3 @@
 pub mod button;
+pub mod focusable;
 pub mod hoverable;
\ No newline at end of file
@@ -0,0 +1,58 @@
+use std::{cell::RefCell, rc::Weak};
+
+use crate::{
+    graphics::{display_list::DisplayList, Position, Size},
+    platform::Platform,
+    state::StateManager,
+    widget_default_methods,
+};
+
+use super::{constraints::BoxConstraints, Key, Widget, WidgetData};
+
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+/// Imposes extra constraints on its child, e.g. a minimum width for a button
+/// or a maximum height for a panel, as far as its parent's constraints allow
+#[derive(Debug, KeySegment)]
+pub struct ConstrainedBox {
+    widget_data: WidgetData,
+    constraints: BoxConstraints,
+    child: Box<dyn Widget>,
+}
+
+impl ConstrainedBox {
+    pub fn new(constraints: BoxConstraints, child: Box<dyn Widget>) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            constraints,
+            child,
+        });
+    }
+}
+
+impl Widget for ConstrainedBox {
+    widget_default_methods!();
+
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        let size = self
+            .child
+            .set_layout(&constraints.enforce(&self.constraints), platform);
+        self.child.set_position(Position::origin());
+        return size;
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        self.child.paint(
+            parent_position + self.widget_data.position.clone(),
+            display_list,
+        );
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        return vec![self.child.as_mut()];
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        return vec![self.child.as_ref()];
+    }
+}
@@ -0,0 +1,122 @@
+use crate::graphics::Size;
+
+/// The range of sizes a parent allows a child to take. Constraints go down
+/// the tree during layout and each widget picks a size within them, which
+/// goes back up to its parent. A max of infinity means the child can be as
+/// large as it likes along that axis, e.g. inside a `ScrollView`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxConstraints {
+    pub min_width: f64,
+    pub max_width: f64,
+    pub min_height: f64,
+    pub max_height: f64,
+}
+
+impl BoxConstraints {
+    /// Only allows exactly `size`
+    pub fn tight(size: &Size) -> Self {
+        return Self {
+            min_width: size.width,
+            max_width: size.width,
+            min_height: size.height,
+            max_height: size.height,
+        };
+    }
+
+    /// Allows anything from nothing up to `size`
+    pub fn loose(size: &Size) -> Self {
+        return Self {
+            min_width: 0.,
+            max_width: size.width,
+            min_height: 0.,
+            max_height: size.height,
+        };
+    }
+
+    /// Allows any size at all
+    pub fn unbounded() -> Self {
+        return Self {
+            min_width: 0.,
+            max_width: f64::INFINITY,
+            min_height: 0.,
+            max_height: f64::INFINITY,
+        };
+    }
+
+    /// The size within these constraints which is closest to `size`
+    pub fn constrain(&self, size: &Size) -> Size {
+        return Size {
+            width: size.width.min(self.max_width).max(self.min_width),
+            height: size.height.min(self.max_height).max(self.min_height),
+        };
+    }
+
+    /// The largest size allowed, or the smallest along any axis which is
+    /// unbounded
+    pub fn biggest(&self) -> Size {
+        return Size {
+            width: if self.max_width.is_finite() {
+                self.max_width
+            } else {
+                self.min_width
+            },
+            height: if self.max_height.is_finite() {
+                self.max_height
+            } else {
+                self.min_height
+            },
+        };
+    }
+
+    pub fn smallest(&self) -> Size {
+        return Size {
+            width: self.min_width,
+            height: self.min_height,
+        };
+    }
+
+    /// The same maximums, but no minimums
+    pub fn loosen(&self) -> Self {
+        return Self {
+            min_width: 0.,
+            max_width: self.max_width,
+            min_height: 0.,
+            max_height: self.max_height,
+        };
+    }
+
+    /// Shrinks the constraints by `amount` in each axis, e.g. to leave room
+    /// for padding, without letting them go below zero
+    pub fn deflate(&self, amount: &Size) -> Self {
+        let min_width = (self.min_width - amount.width).max(0.);
+        let min_height = (self.min_height - amount.height).max(0.);
+        return Self {
+            min_width,
+            max_width: (self.max_width - amount.width).max(min_width),
+            min_height,
+            max_height: (self.max_height - amount.height).max(min_height),
+        };
+    }
+
+    /// These constraints, narrowed as far as possible towards `other`
+    /// without leaving the range allowed by `self`
+    pub fn enforce(&self, other: &BoxConstraints) -> Self {
+        let clamp = |x: f64, min: f64, max: f64| x.min(max).max(min);
+        return Self {
+            min_width: clamp(other.min_width, self.min_width, self.max_width),
+            max_width: clamp(other.max_width, self.min_width, self.max_width),
+            min_height: clamp(other.min_height, self.min_height, self.max_height),
+            max_height: clamp(other.max_height, self.min_height, self.max_height),
+        };
+    }
+
+    /// Only allows `size`, or the size closest to it within these
+    /// constraints
+    pub fn tighten(&self, size: &Size) -> Self {
+        return self.enforce(&BoxConstraints::tight(size));
+    }
+
+    pub fn is_tight(&self) -> bool {
+        return self.min_width >= self.max_width && self.min_height >= self.max_height;
+    }
+}
@@ -1,60 +1,111 @@
 use std::{cell::RefCell, rc::Weak};
 
 use crate::{
-    graphics::{Color, Position, Size},
+    graphics::{display_list::DisplayList, Color, Position, Rect, Size},
+    platform::Platform,
     state::StateManager,
     widget_default_methods,
 };
 
-use super::{Key, Widget, WidgetData};
+use super::{
+    constraints::BoxConstraints, decoration::BoxDecoration, draw_clipped, Key, Overflow, Widget,
+    WidgetData,
+};
 use key_segment::KeySegment;
 use key_segment_derive::KeySegment;
 
 #[derive(Debug, KeySegment)]
 pub struct Container {
     widget_data: WidgetData,
-    background: Color,
+    decoration: BoxDecoration,
     child: Box<dyn Widget>,
+    overflow: Overflow,
 }
 
 impl Container {
     pub fn new(background: Color, child: Box<dyn Widget>) -> Box<Self> {
+        return Self::decorated(BoxDecoration::new(background), child);
+    }
+
+    /// A container painted with `decoration` rather than a plain background.
+    /// The child is inset by the width of its border.
+    pub fn decorated(decoration: BoxDecoration, child: Box<dyn Widget>) -> Box<Self> {
         return Box::new(Self {
             widget_data: WidgetData::new(),
-            background: background,
-            child: child,
+            decoration,
+            child,
+            overflow: Overflow::Visible,
         });
     }
+
+    /// Replaces the plain background with `decoration`. The child is inset
+    /// by the width of its border.
+    pub fn with_decoration(mut self: Box<Self>, decoration: BoxDecoration) -> Box<Self> {
+        self.decoration = decoration;
+        return self;
+    }
+
+    pub fn with_overflow(mut self: Box<Self>, overflow: Overflow) -> Box<Self> {
+        self.overflow = overflow;
+        return self;
+    }
 }
 
 impl Widget for Container {
     widget_default_methods!();
 
-    fn set_layout(&mut self, position: Position, available_space: Size) {
-        self.widget_data.position = position;
-        self.widget_data.available_space = available_space;
-        self.child
-            .set_layout(Position::origin(), self.widget_data.available_space.clone());
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        let border = self.decoration.border.widths();
+        let total_border = Size {
+            width: border.left + border.right,
+            height: border.top + border.bottom,
+        };
+        let child_size = self
+            .child
+            .set_layout(&constraints.deflate(&total_border), platform);
+        self.child.set_position(Position {
+            x: border.left,
+            y: border.top,
+        });
+        return Size {
+            width: child_size.width + total_border.width,
+            height: child_size.height + total_border.height,
+        };
     }
 
-    fn draw(&self, parent_position: Position, platform: &dyn crate::platform::Platform) -> () {
-        platform.graphics().fill_rect(
-            &(parent_position.clone() + self.widget_data.position.clone()),
-            &self.child.get_size(&self.widget_data.available_space),
-            &self.background,
-        );
-        self.child.draw(
-            parent_position + self.widget_data.position.clone(),
-            platform,
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        let rect = Rect::new(
+            parent_position.clone() + self.widget_data.position.clone(),
+            self.widget_data.size.clone(),
         );
+        // The shadow falls outside the container, so it isn't clipped to it
+        self.decoration.draw_shadow(&rect, display_list);
+        let clip = self.get_clip(&parent_position);
+        draw_clipped(clip.as_ref(), display_list, |display_list| {
+            self.decoration.draw(&rect, display_list);
+            self.child.paint(
+                parent_position + self.widget_data.position.clone(),
+                display_list,
+            );
+        });
     }
 
-    fn get_width(&self, available_space: &Size) -> f64 {
-        return self.child.get_width(available_space);
+    fn get_clip(&self, parent_position: &Position) -> Option<Rect> {
+        return self.overflow.clip(Rect::new(
+            parent_position.clone() + self.widget_data.position.clone(),
+            self.widget_data.size.clone(),
+        ));
     }
 
-    fn get_height(&self, available_space: &Size) -> f64 {
-        return self.child.get_height(available_space);
+    fn get_paint_bounds(&self) -> Rect {
+        let child_bounds = self.child.get_paint_bounds();
+        return self
+            .decoration
+            .paint_bounds(&Rect::from_size(self.widget_data.size.clone()))
+            .union(&Rect::new(
+                child_bounds.position + self.child.get_position().clone(),
+                child_bounds.size,
+            ));
     }
 
     fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
@@ -0,0 +1,425 @@
+use std::f64::consts::PI;
+
+use crate::graphics::{
+    display_list::DisplayList, gradient::LinearGradient, path::Path, Color, Position, Rect, Size,
+};
+
+use super::{padding::Inset, Alignment};
+
+/// One edge of a `Border`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BorderSide {
+    pub width: f64,
+    pub color: Color,
+}
+
+impl BorderSide {
+    pub fn new(width: f64, color: Color) -> Self {
+        return Self { width, color };
+    }
+
+    pub fn none() -> Self {
+        return Self::new(0., Color::transparent());
+    }
+
+    fn is_visible(&self) -> bool {
+        return self.width > 0. && self.color.a > 0;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Border {
+    pub top: BorderSide,
+    pub right: BorderSide,
+    pub bottom: BorderSide,
+    pub left: BorderSide,
+}
+
+impl Border {
+    pub fn new(top: BorderSide, right: BorderSide, bottom: BorderSide, left: BorderSide) -> Self {
+        return Self {
+            top,
+            right,
+            bottom,
+            left,
+        };
+    }
+
+    pub fn all(side: BorderSide) -> Self {
+        return Self::new(side.clone(), side.clone(), side.clone(), side);
+    }
+
+    pub fn none() -> Self {
+        return Self::all(BorderSide::none());
+    }
+
+    /// How far the border reaches in from each edge
+    pub fn widths(&self) -> Inset {
+        return Inset {
+            left: self.left.width,
+            top: self.top.width,
+            right: self.right.width,
+            bottom: self.bottom.width,
+        };
+    }
+
+    fn sides(&self) -> [&BorderSide; 4] {
+        return [&self.top, &self.right, &self.bottom, &self.left];
+    }
+
+    fn is_uniform(&self) -> bool {
+        return self.sides().iter().all(|side| *side == &self.top);
+    }
+}
+
+/// A gradient across a decorated box, from `begin` to `end` given as
+/// fractions of the box's size
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxGradient {
+    pub begin: Alignment,
+    pub end: Alignment,
+    pub begin_color: Color,
+    pub end_color: Color,
+}
+
+impl BoxGradient {
+    pub fn new(begin: Alignment, end: Alignment, begin_color: Color, end_color: Color) -> Self {
+        return Self {
+            begin,
+            end,
+            begin_color,
+            end_color,
+        };
+    }
+
+    fn resolve(&self, rect: &Rect) -> LinearGradient {
+        let at = |alignment: &Alignment| Position {
+            x: rect.position.x + rect.size.width * alignment.x,
+            y: rect.position.y + rect.size.height * alignment.y,
+        };
+        return LinearGradient::new(
+            at(&self.begin),
+            at(&self.end),
+            self.begin_color.clone(),
+            self.end_color.clone(),
+        );
+    }
+}
+
+/// A shadow cast by a decorated box. The box's shape is moved by `offset`,
+/// grown by `spread` and then blurred over `blur_radius` pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxShadow {
+    pub offset: Position,
+    pub blur_radius: f64,
+    pub spread: f64,
+    pub color: Color,
+}
+
+impl BoxShadow {
+    pub fn new(offset: Position, blur_radius: f64, spread: f64, color: Color) -> Self {
+        return Self {
+            offset,
+            blur_radius,
+            spread,
+            color,
+        };
+    }
+
+    fn rect(&self, rect: &Rect) -> Rect {
+        return Rect::new(
+            rect.position.clone() + self.offset.clone(),
+            rect.size.clone(),
+        )
+        .inflate(self.spread);
+    }
+}
+
+/// The most layers a blurred shadow is drawn with
+const MAX_SHADOW_LAYERS: usize = 16;
+
+/// How to paint the box behind a `Container`'s child. The shadow goes
+/// underneath, then the background (the gradient if there is one, otherwise
+/// `color`) and then the border on top. A `Container` insets its child by the
+/// border's widths, but doesn't clip it to the rounded corners.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxDecoration {
+    pub color: Color,
+    pub gradient: Option<BoxGradient>,
+    pub border: Border,
+    /// The radius of every corner, which is reduced if the box is too small
+    /// for it
+    pub border_radius: f64,
+    pub shadow: Option<BoxShadow>,
+}
+
+impl BoxDecoration {
+    pub fn new(color: Color) -> Self {
+        return Self {
+            color,
+            gradient: None,
+            border: Border::none(),
+            border_radius: 0.,
+            shadow: None,
+        };
+    }
+
+    pub fn with_gradient(mut self, gradient: BoxGradient) -> Self {
+        self.gradient = Some(gradient);
+        return self;
+    }
+
+    pub fn with_border(mut self, border: Border) -> Self {
+        self.border = border;
+        return self;
+    }
+
+    pub fn with_border_radius(mut self, border_radius: f64) -> Self {
+        self.border_radius = border_radius;
+        return self;
+    }
+
+    pub fn with_shadow(mut self, shadow: BoxShadow) -> Self {
+        self.shadow = Some(shadow);
+        return self;
+    }
+
+    /// The area drawn to when decorating `rect`, which the shadow may take
+    /// outside of it
+    pub fn paint_bounds(&self, rect: &Rect) -> Rect {
+        return match &self.shadow {
+            Some(shadow) => rect.union(&shadow.rect(rect).inflate(shadow.blur_radius / 2.)),
+            None => rect.clone(),
+        };
+    }
+
+    /// The radius the corners of `rect` are actually drawn with
+    fn radius_for(&self, rect: &Rect) -> f64 {
+        return self
+            .border_radius
+            .min(rect.size.width / 2.)
+            .min(rect.size.height / 2.)
+            .max(0.);
+    }
+
+    /// Draws the shadow, if there is one. This is separate from `draw` so
+    /// that it can be drawn outside of a clip which the rest is drawn within.
+    pub fn draw_shadow(&self, rect: &Rect, display_list: &mut DisplayList) -> () {
+        if let Some(shadow) = &self.shadow {
+            if !rect.is_empty() {
+                draw_shadow(shadow, rect, self.radius_for(rect), display_list);
+            }
+        }
+    }
+
+    /// Draws the background and the border, but not the shadow
+    pub fn draw(&self, rect: &Rect, display_list: &mut DisplayList) -> () {
+        if rect.is_empty() {
+            return;
+        }
+        let radius = self.radius_for(rect);
+
+        // Plain boxes are drawn as rects, which every backend fills exactly
+        let outline = if radius > 0. {
+            Some(rounded_rect(rect, &uniform_radii(radius), true))
+        } else {
+            None
+        };
+        match (&self.gradient, &outline) {
+            (Some(gradient), Some(outline)) => {
+                display_list.fill_path_gradient(outline, &gradient.resolve(rect))
+            }
+            (Some(gradient), None) => display_list
+                .fill_path_gradient(&Path::polygon(&corners(rect)), &gradient.resolve(rect)),
+            (None, Some(outline)) => display_list.fill_path(outline, &self.color),
+            (None, None) => display_list.fill_rect(&rect.position, &rect.size, &self.color),
+        }
+
+        draw_border(&self.border, rect, radius, display_list);
+    }
+}
+
+/// Blurs the shadow by stacking translucent copies of its shape, from
+/// `blur_radius / 2` outside it to `blur_radius / 2` inside it, so that
+/// together they reach the shadow's full opacity in the middle
+fn draw_shadow(shadow: &BoxShadow, rect: &Rect, radius: f64, display_list: &mut DisplayList) {
+    let shadow_rect = shadow.rect(rect);
+    let shadow_radius = radius + shadow.spread;
+    let layers = (shadow.blur_radius.ceil() as usize).clamp(1, MAX_SHADOW_LAYERS);
+    let opacity = shadow.color.a as f64 / 255.;
+    let layer_opacity = 1. - (1. - opacity).powf(1. / layers as f64);
+    let color = shadow
+        .color
+        .with_alpha((layer_opacity * 255.).round() as u8);
+    for i in 0..layers {
+        let amount = shadow.blur_radius * (0.5 - i as f64 / layers as f64);
+        let layer = shadow_rect.inflate(amount);
+        if layer.is_empty() {
+            continue;
+        }
+        let layer_radius = (shadow_radius + amount)
+            .min(layer.size.width / 2.)
+            .min(layer.size.height / 2.)
+            .max(0.);
+        display_list.fill_path(
+            &rounded_rect(&layer, &uniform_radii(layer_radius), true),
+            &color,
+        );
+    }
+}
+
+/// A uniform border is filled as a single ring. Otherwise each side is
+/// filled separately, meeting its neighbours on the diagonals of the corners.
+fn draw_border(border: &Border, rect: &Rect, radius: f64, display_list: &mut DisplayList) {
+    let sides = border.sides();
+    if !sides.iter().any(|side| side.is_visible()) {
+        return;
+    }
+    let outer = Corners::outer(rect, radius);
+    let inner = Corners::inner(rect, radius, &border.widths());
+
+    if border.is_uniform() {
+        let mut ring = rounded_rect(rect, &outer.radii, true);
+        ring.segments
+            .extend(rounded_rect(&inner.rect, &inner.radii, false).segments);
+        display_list.fill_path(&ring, &border.top.color);
+        return;
+    }
+
+    for (i, side) in sides.iter().enumerate() {
+        if !side.is_visible() {
+            continue;
+        }
+        // The side runs from the middle of corner `i` to the middle of the
+        // next corner, clockwise
+        let next = (i + 1) % 4;
+        let start = corner_start_angle(i);
+        let end = start + PI / 2.;
+        let path = Path::new()
+            .arc_to(
+                outer.center(i),
+                outer.radii[i].clone(),
+                start + PI / 4.,
+                end,
+            )
+            .arc_to(
+                outer.center(next),
+                outer.radii[next].clone(),
+                end,
+                end + PI / 4.,
+            )
+            .arc_to(
+                inner.center(next),
+                inner.radii[next].clone(),
+                end + PI / 4.,
+                end,
+            )
+            .arc_to(
+                inner.center(i),
+                inner.radii[i].clone(),
+                end,
+                start + PI / 4.,
+            )
+            .close();
+        display_list.fill_path(&path, &side.color);
+    }
+}
+
+/// Corners are numbered clockwise from the top left
+const CORNER_DIRECTIONS: [(f64, f64); 4] = [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.)];
+
+/// The angle at which the arc around a corner starts when going clockwise
+fn corner_start_angle(corner: usize) -> f64 {
+    return PI + corner as f64 * PI / 2.;
+}
+
+fn uniform_radii(radius: f64) -> [Size; 4] {
+    let radii = Size {
+        width: radius,
+        height: radius,
+    };
+    return [radii.clone(), radii.clone(), radii.clone(), radii];
+}
+
+fn corners(rect: &Rect) -> [Position; 4] {
+    return CORNER_DIRECTIONS.map(|(x, y)| Position {
+        x: rect.position.x + rect.size.width * (x + 1.) / 2.,
+        y: rect.position.y + rect.size.height * (y + 1.) / 2.,
+    });
+}
+
+/// A rect with elliptical corners
+struct Corners {
+    rect: Rect,
+    radii: [Size; 4],
+}
+
+impl Corners {
+    fn outer(rect: &Rect, radius: f64) -> Self {
+        return Self {
+            rect: rect.clone(),
+            radii: uniform_radii(radius),
+        };
+    }
+
+    /// The inside edge of a border, whose corners are the outer ones less
+    /// the width of the sides meeting there
+    fn inner(rect: &Rect, radius: f64, widths: &Inset) -> Self {
+        let horizontal = [widths.left, widths.right, widths.right, widths.left];
+        let vertical = [widths.top, widths.top, widths.bottom, widths.bottom];
+        return Self {
+            rect: Rect::new(
+                Position {
+                    x: rect.position.x + widths.left,
+                    y: rect.position.y + widths.top,
+                },
+                Size {
+                    width: (rect.size.width - widths.left - widths.right).max(0.),
+                    height: (rect.size.height - widths.top - widths.bottom).max(0.),
+                },
+            ),
+            radii: [0, 1, 2, 3].map(|i| Size {
+                width: (radius - horizontal[i]).max(0.),
+                height: (radius - vertical[i]).max(0.),
+            }),
+        };
+    }
+
+    fn center(&self, corner: usize) -> Position {
+        let (x, y) = CORNER_DIRECTIONS[corner];
+        let point = &corners(&self.rect)[corner];
+        return Position {
+            x: point.x - x * self.radii[corner].width,
+            y: point.y - y * self.radii[corner].height,
+        };
+    }
+}
+
+/// Traces the outline of a rect with the given corner radii, clockwise from
+/// the top left or, if `clockwise` is false, anticlockwise, so that it cuts a
+/// hole when added to a clockwise path
+fn rounded_rect(rect: &Rect, radii: &[Size; 4], clockwise: bool) -> Path {
+    let corners = Corners {
+        rect: rect.clone(),
+        radii: radii.clone(),
+    };
+    // Both directions start at the left end of the top left corner, and a
+    // fresh subpath is started there so that this can be appended to another
+    let start = corners.center(0);
+    let mut path = Path::new().move_to(Position {
+        x: start.x - radii[0].width,
+        y: start.y,
+    });
+    if clockwise {
+        for (i, radius) in radii.iter().enumerate() {
+            let start = corner_start_angle(i);
+            path = path.arc_to(corners.center(i), radius.clone(), start, start + PI / 2.);
+        }
+    } else {
+        for (i, radius) in radii.iter().enumerate().rev() {
+            let start = corner_start_angle(i);
+            path = path.arc_to(corners.center(i), radius.clone(), start + PI / 2., start);
+        }
+    }
+    return path.close();
+}
@@ -1,27 +1,75 @@
 use std::{cell::RefCell, rc::Weak};
 
 use crate::{
-    graphics::{Position, Size},
+    graphics::{display_list::DisplayList, Position, Size},
+    platform::Platform,
     state::StateManager,
     widget_default_methods,
 };
 
-use super::{Key, Widget, WidgetData};
+use super::{constraints::BoxConstraints, Key, Widget, WidgetData};
 
 use key_segment::KeySegment;
 use key_segment_derive::KeySegment;
 
+/// How an expanding child of a `List` is sized. The space left over after
+/// the other children is shared out in proportion to `factor`, but no child
+/// is made shorter than `min` or longer than `max` along the list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flex {
+    pub factor: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Flex {
+    pub fn new() -> Self {
+        return Self {
+            factor: 1.,
+            min: 0.,
+            max: f64::INFINITY,
+        };
+    }
+
+    pub fn clamp(&self, length: f64) -> f64 {
+        return length.min(self.max).max(self.min);
+    }
+}
+
+impl Default for Flex {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
 #[derive(Debug, KeySegment)]
 pub struct Expanded {
     widget_data: WidgetData,
+    flex: Flex,
 }
 
 impl Expanded {
     pub fn new() -> Box<Self> {
         return Box::new(Self {
             widget_data: WidgetData::new(),
+            flex: Flex::new(),
         });
     }
+
+    pub fn with_flex(mut self: Box<Self>, factor: f64) -> Box<Self> {
+        self.flex.factor = factor;
+        return self;
+    }
+
+    pub fn with_min_size(mut self: Box<Self>, min: f64) -> Box<Self> {
+        self.flex.min = min;
+        return self;
+    }
+
+    pub fn with_max_size(mut self: Box<Self>, max: f64) -> Box<Self> {
+        self.flex.max = max;
+        return self;
+    }
 }
 
 impl Widget for Expanded {
@@ -31,24 +79,16 @@ impl Widget for Expanded {
         return true;
     }
 
-    fn set_layout(&mut self, position: Position, available_space: Size) {
-        self.widget_data.position = position;
-        self.widget_data.available_space = available_space;
+    fn get_flex(&self) -> Flex {
+        return self.flex.clone();
     }
 
-    fn draw(&self, _parent_position: Position, _platform: &dyn crate::platform::Platform) -> () {}
-
-    fn get_size(&self, available_space: &Size) -> Size {
-        return available_space.clone();
+    /// As large as allowed
+    fn layout(&mut self, constraints: &BoxConstraints, _platform: &dyn Platform) -> Size {
+        return constraints.biggest();
     }
 
-    fn get_width(&self, available_space: &Size) -> f64 {
-        return available_space.width;
-    }
-
-    fn get_height(&self, available_space: &Size) -> f64 {
-        return available_space.height;
-    }
+    fn draw(&self, _parent_position: Position, _display_list: &mut DisplayList) -> () {}
 
     fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
         return vec![];
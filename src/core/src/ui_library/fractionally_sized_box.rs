@@ -0,0 +1,111 @@
+use std::{cell::RefCell, rc::Weak};
+
+use crate::{
+    graphics::{display_list::DisplayList, Position, Size},
+    platform::Platform,
+    state::StateManager,
+    widget_default_methods,
+};
+
+use super::{constraints::BoxConstraints, Alignment, Key, Widget, WidgetData};
+
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+/// Sizes its child to a fraction of the space it's given, e.g. a dialog
+/// which covers half the window, and places it within that space at
+/// `alignment`. Without a factor, or along an unbounded axis, the child is
+/// left to size itself as usual.
+#[derive(Debug, KeySegment)]
+pub struct FractionallySizedBox {
+    widget_data: WidgetData,
+    width_factor: Option<f64>,
+    height_factor: Option<f64>,
+    alignment: Alignment,
+    child: Box<dyn Widget>,
+}
+
+impl FractionallySizedBox {
+    pub fn new(
+        width_factor: Option<f64>,
+        height_factor: Option<f64>,
+        child: Box<dyn Widget>,
+    ) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            width_factor,
+            height_factor,
+            alignment: Alignment::center(),
+            child,
+        });
+    }
+
+    pub fn with_alignment(mut self: Box<Self>, alignment: Alignment) -> Box<Self> {
+        self.alignment = alignment;
+        return self;
+    }
+}
+
+/// The range of lengths allowed along one axis, which is a single length if
+/// there is a factor and the axis is bounded
+fn fraction_of(factor: Option<f64>, min: f64, max: f64) -> (f64, f64) {
+    return match factor {
+        Some(factor) if max.is_finite() => (max * factor, max * factor),
+        _ => (min, max),
+    };
+}
+
+impl Widget for FractionallySizedBox {
+    widget_default_methods!();
+
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        let (min_width, max_width) = fraction_of(
+            self.width_factor,
+            constraints.min_width,
+            constraints.max_width,
+        );
+        let (min_height, max_height) = fraction_of(
+            self.height_factor,
+            constraints.min_height,
+            constraints.max_height,
+        );
+        let child_size = self.child.set_layout(
+            &BoxConstraints {
+                min_width,
+                max_width,
+                min_height,
+                max_height,
+            },
+            platform,
+        );
+        let size = constraints.constrain(&Size {
+            width: if constraints.max_width.is_finite() {
+                constraints.max_width
+            } else {
+                child_size.width
+            },
+            height: if constraints.max_height.is_finite() {
+                constraints.max_height
+            } else {
+                child_size.height
+            },
+        });
+        self.child
+            .set_position(self.alignment.offset(&size, &child_size));
+        return size;
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        self.child.paint(
+            parent_position + self.widget_data.position.clone(),
+            display_list,
+        );
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        return vec![self.child.as_mut()];
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        return vec![self.child.as_ref()];
+    }
+}
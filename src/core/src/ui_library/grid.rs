@@ -0,0 +1,354 @@
+use std::{cell::RefCell, rc::Weak};
+
+use crate::{
+    graphics::{display_list::DisplayList, Position, Size},
+    platform::Platform,
+    state::StateManager,
+    widget_default_methods,
+};
+
+use super::{
+    constraints::BoxConstraints,
+    list::{CrossAxisAlignment, MainAxisAlignment},
+    Key, Widget, WidgetData,
+};
+
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+/// How long a row or column of a `Grid` is
+#[derive(Debug, Clone, PartialEq)]
+pub enum GridTrack {
+    /// Exactly this many pixels
+    Fixed(f64),
+    /// A share of the space left over after the other tracks, in proportion
+    /// to this factor. If the grid is unbounded along the axis there's no
+    /// space left over, so these are sized like `Auto` tracks instead.
+    Fraction(f64),
+    /// Just long enough for the cells in it
+    Auto,
+}
+
+/// A child of a `Grid`, placed at `row` and `column` and covering `row_span`
+/// rows and `column_span` columns from there
+#[derive(Debug)]
+pub struct GridCell {
+    row: usize,
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+    horizontal_alignment: CrossAxisAlignment,
+    vertical_alignment: CrossAxisAlignment,
+    child: Box<dyn Widget>,
+}
+
+impl GridCell {
+    pub fn new(row: usize, column: usize, child: Box<dyn Widget>) -> Self {
+        return Self {
+            row,
+            column,
+            row_span: 1,
+            column_span: 1,
+            horizontal_alignment: CrossAxisAlignment::Start,
+            vertical_alignment: CrossAxisAlignment::Start,
+            child,
+        };
+    }
+
+    pub fn with_span(mut self, row_span: usize, column_span: usize) -> Self {
+        self.row_span = row_span.max(1);
+        self.column_span = column_span.max(1);
+        return self;
+    }
+
+    /// Where the child goes if it's smaller than its cell
+    pub fn with_alignment(
+        mut self,
+        horizontal_alignment: CrossAxisAlignment,
+        vertical_alignment: CrossAxisAlignment,
+    ) -> Self {
+        self.horizontal_alignment = horizontal_alignment;
+        self.vertical_alignment = vertical_alignment;
+        return self;
+    }
+}
+
+/// Lays its children out in rows and columns. Cells may span several tracks
+/// and leave others empty. If the grid ends up larger than its tracks, e.g.
+/// because it's given a tight size and has no `Fraction` tracks, the tracks
+/// are spread out according to `column_alignment` and `row_alignment`.
+///
+/// Cells which reach past the last row or column get extra `Auto` tracks to
+/// fit into, as implicit tracks do in a CSS grid, and a warning is logged
+/// the first time the grid is laid out.
+#[derive(Debug, KeySegment)]
+pub struct Grid {
+    widget_data: WidgetData,
+    columns: Vec<GridTrack>,
+    rows: Vec<GridTrack>,
+    column_gap: f64,
+    row_gap: f64,
+    column_alignment: MainAxisAlignment,
+    row_alignment: MainAxisAlignment,
+    cells: Vec<GridCell>,
+    /// The size of the grid as given, before any tracks were added for
+    /// cells which didn't fit, while the warning about it is yet to be
+    /// logged
+    unwarned_explicit_size: Option<(usize, usize)>,
+}
+
+impl Grid {
+    pub fn new(
+        mut columns: Vec<GridTrack>,
+        mut rows: Vec<GridTrack>,
+        cells: Vec<GridCell>,
+    ) -> Box<Self> {
+        let explicit_size = (columns.len(), rows.len());
+        for cell in &cells {
+            if cell.column + cell.column_span > columns.len() {
+                columns.resize(cell.column + cell.column_span, GridTrack::Auto);
+            }
+            if cell.row + cell.row_span > rows.len() {
+                rows.resize(cell.row + cell.row_span, GridTrack::Auto);
+            }
+        }
+        let unwarned_explicit_size = if explicit_size == (columns.len(), rows.len()) {
+            None
+        } else {
+            Some(explicit_size)
+        };
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            columns,
+            rows,
+            column_gap: 0.,
+            row_gap: 0.,
+            column_alignment: MainAxisAlignment::Start,
+            row_alignment: MainAxisAlignment::Start,
+            cells,
+            unwarned_explicit_size,
+        });
+    }
+
+    pub fn with_gaps(mut self: Box<Self>, column_gap: f64, row_gap: f64) -> Box<Self> {
+        self.column_gap = column_gap;
+        self.row_gap = row_gap;
+        return self;
+    }
+
+    pub fn with_track_alignment(
+        mut self: Box<Self>,
+        column_alignment: MainAxisAlignment,
+        row_alignment: MainAxisAlignment,
+    ) -> Box<Self> {
+        self.column_alignment = column_alignment;
+        self.row_alignment = row_alignment;
+        return self;
+    }
+}
+
+/// Whether a track's length depends on what's in it
+fn is_sized_by_content(track: &GridTrack, is_bounded: bool) -> bool {
+    return match track {
+        GridTrack::Fixed(_) => false,
+        GridTrack::Fraction(_) => !is_bounded,
+        GridTrack::Auto => true,
+    };
+}
+
+/// Works out the length of each track along one axis. `cells` holds the
+/// first track, the number of tracks and the length of the content of every
+/// cell which was measured.
+fn size_tracks(
+    tracks: &[GridTrack],
+    gap: f64,
+    max_length: f64,
+    cells: &[(usize, usize, f64)],
+) -> Vec<f64> {
+    let is_bounded = max_length.is_finite();
+    let mut lengths: Vec<f64> = tracks
+        .iter()
+        .map(|track| match track {
+            GridTrack::Fixed(length) => *length,
+            _ => 0.,
+        })
+        .collect();
+
+    // Cells in a single track are sized first, so that cells spanning
+    // several tracks only have to make up the difference
+    for (start, span, length) in cells {
+        if *span == 1 && is_sized_by_content(&tracks[*start], is_bounded) {
+            lengths[*start] = lengths[*start].max(*length);
+        }
+    }
+    for (start, span, length) in cells {
+        if *span == 1 {
+            continue;
+        }
+        let spanned = *start..*start + *span;
+        let current: f64 = lengths[spanned.clone()].iter().sum::<f64>() + gap * (*span - 1) as f64;
+        let growable: Vec<usize> = spanned
+            .filter(|i| is_sized_by_content(&tracks[*i], is_bounded))
+            .collect();
+        if *length > current && !growable.is_empty() {
+            let extra = (*length - current) / growable.len() as f64;
+            for i in growable {
+                lengths[i] += extra;
+            }
+        }
+    }
+
+    if is_bounded {
+        let used: f64 = lengths.iter().sum::<f64>() + gap * tracks.len().saturating_sub(1) as f64;
+        let free_space = (max_length - used).max(0.);
+        let total_fraction: f64 = tracks
+            .iter()
+            .map(|track| match track {
+                GridTrack::Fraction(fraction) => *fraction,
+                _ => 0.,
+            })
+            .sum();
+        for (length, track) in lengths.iter_mut().zip(tracks) {
+            if let GridTrack::Fraction(fraction) = track {
+                if *fraction > 0. {
+                    *length = free_space * fraction / total_fraction;
+                }
+            }
+        }
+    }
+    return lengths;
+}
+
+/// The length of `span` tracks from `start`, including the gaps between them
+fn span_length(lengths: &[f64], gap: f64, start: usize, span: usize) -> f64 {
+    return lengths[start..start + span].iter().sum::<f64>() + gap * span.saturating_sub(1) as f64;
+}
+
+impl Widget for Grid {
+    widget_default_methods!();
+
+    /// Only the cells in tracks which are sized by their content are
+    /// measured before the tracks are sized. Every cell is then laid out
+    /// within the space its tracks give it.
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        if let Some((columns, rows)) = self.unwarned_explicit_size.take() {
+            platform.logger().log(&format!(
+                "Warning: {:?} has cells outside its {}x{} grid, so it was grown to {}x{}",
+                self.get_key(),
+                rows,
+                columns,
+                self.rows.len(),
+                self.columns.len(),
+            ));
+        }
+        let max_size = Size {
+            width: constraints.max_width,
+            height: constraints.max_height,
+        };
+        let is_width_bounded = max_size.width.is_finite();
+        let is_height_bounded = max_size.height.is_finite();
+
+        let mut measured_columns: Vec<(usize, usize, f64)> = vec![];
+        let mut measured_rows: Vec<(usize, usize, f64)> = vec![];
+        for cell in &mut self.cells {
+            let columns = &self.columns[cell.column..cell.column + cell.column_span];
+            let rows = &self.rows[cell.row..cell.row + cell.row_span];
+            let is_width_measured = columns
+                .iter()
+                .any(|track| is_sized_by_content(track, is_width_bounded));
+            let is_height_measured = rows
+                .iter()
+                .any(|track| is_sized_by_content(track, is_height_bounded));
+            if is_width_measured || is_height_measured {
+                // Along an axis sized by content the child gets no maximum,
+                // so that it's measured at its natural length rather than
+                // filling the whole grid
+                let size = cell.child.set_layout(
+                    &BoxConstraints::loose(&Size {
+                        width: if is_width_measured {
+                            f64::INFINITY
+                        } else {
+                            max_size.width
+                        },
+                        height: if is_height_measured {
+                            f64::INFINITY
+                        } else {
+                            max_size.height
+                        },
+                    }),
+                    platform,
+                );
+                measured_columns.push((cell.column, cell.column_span, size.width));
+                measured_rows.push((cell.row, cell.row_span, size.height));
+            }
+        }
+
+        let column_widths = size_tracks(
+            &self.columns,
+            self.column_gap,
+            max_size.width,
+            &measured_columns,
+        );
+        let row_heights = size_tracks(&self.rows, self.row_gap, max_size.height, &measured_rows);
+        let size = constraints.constrain(&Size {
+            width: span_length(&column_widths, self.column_gap, 0, self.columns.len()),
+            height: span_length(&row_heights, self.row_gap, 0, self.rows.len()),
+        });
+        let column_starts =
+            self.column_alignment
+                .place(&column_widths, self.column_gap, size.width);
+        let row_starts = self
+            .row_alignment
+            .place(&row_heights, self.row_gap, size.height);
+
+        for cell in &mut self.cells {
+            let cell_size = Size {
+                width: span_length(
+                    &column_widths,
+                    self.column_gap,
+                    cell.column,
+                    cell.column_span,
+                ),
+                height: span_length(&row_heights, self.row_gap, cell.row, cell.row_span),
+            };
+            let child_size = cell
+                .child
+                .set_layout(&BoxConstraints::loose(&cell_size), platform);
+            cell.child.set_position(Position {
+                x: column_starts[cell.column]
+                    + cell
+                        .horizontal_alignment
+                        .offset(cell_size.width, child_size.width),
+                y: row_starts[cell.row]
+                    + cell
+                        .vertical_alignment
+                        .offset(cell_size.height, child_size.height),
+            });
+        }
+        return size;
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        for cell in &self.cells {
+            cell.child.paint(
+                parent_position.clone() + self.widget_data.position.clone(),
+                display_list,
+            );
+        }
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        let mut result: Vec<&mut dyn Widget> = vec![];
+        for cell in &mut self.cells {
+            result.push(cell.child.as_mut());
+        }
+        return result;
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        let mut result: Vec<&dyn Widget> = vec![];
+        for cell in &self.cells {
+            result.push(cell.child.as_ref());
+        }
+        return result;
+    }
+}
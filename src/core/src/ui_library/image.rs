@@ -0,0 +1,142 @@
+use std::{cell::RefCell, rc::Weak};
+
+use crate::{
+    graphics::{bitmap::Bitmap, display_list::DisplayList, Position, Rect, Size},
+    platform::Platform,
+    state::StateManager,
+    widget_default_methods,
+};
+
+use super::{constraints::BoxConstraints, draw_clipped, Alignment, Key, Widget, WidgetData};
+
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+/// How an `Image` scales its bitmap when it's laid out at a different size
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageFit {
+    /// As large as possible while still showing the whole bitmap, keeping
+    /// its aspect ratio
+    Contain,
+    /// As small as possible while still covering the whole image, keeping
+    /// its aspect ratio. Whatever sticks out is clipped.
+    Cover,
+    /// Stretched to exactly the image's size
+    Fill,
+}
+
+impl ImageFit {
+    /// The size to draw a bitmap of size `bitmap` at in a box of size `size`
+    pub fn apply(&self, bitmap: &Size, size: &Size) -> Size {
+        if bitmap.width <= 0. || bitmap.height <= 0. {
+            return size.clone();
+        }
+        let scale_x = size.width / bitmap.width;
+        let scale_y = size.height / bitmap.height;
+        let scale = match self {
+            ImageFit::Contain => scale_x.min(scale_y),
+            ImageFit::Cover => scale_x.max(scale_y),
+            ImageFit::Fill => return size.clone(),
+        };
+        return Size {
+            width: bitmap.width * scale,
+            height: bitmap.height * scale,
+        };
+    }
+}
+
+/// Shows a bitmap. The image takes the bitmap's own size if the constraints
+/// allow it, and otherwise the closest size to it with the same aspect
+/// ratio, so it only needs sizing explicitly if it should be drawn at a
+/// different scale. Load the bitmap with `GraphicsLibrary::load_bitmap`.
+#[derive(Debug, KeySegment)]
+pub struct Image {
+    widget_data: WidgetData,
+    bitmap: Bitmap,
+    fit: ImageFit,
+    alignment: Alignment,
+}
+
+impl Image {
+    pub fn new(bitmap: Bitmap) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            bitmap,
+            fit: ImageFit::Contain,
+            alignment: Alignment::center(),
+        });
+    }
+
+    pub fn with_fit(mut self: Box<Self>, fit: ImageFit) -> Box<Self> {
+        self.fit = fit;
+        return self;
+    }
+
+    /// Where the bitmap goes if it doesn't fill the image exactly
+    pub fn with_alignment(mut self: Box<Self>, alignment: Alignment) -> Box<Self> {
+        self.alignment = alignment;
+        return self;
+    }
+
+    fn get_size_for(&self, constraints: &BoxConstraints) -> Size {
+        let intrinsic = self.bitmap.size();
+        if constraints.is_tight() || intrinsic.width <= 0. || intrinsic.height <= 0. {
+            return constraints.constrain(&intrinsic);
+        }
+        let aspect_ratio = intrinsic.width / intrinsic.height;
+        let mut width = intrinsic.width;
+        let mut height = intrinsic.height;
+        // As with `AspectRatio`, shrink to fit the maximums and then grow to
+        // meet the minimums
+        if width > constraints.max_width {
+            width = constraints.max_width;
+            height = width / aspect_ratio;
+        }
+        if height > constraints.max_height {
+            height = constraints.max_height;
+            width = height * aspect_ratio;
+        }
+        if width < constraints.min_width {
+            width = constraints.min_width;
+            height = width / aspect_ratio;
+        }
+        if height < constraints.min_height {
+            height = constraints.min_height;
+            width = height * aspect_ratio;
+        }
+        return constraints.constrain(&Size { width, height });
+    }
+}
+
+impl Widget for Image {
+    widget_default_methods!();
+
+    fn layout(&mut self, constraints: &BoxConstraints, _platform: &dyn Platform) -> Size {
+        return self.get_size_for(constraints);
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        let my_pos = parent_position + self.widget_data.position.clone();
+        let size = &self.widget_data.size;
+        let bitmap_size = self.fit.apply(&self.bitmap.size(), size);
+        let clip = if bitmap_size.width > size.width || bitmap_size.height > size.height {
+            Some(Rect::new(my_pos.clone(), size.clone()))
+        } else {
+            None
+        };
+        draw_clipped(clip.as_ref(), display_list, |display_list| {
+            display_list.draw_bitmap(
+                &self.bitmap,
+                &(my_pos.clone() + self.alignment.offset(size, &bitmap_size)),
+                &bitmap_size,
+            );
+        });
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        return vec![];
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        return vec![];
+    }
+}
@@ -1,13 +1,13 @@
 use std::{cell::RefCell, rc::Weak};
 
 use crate::{
-    graphics::{Position, Size},
+    graphics::{display_list::DisplayList, Position, Size},
     platform::Platform,
     state::StateManager,
     widget_default_methods,
 };
 
-use super::{Key, Widget, WidgetData};
+use super::{constraints::BoxConstraints, expanded::Flex, Key, Widget, WidgetData};
 
 use key_segment::KeySegment;
 use key_segment_derive::KeySegment;
@@ -28,6 +28,44 @@ pub enum CrossAxisAlignment {
     End,
 }
 
+impl MainAxisAlignment {
+    /// Where each of a row of items starts, when items of the given
+    /// `lengths` with `gap` between them are spread across `length`
+    pub fn place(&self, lengths: &[f64], gap: f64, length: f64) -> Vec<f64> {
+        let count = lengths.len();
+        let total = lengths.iter().sum::<f64>() + gap * count.saturating_sub(1) as f64;
+        let extra_space = (length - total).max(0.);
+        let (mut offset, extra_gap) = match self {
+            MainAxisAlignment::Start => (0., 0.),
+            MainAxisAlignment::Center => (extra_space / 2., 0.),
+            MainAxisAlignment::End => (extra_space, 0.),
+            MainAxisAlignment::SpaceBetween if count > 1 => (0., extra_space / (count - 1) as f64),
+            MainAxisAlignment::SpaceBetween => (0., 0.),
+            MainAxisAlignment::SpaceAround => {
+                let extra_gap = extra_space / (count + 1) as f64;
+                (extra_gap, extra_gap)
+            }
+        };
+        let mut starts = vec![];
+        for item_length in lengths {
+            starts.push(offset);
+            offset += item_length + gap + extra_gap;
+        }
+        return starts;
+    }
+}
+
+impl CrossAxisAlignment {
+    /// The offset of something of length `inner` aligned within `outer`
+    pub fn offset(&self, outer: f64, inner: f64) -> f64 {
+        return match self {
+            CrossAxisAlignment::Start => 0.,
+            CrossAxisAlignment::Center => (outer - inner) / 2.,
+            CrossAxisAlignment::End => outer - inner,
+        };
+    }
+}
+
 #[derive(Debug)]
 pub enum MainAxisSize {
     Min,
@@ -76,22 +114,149 @@ impl List {
             children,
         });
     }
+
+    fn main_axis_length(&self, size: &Size) -> f64 {
+        return match self.direction {
+            ListDirection::Column => size.height,
+            ListDirection::Row => size.width,
+        };
+    }
+
+    fn cross_axis_length(&self, size: &Size) -> f64 {
+        return match self.direction {
+            ListDirection::Column => size.width,
+            ListDirection::Row => size.height,
+        };
+    }
+
+    /// Constraints for a child which can be between `min_main` and
+    /// `max_main` long and up to `max_cross` across
+    fn child_constraints(&self, min_main: f64, max_main: f64, max_cross: f64) -> BoxConstraints {
+        return match self.direction {
+            ListDirection::Column => BoxConstraints {
+                min_width: 0.,
+                max_width: max_cross,
+                min_height: min_main,
+                max_height: max_main,
+            },
+            ListDirection::Row => BoxConstraints {
+                min_width: min_main,
+                max_width: max_main,
+                min_height: 0.,
+                max_height: max_cross,
+            },
+        };
+    }
+
+    fn set_main_axis_length(&self, size: &mut Size, length: f64) {
+        match self.direction {
+            ListDirection::Column => size.height = length,
+            ListDirection::Row => size.width = length,
+        };
+    }
+
+    /// Gives the expanding children the main axis space which the others
+    /// don't use, in proportion to their flex factors. Any child which would
+    /// break its min or max is fixed at that limit and the rest is shared
+    /// out again among the others, as in CSS flexbox.
+    fn share_space_between_expanding_children(&self, child_sizes: &mut [Size], list_size: &Size) {
+        let mut unfrozen: Vec<(usize, Flex)> = vec![];
+        let mut used_space = 0.;
+        for (i, child) in self.children.iter().enumerate() {
+            if child.does_expand() {
+                unfrozen.push((i, child.get_flex()));
+            } else {
+                used_space += self.main_axis_length(&child_sizes[i]);
+            }
+        }
+        let mut free_space = (self.main_axis_length(list_size) - used_space).max(0.);
+
+        while !unfrozen.is_empty() {
+            let total_factor: f64 = unfrozen.iter().map(|(_, flex)| flex.factor).sum();
+            let shares: Vec<(f64, f64)> = unfrozen
+                .iter()
+                .map(|(_, flex)| {
+                    let share = if flex.factor > 0. {
+                        free_space * flex.factor / total_factor
+                    } else {
+                        0.
+                    };
+                    (share, flex.clamp(share))
+                })
+                .collect();
+            let total_violation: f64 = shares.iter().map(|(share, clamped)| clamped - share).sum();
+
+            // Only freeze the children which were held back in the same
+            // direction as the overall violation, since fixing those frees
+            // up or uses up space for the rest
+            let mut still_unfrozen: Vec<(usize, Flex)> = vec![];
+            for ((i, flex), (share, clamped)) in unfrozen.into_iter().zip(shares) {
+                let is_frozen = if total_violation > 0. {
+                    clamped > share
+                } else if total_violation < 0. {
+                    clamped < share
+                } else {
+                    true
+                };
+                if is_frozen {
+                    self.set_main_axis_length(&mut child_sizes[i], clamped);
+                    free_space -= clamped;
+                } else {
+                    still_unfrozen.push((i, flex));
+                }
+            }
+            unfrozen = still_unfrozen;
+        }
+    }
 }
 
 impl Widget for List {
     widget_default_methods!();
 
-    fn set_layout(&mut self, position: Position, available_space: Size) {
-        self.widget_data.position = position;
-        self.widget_data.available_space = available_space;
+    /// Lays out the children which don't expand first, with as much space
+    /// along the list as the list has, and then shares out what they left
+    /// between the ones which do
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        let min_size = constraints.smallest();
+        let max_size = Size {
+            width: constraints.max_width,
+            height: constraints.max_height,
+        };
+        let max_main = self.main_axis_length(&max_size);
+        let max_cross = self.cross_axis_length(&max_size);
 
-        let mut child_sizes: Vec<Size> = self
+        let mut child_sizes: Vec<Size> = vec![Size::zero(); self.children.len()];
+        let loose_constraints = self.child_constraints(0., max_main, max_cross);
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if !child.does_expand() {
+                child_sizes[i] = child.set_layout(&loose_constraints, platform);
+            }
+        }
+        self.share_space_between_expanding_children(&mut child_sizes, &constraints.biggest());
+        let expanding_child_constraints: Vec<Option<BoxConstraints>> = self
             .children
             .iter()
-            .map(|child| child.get_size(&self.widget_data.available_space))
+            .zip(&child_sizes)
+            .map(|(child, child_size)| {
+                if !child.does_expand() {
+                    return None;
+                }
+                let main = self.main_axis_length(child_size);
+                return Some(self.child_constraints(main, main, max_cross));
+            })
             .collect();
+        for ((child, child_size), child_constraints) in self
+            .children
+            .iter_mut()
+            .zip(&mut child_sizes)
+            .zip(expanding_child_constraints)
+        {
+            if let Some(child_constraints) = child_constraints {
+                *child_size = child.set_layout(&child_constraints, platform);
+            }
+        }
 
-        let mut total_child_size = child_sizes
+        let total_child_size = child_sizes
             .iter()
             .map(|x| x.clone())
             .reduce(match self.direction {
@@ -110,60 +275,35 @@ impl Widget for List {
             })
             .unwrap();
 
-        for (i, child) in self.children.iter().enumerate() {
-            // We assume that there is at most one expanding child
-            if child.does_expand() {
-                match &self.direction {
-                    ListDirection::Column => {
-                        child_sizes[i].height = self.widget_data.available_space.height
-                            - total_child_size.height
-                            + child_sizes[i].height;
-                        total_child_size.height = self.widget_data.available_space.height;
-                    }
-                    ListDirection::Row => {
-                        child_sizes[i].width = self.widget_data.available_space.width
-                            - total_child_size.width
-                            + child_sizes[i].width;
-                        total_child_size.width = self.widget_data.available_space.width;
-                    }
-                }
-                break;
-            }
-        }
-
         let total_child_main_axis_size: f64;
         let total_child_cross_axis_size: f64;
-        let main_axis_available_space: f64;
-        let cross_axis_available_space: f64;
 
         match self.direction {
             ListDirection::Column => {
                 total_child_main_axis_size = total_child_size.height;
                 total_child_cross_axis_size = total_child_size.width;
-                main_axis_available_space = self.widget_data.available_space.height;
-                cross_axis_available_space = self.widget_data.available_space.width;
             }
             ListDirection::Row => {
                 total_child_main_axis_size = total_child_size.width;
                 total_child_cross_axis_size = total_child_size.height;
-                main_axis_available_space = self.widget_data.available_space.width;
-                cross_axis_available_space = self.widget_data.available_space.height;
             }
         };
+        // A list can't fill an axis which is unbounded, so it just fits its
+        // children along it instead
         let main_axis_real_size = match self.main_axis_size {
-            MainAxisSize::Max => main_axis_available_space,
-            MainAxisSize::Min => total_child_main_axis_size,
+            MainAxisSize::Max if max_main.is_finite() => max_main,
+            _ => total_child_main_axis_size.max(self.main_axis_length(&min_size)),
         };
 
         let cross_axis_real_size = match self.cross_axis_size {
-            CrossAxisSize::Max => cross_axis_available_space,
-            CrossAxisSize::Min => total_child_cross_axis_size,
+            CrossAxisSize::Max if max_cross.is_finite() => max_cross,
+            _ => total_child_cross_axis_size.max(self.cross_axis_length(&min_size)),
         };
 
         let main_axis_extra_space: f64 = main_axis_real_size - total_child_main_axis_size;
 
         let mut used_main_axis_space = 0.;
-        let child_positions: Vec<(Position, Size)> = (0..self.children.len())
+        let child_positions: Vec<Position> = (0..self.children.len())
             .map(|i| {
                 let child_size = &child_sizes[i];
                 let child_main_axis_size: f64;
@@ -216,100 +356,25 @@ impl Widget for List {
                 let x: f64;
                 let y: f64;
 
-                let real_child_size: Size;
-
                 match self.direction {
                     ListDirection::Column => {
                         x = cross_axis_pos;
                         y = main_axis_pos;
-                        real_child_size = Size {
-                            width: child_cross_axis_size,
-                            height: child_main_axis_size,
-                        };
                     }
                     ListDirection::Row => {
                         x = main_axis_pos;
                         y = cross_axis_pos;
-                        real_child_size = Size {
-                            width: child_main_axis_size,
-                            height: child_cross_axis_size,
-                        };
                     }
                 }
 
                 used_main_axis_space += child_main_axis_size + used_main_axis_extra_space;
-                return (Position { x, y }, real_child_size);
+                return Position { x, y };
             })
             .collect();
 
-        for (i, child) in self.children.iter_mut().enumerate() {
-            let (position, real_child_size) = &child_positions[i];
-
-            child.set_layout(position.clone(), real_child_size.clone());
+        for (child, position) in self.children.iter_mut().zip(child_positions) {
+            child.set_position(position);
         }
-    }
-
-    fn draw(&self, parent_position: Position, platform: &dyn Platform) -> () {
-        for child in &self.children {
-            child.draw(
-                parent_position.clone() + self.widget_data.position.clone(),
-                platform,
-            );
-        }
-    }
-
-    fn get_size(&self, available_space: &Size) -> Size {
-        let child_sizes = self
-            .children
-            .iter()
-            .map(|child| child.get_size(available_space));
-
-        let total_child_size = child_sizes
-            .clone()
-            .reduce(match self.direction {
-                ListDirection::Column => |a: Size, s: Size| Size {
-                    width: if a.width >= s.width { a.width } else { s.width },
-                    height: a.height + s.height,
-                },
-                ListDirection::Row => |a: Size, s: Size| Size {
-                    width: a.width + s.width,
-                    height: if a.height >= s.height {
-                        a.height
-                    } else {
-                        s.height
-                    },
-                },
-            })
-            .unwrap();
-
-        let total_child_main_axis_size: f64;
-        let total_child_cross_axis_size: f64;
-        let main_axis_available_space: f64;
-        let cross_axis_available_space: f64;
-
-        match self.direction {
-            ListDirection::Column => {
-                total_child_main_axis_size = total_child_size.height;
-                total_child_cross_axis_size = total_child_size.width;
-                main_axis_available_space = available_space.height;
-                cross_axis_available_space = available_space.width;
-            }
-            ListDirection::Row => {
-                total_child_main_axis_size = total_child_size.width;
-                total_child_cross_axis_size = total_child_size.height;
-                main_axis_available_space = available_space.width;
-                cross_axis_available_space = available_space.height;
-            }
-        };
-        let main_axis_real_size = match self.main_axis_size {
-            MainAxisSize::Max => main_axis_available_space,
-            MainAxisSize::Min => total_child_main_axis_size,
-        };
-
-        let cross_axis_real_size = match self.cross_axis_size {
-            CrossAxisSize::Max => cross_axis_available_space,
-            CrossAxisSize::Min => total_child_cross_axis_size,
-        };
 
         return match self.direction {
             ListDirection::Column => Size {
@@ -323,12 +388,13 @@ impl Widget for List {
         };
     }
 
-    fn get_width(&self, available_space: &Size) -> f64 {
-        return self.get_size(available_space).width;
-    }
-
-    fn get_height(&self, available_space: &Size) -> f64 {
-        return self.get_size(available_space).height;
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        for child in &self.children {
+            child.paint(
+                parent_position.clone() + self.widget_data.position.clone(),
+                display_list,
+            );
+        }
     }
 
     fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
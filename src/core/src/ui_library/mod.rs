@@ -1,18 +1,34 @@
+pub mod align;
+pub mod aspect_ratio;
 pub mod compound;
+pub mod constrained_box;
+pub mod constraints;
 pub mod container;
+pub mod decoration;
 pub mod expanded;
+pub mod fractionally_sized_box;
+pub mod grid;
+pub mod image;
 pub mod list;
 pub mod padding;
+pub mod provider;
+pub mod scroll_view;
 pub mod sized_box;
+pub mod stack;
+pub mod text;
+pub mod theme;
+pub mod transformed;
+pub mod wrap;
 
 use derivative::Derivative;
 use key_segment::KeySegment;
 
-use std::{cell::RefCell, fmt::Debug, rc::Weak};
+use std::{cell::RefCell, collections::HashSet, fmt::Debug, rc::Weak};
 
+use self::{constraints::BoxConstraints, expanded::Flex};
 use crate::{
-    event_handlers::HandledEventInfo,
-    graphics::{Position, Size},
+    event_handlers::{HandledEventInfo, KeyboardEvent, MouseButtonEvent, PointerEvent, WheelEvent},
+    graphics::{display_list::DisplayList, Position, Rect, Size, Transform},
     platform::Platform,
     state::{State, StateManager},
 };
@@ -22,6 +38,23 @@ pub struct Key {
     pub value: String,
 }
 
+impl Key {
+    /// Whether `other` belongs to a widget somewhere below this one
+    pub fn is_ancestor_of(&self, other: &Key) -> bool {
+        return other.value.len() > self.value.len()
+            && other.value.starts_with(&self.value)
+            && other.value[self.value.len()..].starts_with('/');
+    }
+
+    /// The key above the app, where app-wide things like the theme are
+    /// provided
+    pub fn root() -> Self {
+        return Self {
+            value: String::new(),
+        };
+    }
+}
+
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct WidgetData {
@@ -29,7 +62,11 @@ pub struct WidgetData {
     #[derivative(Debug="ignore")]
     pub state_manager: Weak<RefCell<StateManager>>,
     pub position: Position,
-    pub available_space: Size,
+    pub constraints: BoxConstraints,
+    pub size: Size,
+    /// Whether the widget has to be laid out again even if its constraints
+    /// haven't changed
+    pub needs_layout: bool,
 }
 
 impl WidgetData {
@@ -38,7 +75,9 @@ impl WidgetData {
             key: None,
             state_manager: Weak::new(),
             position: Position::origin(),
-            available_space: Size::zero(),
+            constraints: BoxConstraints::tight(&Size::zero()),
+            size: Size::zero(),
+            needs_layout: true,
         };
     }
 }
@@ -51,7 +90,11 @@ pub struct CompoundWidgetData {
     pub state_manager: Weak<RefCell<StateManager>>,
     pub cached_build: Option<Box<dyn Widget>>,
     pub position: Position,
-    pub available_space: Size,
+    pub constraints: BoxConstraints,
+    pub size: Size,
+    /// Whether the widget has to be laid out again even if its constraints
+    /// haven't changed
+    pub needs_layout: bool,
 }
 
 impl CompoundWidgetData {
@@ -61,11 +104,107 @@ impl CompoundWidgetData {
             state_manager: Weak::new(),
             cached_build: None,
             position: Position::origin(),
-            available_space: Size::zero(),
+            constraints: BoxConstraints::tight(&Size::zero()),
+            size: Size::zero(),
+            needs_layout: true,
+        };
+    }
+}
+
+/// What happens to the parts of a widget's children which don't fit inside it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Overflow {
+    /// They are drawn anyway, on top of whatever is around the widget
+    Visible,
+    /// They are cut off, and can't be clicked on either
+    Clip,
+}
+
+impl Overflow {
+    /// The clip rect for a widget occupying `bounds`
+    pub fn clip(&self, bounds: Rect) -> Option<Rect> {
+        return match self {
+            Overflow::Visible => None,
+            Overflow::Clip => Some(bounds),
         };
     }
 }
 
+/// Where a child goes within a larger area, as a fraction of the space left
+/// over along each axis: 0 puts it at the start, 0.5 in the middle and 1 at
+/// the end
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alignment {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Alignment {
+    pub fn new(x: f64, y: f64) -> Self {
+        return Self { x, y };
+    }
+
+    pub fn top_left() -> Self {
+        return Self::new(0., 0.);
+    }
+
+    pub fn top_center() -> Self {
+        return Self::new(0.5, 0.);
+    }
+
+    pub fn top_right() -> Self {
+        return Self::new(1., 0.);
+    }
+
+    pub fn center_left() -> Self {
+        return Self::new(0., 0.5);
+    }
+
+    pub fn center() -> Self {
+        return Self::new(0.5, 0.5);
+    }
+
+    pub fn center_right() -> Self {
+        return Self::new(1., 0.5);
+    }
+
+    pub fn bottom_left() -> Self {
+        return Self::new(0., 1.);
+    }
+
+    pub fn bottom_center() -> Self {
+        return Self::new(0.5, 1.);
+    }
+
+    pub fn bottom_right() -> Self {
+        return Self::new(1., 1.);
+    }
+
+    /// The position of something of size `inner` aligned within `outer`
+    pub fn offset(&self, outer: &Size, inner: &Size) -> Position {
+        return Position {
+            x: (outer.width - inner.width) * self.x,
+            y: (outer.height - inner.height) * self.y,
+        };
+    }
+}
+
+/// Calls `draw`, clipping everything it adds to the display list to `clip`
+pub fn draw_clipped(
+    clip: Option<&Rect>,
+    display_list: &mut DisplayList,
+    draw: impl FnOnce(&mut DisplayList),
+) {
+    match clip {
+        Some(clip) => {
+            display_list.push_clip(clip);
+            draw(display_list);
+            display_list.pop_clip();
+        }
+        None => draw(display_list),
+    }
+}
+
 pub trait Widget: Debug + KeySegment {
     fn get_key(&self) -> &Key;
     fn set_key(&mut self, key: Key) -> ();
@@ -74,17 +213,14 @@ pub trait Widget: Debug + KeySegment {
 
     fn get_position(&self) -> &Position;
     fn set_position(&mut self, position: Position) -> ();
-    fn get_available_space(&self) -> &Size;
-    fn set_available_space(&mut self, available_space: Size) -> ();
-
-    fn get_width(&self, available_space: &Size) -> f64;
-    fn get_height(&self, available_space: &Size) -> f64;
-    fn get_size(&self, available_space: &Size) -> Size {
-        return Size {
-            width: self.get_width(available_space),
-            height: self.get_height(available_space),
-        };
-    }
+    fn get_constraints(&self) -> &BoxConstraints;
+    fn set_constraints(&mut self, constraints: BoxConstraints) -> ();
+    /// The size the widget chose at the last layout
+    fn get_size(&self) -> &Size;
+    fn set_size(&mut self, size: Size) -> ();
+    fn get_needs_layout(&self) -> bool;
+    fn set_needs_layout(&mut self, needs_layout: bool) -> ();
+
     fn does_expand(&self) -> bool {
         for child in self.get_children() {
             if child.does_expand() {
@@ -93,12 +229,92 @@ pub trait Widget: Debug + KeySegment {
         }
         return false;
     }
+    /// How an expanding widget shares leftover space with its siblings. By
+    /// default this comes from the expanding widget inside it.
+    fn get_flex(&self) -> Flex {
+        for child in self.get_children() {
+            if child.does_expand() {
+                return child.get_flex();
+            }
+        }
+        return Flex::new();
+    }
 
     fn get_children(&self) -> Vec<&dyn Widget>;
     fn get_children_mut(&mut self) -> Vec<&mut dyn Widget>;
 
-    fn draw(&self, parent_position: Position, platform: &dyn Platform) -> ();
-    fn set_layout(&mut self, position: Position, available_space: Size);
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> ();
+    /// Draws the widget unless only part of the screen is being repainted
+    /// and the widget draws entirely outside of it. Parents draw their
+    /// children with this rather than `draw`, so that whole subtrees are
+    /// skipped.
+    fn paint(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        if display_list.has_visible_region() {
+            let bounds = self.get_paint_bounds();
+            let bounds = Rect::new(
+                bounds.position + parent_position.clone() + self.get_position().clone(),
+                bounds.size,
+            );
+            if !display_list.is_visible(&bounds) {
+                return;
+            }
+        }
+        self.draw(parent_position, display_list);
+    }
+    /// The rect, in the same coordinates as `parent_position`, outside of
+    /// which this widget and its children are neither drawn nor hit by the
+    /// pointer. Widgets which clip
+    /// must apply it themselves in `draw`.
+    fn get_clip(&self, _parent_position: &Position) -> Option<Rect> {
+        return None;
+    }
+    /// The area this widget and its children draw to, in the widget's own
+    /// coordinates. This is its own rect unless something inside draws
+    /// outside of it, like a shadow.
+    fn get_paint_bounds(&self) -> Rect {
+        let mut bounds = Rect::from_size(self.get_size().clone());
+        for child in self.get_children() {
+            let child_bounds = child.get_paint_bounds();
+            bounds = bounds.union(&Rect::new(
+                child_bounds.position + child.get_position().clone(),
+                child_bounds.size,
+            ));
+        }
+        return bounds;
+    }
+    /// Lays the widget and its children out within `constraints` and
+    /// returns the size it chose. Parents call this once per child per
+    /// layout pass and then place the child with `set_position`. The last
+    /// layout is reused if the constraints are the same and nothing inside
+    /// the widget has changed since.
+    fn set_layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        if !self.get_needs_layout() && self.get_constraints() == constraints {
+            return self.get_size().clone();
+        }
+        self.set_constraints(constraints.clone());
+        let size = constraints.constrain(&self.layout(constraints, platform));
+        self.set_size(size.clone());
+        self.set_needs_layout(false);
+        return size;
+    }
+
+    /// Marks the widget with `key` and every widget above it as needing
+    /// layout, so that the next layout pass reaches it instead of reusing
+    /// their old layouts. Nothing else is laid out again unless its
+    /// constraints change.
+    fn invalidate_layout_for_key(&mut self, key: &Key) -> () {
+        if self.get_key() != key && !self.get_key().is_ancestor_of(key) {
+            return;
+        }
+        self.set_needs_layout(true);
+        for child in self.get_children_mut() {
+            child.invalidate_layout_for_key(key);
+        }
+    }
+    /// Picks a size within `constraints`, laying out and positioning the
+    /// children on the way. Anything outside the constraints is clamped by
+    /// `set_layout`, which is what everyone else should call.
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size;
 
     fn get_cached_build(&self) -> Option<&dyn Widget> {
         return None;
@@ -114,16 +330,51 @@ pub trait Widget: Debug + KeySegment {
         return None;
     }
 
+    /// Builds the widget and everything below it again. Afterwards the state
+    /// manager forgets the state, provided values and dependencies of the
+    /// widgets which the new build left out.
     fn rebuild(&mut self) -> () {
-        let build: Option<Box<dyn Widget>> = self.build();
-        self.set_cached_build(build);
         let my_key = self.get_key().clone();
         let state_manager = self.get_state_manager();
+        // Everything below looks its contexts up again while it's built
+        if let Some(x) = state_manager.upgrade() {
+            x.borrow_mut().forget_dependencies_from(&my_key);
+        }
+
+        self.set_needs_layout(true);
+        let build: Option<Box<dyn Widget>> = self.build();
+        self.set_cached_build(build);
         for (i, child) in self.get_children_mut().iter_mut().enumerate() {
             let mut new_parent_key_value = my_key.value.clone();
             new_parent_key_value.push_str(&format!("/{}", i));
             child.rebuild_with_key(new_parent_key_value, state_manager.clone());
         }
+
+        if let Some(x) = state_manager.upgrade() {
+            let mut live_keys: HashSet<Key> = HashSet::new();
+            for child in self.get_children() {
+                child.collect_keys(&mut live_keys);
+            }
+            x.borrow_mut().forget_removed_below(&my_key, &live_keys);
+        }
+    }
+
+    /// Rebuilds the widget with `key` if it's this one or somewhere below it.
+    /// Returns whether it was found.
+    fn rebuild_key(&mut self, key: &Key) -> bool {
+        if self.get_key() == key {
+            self.rebuild();
+            return true;
+        }
+        if !self.get_key().is_ancestor_of(key) {
+            return false;
+        }
+        for child in self.get_children_mut() {
+            if child.rebuild_key(key) {
+                return true;
+            }
+        }
+        return false;
     }
 
     fn rebuild_with_key(
@@ -136,6 +387,7 @@ pub trait Widget: Debug + KeySegment {
         my_key.push_str(&format!("/{}", self.key_segment()));
         let my_key = Key { value: my_key };
         self.set_key(my_key.clone());
+        self.set_needs_layout(true);
         let build: Option<Box<dyn Widget>> = self.build();
         self.set_cached_build(build);
         for (i, child) in self.get_children_mut().iter_mut().enumerate() {
@@ -154,18 +406,107 @@ pub trait Widget: Debug + KeySegment {
         return false;
     }
 
-    fn on_mouse_move_anywhere(
+    fn on_mouse_button(
         &mut self,
+        _event: &MouseButtonEvent,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        return false;
+    }
+
+    fn on_mouse_enter(
+        &mut self,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> () {
+    }
+
+    fn on_mouse_leave(
+        &mut self,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> () {
+    }
+
+    fn on_mouse_wheel(
+        &mut self,
+        _event: &WheelEvent,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        return false;
+    }
+
+    /// Positions in `event` are relative to the widget's top-left corner
+    fn on_pointer_event(
+        &mut self,
+        event: &PointerEvent,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        return match event {
+            PointerEvent::Move(mouse_pos) => {
+                self.on_mouse_move(mouse_pos, platform, handled_event_info)
+            }
+            PointerEvent::Button(event) => {
+                self.on_mouse_button(event, platform, handled_event_info)
+            }
+            PointerEvent::Enter(_) => {
+                self.on_mouse_enter(platform, handled_event_info);
+                false
+            }
+            PointerEvent::Leave(_) => {
+                self.on_mouse_leave(platform, handled_event_info);
+                false
+            }
+            PointerEvent::Wheel(event) => self.on_mouse_wheel(event, platform, handled_event_info),
+        };
+    }
+
+    /// Collects the keys of every widget containing `position`, parents
+    /// before their children
+    fn hit_test(
+        &self,
         parent_position: Position,
-        mouse_pos: &Position,
+        position: &Position,
+        platform: &dyn Platform,
+        hits: &mut Vec<Key>,
+    ) {
+        if let Some(clip) = self.get_clip(&parent_position) {
+            if !clip.contains(position) {
+                return;
+            }
+        }
+        let my_pos = self.get_position().clone() + parent_position;
+        let my_size = self.get_size().clone();
+        if Rect::new(my_pos.clone(), my_size).contains(position) {
+            hits.push(self.get_key().clone());
+        }
+        for child in self.get_children() {
+            child.hit_test(my_pos.clone(), position, platform, hits);
+        }
+    }
+
+    /// Offers the event to every widget under the pointer, deepest first,
+    /// until one of them captures it
+    fn on_pointer_event_anywhere(
+        &mut self,
+        parent_position: Position,
+        event: &PointerEvent,
         platform: &dyn Platform,
         handled_event_info: &mut HandledEventInfo,
     ) -> bool {
+        if let Some(clip) = self.get_clip(&parent_position) {
+            if !clip.contains(event.position()) {
+                return false;
+            }
+        }
         let my_relative_pos = self.get_position().clone();
         for child in self.get_children_mut() {
-            let captured = child.on_mouse_move_anywhere(
+            let captured = child.on_pointer_event_anywhere(
                 parent_position.clone() + my_relative_pos.clone(),
-                mouse_pos,
+                event,
                 platform,
                 handled_event_info,
             );
@@ -174,16 +515,172 @@ pub trait Widget: Debug + KeySegment {
             }
         }
         let my_pos = my_relative_pos + parent_position;
-        let my_size = self.get_size(self.get_available_space());
-        if my_pos.x <= mouse_pos.x
-            && mouse_pos.x <= my_pos.x + my_size.width
-            && my_pos.y <= mouse_pos.y
-            && mouse_pos.y <= my_pos.y + my_size.height
-        {
-            return self.on_mouse_move(mouse_pos, platform, handled_event_info);
+        let my_size = self.get_size().clone();
+        if Rect::new(my_pos.clone(), my_size).contains(event.position()) {
+            return dispatch_pointer_event(self, my_pos, event, platform, handled_event_info);
         }
         return false;
     }
+
+    /// Delivers the event straight to the widget with the given key, wherever
+    /// the pointer is. This is how a drag stays with the widget it started on.
+    fn on_pointer_event_for_key(
+        &mut self,
+        key: &Key,
+        parent_position: Position,
+        event: &PointerEvent,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        let my_pos = self.get_position().clone() + parent_position;
+        if self.get_key() == key {
+            return dispatch_pointer_event(self, my_pos, event, platform, handled_event_info);
+        }
+        for child in self.get_children_mut() {
+            if child.get_key() == key || child.get_key().is_ancestor_of(key) {
+                return child.on_pointer_event_for_key(
+                    key,
+                    my_pos,
+                    event,
+                    platform,
+                    handled_event_info,
+                );
+            }
+        }
+        return false;
+    }
+
+    fn is_focusable(&self) -> bool {
+        return false;
+    }
+
+    fn on_focus_change(
+        &mut self,
+        _is_focused: bool,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> () {
+    }
+
+    fn on_keyboard_event(
+        &mut self,
+        _event: &KeyboardEvent,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        return false;
+    }
+
+    /// Delivers the event to the widget with the given key and then to each
+    /// of its ancestors in turn, stopping as soon as one captures it
+    fn on_keyboard_event_for_key(
+        &mut self,
+        key: &Key,
+        parent_position: Position,
+        event: &KeyboardEvent,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        let my_pos = self.get_position().clone() + parent_position;
+        for child in self.get_children_mut() {
+            if child.get_key() == key || child.get_key().is_ancestor_of(key) {
+                let captured = child.on_keyboard_event_for_key(
+                    key,
+                    my_pos.clone(),
+                    event,
+                    platform,
+                    handled_event_info,
+                );
+                if captured {
+                    return true;
+                }
+                break;
+            }
+        }
+        return dispatch_event(self, my_pos, handled_event_info, |widget, info| {
+            widget.on_keyboard_event(event, platform, info)
+        });
+    }
+
+    fn set_focus_for_key(
+        &mut self,
+        key: &Key,
+        is_focused: bool,
+        parent_position: Position,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> () {
+        let my_pos = self.get_position().clone() + parent_position;
+        if self.get_key() == key {
+            dispatch_event(self, my_pos, handled_event_info, |widget, info| {
+                widget.on_focus_change(is_focused, platform, info);
+                false
+            });
+            return;
+        }
+        for child in self.get_children_mut() {
+            if child.get_key() == key || child.get_key().is_ancestor_of(key) {
+                child.set_focus_for_key(key, is_focused, my_pos, platform, handled_event_info);
+                return;
+            }
+        }
+    }
+
+    /// Collects the keys of this widget and everything below it
+    fn collect_keys(&self, keys: &mut HashSet<Key>) -> () {
+        keys.insert(self.get_key().clone());
+        for child in self.get_children() {
+            child.collect_keys(keys);
+        }
+    }
+
+    /// Collects the keys of every focusable widget in tab order
+    fn collect_focusable_keys(&self, keys: &mut Vec<Key>) -> () {
+        if self.is_focusable() {
+            keys.push(self.get_key().clone());
+        }
+        for child in self.get_children() {
+            child.collect_focusable_keys(keys);
+        }
+    }
+}
+
+/// Calls `handler` on the widget, then converts any dirty regions it marked
+/// into screen coordinates and records whether it captured the event
+fn dispatch_event<W: Widget + ?Sized>(
+    widget: &mut W,
+    widget_position: Position,
+    handled_event_info: &mut HandledEventInfo,
+    handler: impl FnOnce(&mut W, &mut HandledEventInfo) -> bool,
+) -> bool {
+    let first_new_dirty_region = handled_event_info.dirty_regions.len();
+    let captured = handler(widget, handled_event_info);
+    handled_event_info.transform_dirty_regions_from(
+        first_new_dirty_region,
+        &Transform::translate(widget_position),
+    );
+    if captured {
+        handled_event_info.captured_by = Some(widget.get_key().clone());
+    }
+    return captured;
+}
+
+/// Calls `on_pointer_event` on the widget with the event's position moved
+/// into the widget's own coordinates
+fn dispatch_pointer_event<W: Widget + ?Sized>(
+    widget: &mut W,
+    widget_position: Position,
+    event: &PointerEvent,
+    platform: &dyn Platform,
+    handled_event_info: &mut HandledEventInfo,
+) -> bool {
+    let local_event = event.transformed(&Transform::translate(widget_position.clone()).inverse());
+    return dispatch_event(
+        widget,
+        widget_position,
+        handled_event_info,
+        |widget, info| widget.on_pointer_event(&local_event, platform, info),
+    );
 }
 
 pub trait CompoundWidget: Widget {
@@ -194,8 +691,12 @@ pub trait CompoundWidget: Widget {
 
     fn get_position(&self) -> &Position;
     fn set_position(&mut self, position: Position) -> ();
-    fn get_available_space(&self) -> &Size;
-    fn set_available_space(&mut self, available_space: Size) -> ();
+    fn get_constraints(&self) -> &BoxConstraints;
+    fn set_constraints(&mut self, constraints: BoxConstraints) -> ();
+    fn get_size(&self) -> &Size;
+    fn set_size(&mut self, size: Size) -> ();
+    fn get_needs_layout(&self) -> bool;
+    fn set_needs_layout(&mut self, needs_layout: bool) -> ();
 
     fn get_cached_build(&self) -> Option<&dyn Widget>;
     fn get_cached_build_mut(&mut self) -> Option<&mut dyn Widget>;
@@ -211,6 +712,59 @@ pub trait CompoundWidget: Widget {
     ) -> bool {
         return false;
     }
+
+    fn on_mouse_button(
+        &mut self,
+        _event: &MouseButtonEvent,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        return false;
+    }
+
+    fn on_mouse_enter(
+        &mut self,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> () {
+    }
+
+    fn on_mouse_leave(
+        &mut self,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> () {
+    }
+
+    fn on_mouse_wheel(
+        &mut self,
+        _event: &WheelEvent,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        return false;
+    }
+
+    fn is_focusable(&self) -> bool {
+        return false;
+    }
+
+    fn on_focus_change(
+        &mut self,
+        _is_focused: bool,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> () {
+    }
+
+    fn on_keyboard_event(
+        &mut self,
+        _event: &KeyboardEvent,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        return false;
+    }
 }
 
 impl<T: CompoundWidget> Widget for T {
@@ -235,12 +789,28 @@ impl<T: CompoundWidget> Widget for T {
         return CompoundWidget::set_position(self, position);
     }
 
-    fn get_available_space(&self) -> &Size {
-        return CompoundWidget::get_available_space(self);
+    fn get_constraints(&self) -> &BoxConstraints {
+        return CompoundWidget::get_constraints(self);
     }
 
-    fn set_available_space(&mut self, available_space: Size) -> () {
-        return CompoundWidget::set_available_space(self, available_space);
+    fn set_constraints(&mut self, constraints: BoxConstraints) -> () {
+        return CompoundWidget::set_constraints(self, constraints);
+    }
+
+    fn get_size(&self) -> &Size {
+        return CompoundWidget::get_size(self);
+    }
+
+    fn set_size(&mut self, size: Size) -> () {
+        return CompoundWidget::set_size(self, size);
+    }
+
+    fn get_needs_layout(&self) -> bool {
+        return CompoundWidget::get_needs_layout(self);
+    }
+
+    fn set_needs_layout(&mut self, needs_layout: bool) -> () {
+        return CompoundWidget::set_needs_layout(self, needs_layout);
     }
 
     fn get_cached_build(&self) -> Option<&dyn Widget> {
@@ -269,46 +839,82 @@ impl<T: CompoundWidget> Widget for T {
         return vec![Widget::get_cached_build(self).unwrap()];
     }
 
-    fn set_layout(&mut self, position: Position, available_space: Size) {
-        Widget::set_position(self, position);
-        Widget::set_available_space(self, available_space.clone());
-        Widget::get_cached_build_mut(self)
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        return Widget::get_cached_build_mut(self)
             .unwrap()
-            .set_layout(Position::origin(), available_space);
+            .set_layout(constraints, platform);
     }
 
-    fn draw(&self, parent_position: Position, platform: &dyn Platform) -> () {
-        Widget::get_cached_build(self).unwrap().draw(
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        Widget::get_cached_build(self).unwrap().paint(
             parent_position + Widget::get_position(self).clone(),
-            platform,
+            display_list,
         );
     }
 
-    fn get_height(&self, available_space: &Size) -> f64 {
-        return Widget::get_cached_build(self)
-            .unwrap()
-            .get_height(available_space);
+    fn on_mouse_move(
+        &mut self,
+        mouse_pos: &Position,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        return CompoundWidget::on_mouse_move(self, mouse_pos, platform, handled_event_info);
     }
 
-    fn get_width(&self, available_space: &Size) -> f64 {
-        return Widget::get_cached_build(self)
-            .unwrap()
-            .get_width(available_space);
+    fn on_mouse_button(
+        &mut self,
+        event: &MouseButtonEvent,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        return CompoundWidget::on_mouse_button(self, event, platform, handled_event_info);
     }
 
-    fn get_size(&self, available_space: &Size) -> Size {
-        return Widget::get_cached_build(self)
-            .unwrap()
-            .get_size(available_space);
+    fn on_mouse_enter(
+        &mut self,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> () {
+        CompoundWidget::on_mouse_enter(self, platform, handled_event_info);
     }
 
-    fn on_mouse_move(
+    fn on_mouse_leave(
         &mut self,
-        mouse_pos: &Position,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> () {
+        CompoundWidget::on_mouse_leave(self, platform, handled_event_info);
+    }
+
+    fn on_mouse_wheel(
+        &mut self,
+        event: &WheelEvent,
         platform: &dyn Platform,
         handled_event_info: &mut HandledEventInfo,
     ) -> bool {
-        return CompoundWidget::on_mouse_move(self, mouse_pos, platform, handled_event_info);
+        return CompoundWidget::on_mouse_wheel(self, event, platform, handled_event_info);
+    }
+
+    fn is_focusable(&self) -> bool {
+        return CompoundWidget::is_focusable(self);
+    }
+
+    fn on_focus_change(
+        &mut self,
+        is_focused: bool,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> () {
+        CompoundWidget::on_focus_change(self, is_focused, platform, handled_event_info);
+    }
+
+    fn on_keyboard_event(
+        &mut self,
+        event: &KeyboardEvent,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        return CompoundWidget::on_keyboard_event(self, event, platform, handled_event_info);
     }
 }
 
@@ -337,8 +943,12 @@ pub trait StatefulWidget: CompoundWidget {
 
     fn get_position(&self) -> &Position;
     fn set_position(&mut self, position: Position) -> ();
-    fn get_available_space(&self) -> &Size;
-    fn set_available_space(&mut self, available_space: Size) -> ();
+    fn get_constraints(&self) -> &BoxConstraints;
+    fn set_constraints(&mut self, constraints: BoxConstraints) -> ();
+    fn get_size(&self) -> &Size;
+    fn set_size(&mut self, size: Size) -> ();
+    fn get_needs_layout(&self) -> bool;
+    fn set_needs_layout(&mut self, needs_layout: bool) -> ();
 
     fn get_cached_build(&self) -> Option<&dyn Widget>;
     fn get_cached_build_mut(&mut self) -> Option<&mut dyn Widget>;
@@ -357,6 +967,91 @@ pub trait StatefulWidget: CompoundWidget {
     ) -> StatefulWidgetEventHandlerInfo {
         return StatefulWidgetEventHandlerInfo::new();
     }
+
+    fn on_mouse_button(
+        &mut self,
+        _state: &mut Self::T,
+        _event: &MouseButtonEvent,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> StatefulWidgetEventHandlerInfo {
+        return StatefulWidgetEventHandlerInfo::new();
+    }
+
+    fn on_mouse_enter(
+        &mut self,
+        _state: &mut Self::T,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> StatefulWidgetEventHandlerInfo {
+        return StatefulWidgetEventHandlerInfo::new();
+    }
+
+    fn on_mouse_leave(
+        &mut self,
+        _state: &mut Self::T,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> StatefulWidgetEventHandlerInfo {
+        return StatefulWidgetEventHandlerInfo::new();
+    }
+
+    fn on_mouse_wheel(
+        &mut self,
+        _state: &mut Self::T,
+        _event: &WheelEvent,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> StatefulWidgetEventHandlerInfo {
+        return StatefulWidgetEventHandlerInfo::new();
+    }
+
+    fn is_focusable(&self) -> bool {
+        return false;
+    }
+
+    fn on_focus_change(
+        &mut self,
+        _state: &mut Self::T,
+        _is_focused: bool,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> StatefulWidgetEventHandlerInfo {
+        return StatefulWidgetEventHandlerInfo::new();
+    }
+
+    fn on_keyboard_event(
+        &mut self,
+        _state: &mut Self::T,
+        _event: &KeyboardEvent,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> StatefulWidgetEventHandlerInfo {
+        return StatefulWidgetEventHandlerInfo::new();
+    }
+}
+
+/// Rebuilds a stateful widget after its state changed and lays the new subtree
+/// out within the same constraints as the old one. Only the area the widget
+/// drew to before and after is marked as dirty, and if its size changed its
+/// ancestors need a relayout too.
+fn rebuild_and_mark_dirty<U: StatefulWidget>(
+    widget: &mut U,
+    platform: &dyn Platform,
+    handled_event_info: &mut HandledEventInfo,
+) {
+    let constraints = Widget::get_constraints(widget).clone();
+    let old_size = Widget::get_size(widget).clone();
+    let old_paint_bounds = Widget::get_paint_bounds(widget);
+
+    widget.rebuild();
+    let new_size = Widget::set_layout(widget, &constraints, platform);
+
+    if new_size != old_size {
+        handled_event_info.request_relayout(Widget::get_key(widget).clone());
+    }
+    handled_event_info.mark_dirty(old_paint_bounds);
+    handled_event_info.mark_dirty(Widget::get_paint_bounds(widget));
 }
 
 impl<U: StatefulWidget> CompoundWidget for U {
@@ -381,12 +1076,28 @@ impl<U: StatefulWidget> CompoundWidget for U {
         return StatefulWidget::set_position(self, position);
     }
 
-    fn get_available_space(&self) -> &Size {
-        return StatefulWidget::get_available_space(self);
+    fn get_constraints(&self) -> &BoxConstraints {
+        return StatefulWidget::get_constraints(self);
+    }
+
+    fn set_constraints(&mut self, constraints: BoxConstraints) -> () {
+        return StatefulWidget::set_constraints(self, constraints);
+    }
+
+    fn get_size(&self) -> &Size {
+        return StatefulWidget::get_size(self);
+    }
+
+    fn set_size(&mut self, size: Size) -> () {
+        return StatefulWidget::set_size(self, size);
     }
 
-    fn set_available_space(&mut self, available_space: Size) -> () {
-        return StatefulWidget::set_available_space(self, available_space);
+    fn get_needs_layout(&self) -> bool {
+        return StatefulWidget::get_needs_layout(self);
+    }
+
+    fn set_needs_layout(&mut self, needs_layout: bool) -> () {
+        return StatefulWidget::set_needs_layout(self, needs_layout);
     }
 
     fn get_cached_build(&self) -> Option<&dyn Widget> {
@@ -403,10 +1114,15 @@ impl<U: StatefulWidget> CompoundWidget for U {
 
     fn build(&self) -> Box<dyn Widget> {
         let state_manager_rc = Weak::upgrade(&CompoundWidget::get_state_manager(self)).unwrap();
-        let mut state_manager = (*state_manager_rc).borrow_mut();
-        let state =
-            state_manager.get_state(CompoundWidget::get_key(self), &|| self.state_factory());
-        return StatefulWidget::build(self, state);
+        let key = CompoundWidget::get_key(self);
+        // The state is taken out rather than borrowed so that `build` can use
+        // the state manager too, e.g. to look up a `Provider`
+        let mut state = state_manager_rc
+            .borrow_mut()
+            .take_state(key, &|| self.state_factory());
+        let build = StatefulWidget::build(self, &mut state);
+        state_manager_rc.borrow_mut().return_state(key, state);
+        return build;
     }
 
     fn on_mouse_move(
@@ -415,24 +1131,103 @@ impl<U: StatefulWidget> CompoundWidget for U {
         platform: &dyn Platform,
         handled_event_info: &mut HandledEventInfo,
     ) -> bool {
-        let handler_info: StatefulWidgetEventHandlerInfo;
-        {
-            let state_manager_rc = Weak::upgrade(&CompoundWidget::get_state_manager(self)).unwrap();
-            let mut state_manager = (*state_manager_rc).borrow_mut();
-            let state =
-                state_manager.get_state(CompoundWidget::get_key(self), &|| self.state_factory());
+        return handle_stateful_event(self, platform, handled_event_info, |widget, state, info| {
+            StatefulWidget::on_mouse_move(widget, state, mouse_pos, platform, info)
+        });
+    }
+
+    fn on_mouse_button(
+        &mut self,
+        event: &MouseButtonEvent,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        return handle_stateful_event(self, platform, handled_event_info, |widget, state, info| {
+            StatefulWidget::on_mouse_button(widget, state, event, platform, info)
+        });
+    }
 
-            handler_info =
-                StatefulWidget::on_mouse_move(self, state, mouse_pos, platform, handled_event_info)
-        }
-        if handler_info.needs_rebuild {
-            self.rebuild();
-        }
+    fn on_mouse_enter(
+        &mut self,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> () {
+        handle_stateful_event(self, platform, handled_event_info, |widget, state, info| {
+            StatefulWidget::on_mouse_enter(widget, state, platform, info)
+        });
+    }
+
+    fn on_mouse_leave(
+        &mut self,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> () {
+        handle_stateful_event(self, platform, handled_event_info, |widget, state, info| {
+            StatefulWidget::on_mouse_leave(widget, state, platform, info)
+        });
+    }
+
+    fn on_mouse_wheel(
+        &mut self,
+        event: &WheelEvent,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        return handle_stateful_event(self, platform, handled_event_info, |widget, state, info| {
+            StatefulWidget::on_mouse_wheel(widget, state, event, platform, info)
+        });
+    }
+
+    fn is_focusable(&self) -> bool {
+        return StatefulWidget::is_focusable(self);
+    }
 
-        return handler_info.was_captured;
+    fn on_focus_change(
+        &mut self,
+        is_focused: bool,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> () {
+        handle_stateful_event(self, platform, handled_event_info, |widget, state, info| {
+            StatefulWidget::on_focus_change(widget, state, is_focused, platform, info)
+        });
+    }
+
+    fn on_keyboard_event(
+        &mut self,
+        event: &KeyboardEvent,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        return handle_stateful_event(self, platform, handled_event_info, |widget, state, info| {
+            StatefulWidget::on_keyboard_event(widget, state, event, platform, info)
+        });
     }
 }
 
+/// Runs one of a stateful widget's event handlers with its state taken out
+/// of the state manager, rebuilding afterwards if the handler asks for it.
+/// Returns whether the handler captured the event.
+fn handle_stateful_event<U: StatefulWidget>(
+    widget: &mut U,
+    platform: &dyn Platform,
+    handled_event_info: &mut HandledEventInfo,
+    handler: impl FnOnce(&mut U, &mut U::T, &mut HandledEventInfo) -> StatefulWidgetEventHandlerInfo,
+) -> bool {
+    let state_manager_rc = Weak::upgrade(&CompoundWidget::get_state_manager(widget)).unwrap();
+    let key = CompoundWidget::get_key(widget).clone();
+    let mut state = state_manager_rc
+        .borrow_mut()
+        .take_state(&key, &|| widget.state_factory());
+    let handler_info = handler(widget, &mut state, handled_event_info);
+    state_manager_rc.borrow_mut().return_state(&key, state);
+    if handler_info.needs_rebuild {
+        rebuild_and_mark_dirty(widget, platform, handled_event_info);
+    }
+
+    return handler_info.was_captured;
+}
+
 #[macro_export]
 macro_rules! widget_default_methods {
     () => {
@@ -463,12 +1258,28 @@ macro_rules! widget_default_methods {
             self.widget_data.position = position;
         }
     
-        fn get_available_space(&self) -> &Size {
-            return &self.widget_data.available_space;
+        fn get_constraints(&self) -> &BoxConstraints {
+            return &self.widget_data.constraints;
+        }
+    
+        fn set_constraints(&mut self, constraints: BoxConstraints) -> () {
+            self.widget_data.constraints = constraints;
+        }
+    
+        fn get_size(&self) -> &Size {
+            return &self.widget_data.size;
+        }
+    
+        fn set_size(&mut self, size: Size) -> () {
+            self.widget_data.size = size;
+        }
+    
+        fn get_needs_layout(&self) -> bool {
+            return self.widget_data.needs_layout;
         }
     
-        fn set_available_space(&mut self, available_space: Size) -> () {
-            self.widget_data.available_space = available_space;
+        fn set_needs_layout(&mut self, needs_layout: bool) -> () {
+            self.widget_data.needs_layout = needs_layout;
         }
     
     }
@@ -508,7 +1319,9 @@ macro_rules! widget_default_fields {
         #[derivative(Debug="ignore")]
         state_manager: Weak<RefCell<StateManager>>,
         position: Position,
-        available_space: Size,    
+        constraints: BoxConstraints,
+        size: Size,
+        needs_layout: bool,
     };
 }
 
@@ -1,13 +1,13 @@
 use std::{cell::RefCell, rc::Weak};
 
 use crate::{
-    graphics::{Position, Size},
+    graphics::{display_list::DisplayList, Position, Rect, Size},
     platform::Platform,
     state::StateManager,
     widget_default_methods,
 };
 
-use super::{Key, Widget, WidgetData};
+use super::{constraints::BoxConstraints, draw_clipped, Key, Overflow, Widget, WidgetData};
 
 use key_segment::KeySegment;
 use key_segment_derive::KeySegment;
@@ -45,6 +45,7 @@ pub struct Padding {
     widget_data: WidgetData,
     padding: Inset,
     child: Option<Box<dyn Widget>>,
+    overflow: Overflow,
 }
 
 impl Padding {
@@ -53,13 +54,19 @@ impl Padding {
             widget_data: WidgetData::new(),
             padding,
             child,
+            overflow: Overflow::Visible,
         });
     }
 
-    fn get_available_space_for_child(&self, available_space: &Size) -> Size {
+    pub fn with_overflow(mut self: Box<Self>, overflow: Overflow) -> Box<Self> {
+        self.overflow = overflow;
+        return self;
+    }
+
+    fn get_total_padding(&self) -> Size {
         return Size {
-            width: available_space.width - self.padding.left - self.padding.right,
-            height: available_space.height - self.padding.top - self.padding.bottom,
+            width: self.padding.left + self.padding.right,
+            height: self.padding.top + self.padding.bottom,
         };
     }
 }
@@ -67,47 +74,43 @@ impl Padding {
 impl Widget for Padding {
     widget_default_methods!();
 
-    fn set_layout(&mut self, position: Position, available_space: Size) {
-        self.widget_data.position = position;
-        self.widget_data.available_space = available_space;
-        let available_space_for_child =
-            self.get_available_space_for_child(&self.widget_data.available_space);
-        match &mut self.child {
-            Some(child) => child.set_layout(
-                Position {
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        let total_padding = self.get_total_padding();
+        let child_size = match &mut self.child {
+            Some(child) => {
+                let child_size = child.set_layout(&constraints.deflate(&total_padding), platform);
+                child.set_position(Position {
                     x: self.padding.left,
                     y: self.padding.top,
-                },
-                available_space_for_child,
-            ),
-            None => (),
+                });
+                child_size
+            }
+            None => Size::zero(),
+        };
+        return Size {
+            width: child_size.width + total_padding.width,
+            height: child_size.height + total_padding.height,
         };
     }
 
-    fn draw(&self, parent_position: Position, platform: &dyn Platform) -> () {
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        let clip = self.get_clip(&parent_position);
         match &self.child {
-            Some(child) => child.draw(
-                parent_position + self.widget_data.position.clone(),
-                platform,
-            ),
+            Some(child) => draw_clipped(clip.as_ref(), display_list, |display_list| {
+                child.paint(
+                    parent_position + self.widget_data.position.clone(),
+                    display_list,
+                );
+            }),
             None => (),
         };
     }
 
-    fn get_width(&self, available_space: &Size) -> f64 {
-        let child_width = match &self.child {
-            Some(child) => child.get_width(&self.get_available_space_for_child(available_space)),
-            None => 0.,
-        };
-        return child_width + self.padding.left + self.padding.right;
-    }
-
-    fn get_height(&self, available_space: &Size) -> f64 {
-        let child_height = match &self.child {
-            Some(child) => child.get_height(&self.get_available_space_for_child(available_space)),
-            None => 0.,
-        };
-        return child_height + self.padding.top + self.padding.bottom;
+    fn get_clip(&self, parent_position: &Position) -> Option<Rect> {
+        return self.overflow.clip(Rect::new(
+            parent_position.clone() + self.widget_data.position.clone(),
+            self.widget_data.size.clone(),
+        ));
     }
 
     fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
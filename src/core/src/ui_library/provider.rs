@@ -0,0 +1,88 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    graphics::{display_list::DisplayList, Position, Size},
+    platform::Platform,
+    state::StateManager,
+    widget_default_methods,
+};
+
+use super::{constraints::BoxConstraints, Key, Widget, WidgetData};
+
+use derivative::Derivative;
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+/// Makes a value available to everything below it, e.g. the theme, the
+/// circuit being edited or the running simulation, without passing it down
+/// through every widget in between. Descendants read it with `lookup`, and
+/// are rebuilt when it's replaced with `HandledEventInfo::update_context`.
+///
+/// Like the state of a `StatefulWidget`, the value belongs to the provider's
+/// key, so `value` is only the initial value and rebuilding the provider
+/// doesn't reset it.
+#[derive(Derivative, KeySegment)]
+#[derivative(Debug(bound = ""))]
+pub struct Provider<T: 'static> {
+    widget_data: WidgetData,
+    #[derivative(Debug = "ignore")]
+    value: Rc<T>,
+    child: Box<dyn Widget>,
+}
+
+impl<T: 'static> Provider<T> {
+    pub fn new(value: T, child: Box<dyn Widget>) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            value: Rc::new(value),
+            child,
+        });
+    }
+}
+
+impl<T: 'static> Widget for Provider<T> {
+    widget_default_methods!();
+
+    /// Registers the value before the descendants are built so that they
+    /// can find it
+    fn build(&mut self) -> Option<Box<dyn Widget>> {
+        if let Some(state_manager) = self.widget_data.state_manager.upgrade() {
+            state_manager
+                .borrow_mut()
+                .provide_context(self.get_key().clone(), self.value.clone());
+        }
+        return None;
+    }
+
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        let size = self.child.set_layout(constraints, platform);
+        self.child.set_position(Position::origin());
+        return size;
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        self.child.paint(
+            parent_position + self.widget_data.position.clone(),
+            display_list,
+        );
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        return vec![self.child.as_mut()];
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        return vec![self.child.as_ref()];
+    }
+}
+
+/// The value of the nearest `Provider` of a `T` above `widget`, if there is
+/// one. `widget` is rebuilt whenever that value is replaced.
+pub fn lookup<T: 'static>(widget: &dyn Widget) -> Option<Rc<T>> {
+    let state_manager = widget.get_state_manager().upgrade()?;
+    return state_manager
+        .borrow_mut()
+        .lookup_context::<T>(widget.get_key());
+}
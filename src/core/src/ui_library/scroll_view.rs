@@ -0,0 +1,384 @@
+use std::{cell::RefCell, rc::Weak};
+
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+use crate::{
+    event_handlers::{
+        HandledEventInfo, MouseButtonEvent, MouseButtonEventKind, PointerEvent, WheelEvent,
+    },
+    graphics::{display_list::DisplayList, Color, Position, Rect, Size},
+    platform::Platform,
+    state::{State, StateManager},
+    widget_default_methods,
+};
+
+use super::{
+    constraints::BoxConstraints, dispatch_pointer_event, theme::Theme, Key, Widget, WidgetData,
+};
+
+const SCROLLBAR_THICKNESS: f64 = 8.;
+const MIN_THUMB_LENGTH: f64 = 16.;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScrollAxis {
+    Vertical,
+    Horizontal,
+}
+
+pub struct ScrollViewState {
+    /// How far the content has been scrolled along the axis, in pixels
+    offset: f64,
+    /// The pointer's position along the axis and the offset at the moment the
+    /// thumb was grabbed, while it is being dragged
+    thumb_drag_start: Option<(f64, f64)>,
+}
+
+impl ScrollViewState {
+    pub fn new() -> Box<Self> {
+        return Box::new(Self {
+            offset: 0.,
+            thumb_drag_start: None,
+        });
+    }
+}
+
+impl State for ScrollViewState {}
+
+/// Fills the available space and shows as much of its child as fits, letting
+/// the user scroll through the rest with the mouse wheel or the scrollbar.
+/// The child is laid out with no maximum along the axis, so it can be as long
+/// as it likes, but it is at least as long as the scroll view.
+#[derive(Debug, KeySegment)]
+pub struct ScrollView {
+    widget_data: WidgetData,
+    axis: ScrollAxis,
+    child: Box<dyn Widget>,
+    content_size: Size,
+    /// Read from the theme when the scroll view is built, so that drawing
+    /// doesn't have to look it up
+    track_color: Color,
+    thumb_color: Color,
+}
+
+impl ScrollView {
+    pub fn new(axis: ScrollAxis, child: Box<dyn Widget>) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            axis,
+            child,
+            content_size: Size::zero(),
+            track_color: Color::transparent(),
+            thumb_color: Color::transparent(),
+        });
+    }
+
+    fn length(&self, size: &Size) -> f64 {
+        return match self.axis {
+            ScrollAxis::Vertical => size.height,
+            ScrollAxis::Horizontal => size.width,
+        };
+    }
+
+    fn distance_along(&self, position: &Position) -> f64 {
+        return match self.axis {
+            ScrollAxis::Vertical => position.y,
+            ScrollAxis::Horizontal => position.x,
+        };
+    }
+
+    fn max_offset(&self) -> f64 {
+        return (self.length(&self.content_size) - self.length(&self.widget_data.size)).max(0.);
+    }
+
+    fn with_state<R>(&self, f: impl FnOnce(&mut ScrollViewState) -> R) -> R {
+        let state_manager_rc = Weak::upgrade(&self.widget_data.state_manager).unwrap();
+        let mut state_manager = (*state_manager_rc).borrow_mut();
+        return f(state_manager.get_state(self.get_key(), &ScrollViewState::new));
+    }
+
+    fn offset(&self) -> f64 {
+        return self.with_state(|state| state.offset);
+    }
+
+    fn child_position(&self, offset: f64) -> Position {
+        return match self.axis {
+            ScrollAxis::Vertical => Position { x: 0., y: -offset },
+            ScrollAxis::Horizontal => Position { x: -offset, y: 0. },
+        };
+    }
+
+    /// The scrollbar runs along the right or bottom edge, in the scroll
+    /// view's own coordinates. There isn't one if everything fits.
+    fn track_rect(&self) -> Option<Rect> {
+        if self.max_offset() <= 0. {
+            return None;
+        }
+        let size = &self.widget_data.size;
+        return Some(match self.axis {
+            ScrollAxis::Vertical => Rect::new(
+                Position {
+                    x: size.width - SCROLLBAR_THICKNESS,
+                    y: 0.,
+                },
+                Size {
+                    width: SCROLLBAR_THICKNESS,
+                    height: size.height,
+                },
+            ),
+            ScrollAxis::Horizontal => Rect::new(
+                Position {
+                    x: 0.,
+                    y: size.height - SCROLLBAR_THICKNESS,
+                },
+                Size {
+                    width: size.width,
+                    height: SCROLLBAR_THICKNESS,
+                },
+            ),
+        });
+    }
+
+    /// The thumb is as long, relative to the track, as the visible part of
+    /// the content is relative to the whole
+    fn thumb_length(&self) -> f64 {
+        let viewport_length = self.length(&self.widget_data.size);
+        return (viewport_length / self.length(&self.content_size) * viewport_length)
+            .max(MIN_THUMB_LENGTH)
+            .min(viewport_length);
+    }
+
+    /// How far the thumb can move along the track
+    fn thumb_travel(&self) -> f64 {
+        return self.length(&self.widget_data.size) - self.thumb_length();
+    }
+
+    fn thumb_rect(&self, offset: f64) -> Option<Rect> {
+        let track = self.track_rect()?;
+        let start = offset / self.max_offset() * self.thumb_travel();
+        return Some(match self.axis {
+            ScrollAxis::Vertical => Rect::new(
+                Position {
+                    x: track.position.x,
+                    y: start,
+                },
+                Size {
+                    width: SCROLLBAR_THICKNESS,
+                    height: self.thumb_length(),
+                },
+            ),
+            ScrollAxis::Horizontal => Rect::new(
+                Position {
+                    x: start,
+                    y: track.position.y,
+                },
+                Size {
+                    width: self.thumb_length(),
+                    height: SCROLLBAR_THICKNESS,
+                },
+            ),
+        });
+    }
+
+    /// Scrolls to `offset`, or as close to it as the content allows, and
+    /// returns whether anything moved
+    fn scroll_to(&mut self, offset: f64, handled_event_info: &mut HandledEventInfo) -> bool {
+        let offset = offset.clamp(0., self.max_offset());
+        let old_offset = self.with_state(|state| std::mem::replace(&mut state.offset, offset));
+        if offset == old_offset {
+            return false;
+        }
+        // Positions are relative to the parent, so moving the child moves
+        // everything below it without a relayout
+        self.child.set_position(self.child_position(offset));
+        handled_event_info.mark_dirty(Rect::from_size(self.widget_data.size.clone()));
+        return true;
+    }
+}
+
+impl Widget for ScrollView {
+    widget_default_methods!();
+
+    fn does_expand(&self) -> bool {
+        return true;
+    }
+
+    /// Rebuilt, and so redrawn, whenever the theme changes
+    fn build(&mut self) -> Option<Box<dyn Widget>> {
+        let theme = Theme::of(self);
+        self.track_color = theme.palette.scrollbar_track.clone();
+        self.thumb_color = theme.palette.scrollbar_thumb.clone();
+        return None;
+    }
+
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        let viewport = constraints.biggest();
+        // The child may be as long as it likes along the axis, but no shorter
+        // than the viewport, so content which expands just fills it
+        let child_constraints = match self.axis {
+            ScrollAxis::Vertical => BoxConstraints {
+                min_width: 0.,
+                max_width: viewport.width,
+                min_height: viewport.height,
+                max_height: f64::INFINITY,
+            },
+            ScrollAxis::Horizontal => BoxConstraints {
+                min_width: viewport.width,
+                max_width: f64::INFINITY,
+                min_height: 0.,
+                max_height: viewport.height,
+            },
+        };
+        self.content_size = self.child.set_layout(&child_constraints, platform);
+        // The scrollbar depends on the viewport's size, which is needed
+        // before the offset can be clamped
+        self.widget_data.size = viewport.clone();
+
+        let max_offset = self.max_offset();
+        let offset = self.with_state(|state| {
+            state.offset = state.offset.clamp(0., max_offset);
+            state.offset
+        });
+        self.child.set_position(self.child_position(offset));
+        return viewport;
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        let my_pos = parent_position + self.widget_data.position.clone();
+        display_list.push_clip(&Rect::new(my_pos.clone(), self.widget_data.size.clone()));
+        self.child.paint(my_pos.clone(), display_list);
+        if let Some(track) = self.track_rect() {
+            display_list.fill_rect(
+                &(my_pos.clone() + track.position),
+                &track.size,
+                &self.track_color,
+            );
+        }
+        if let Some(thumb) = self.thumb_rect(self.offset()) {
+            display_list.fill_rect(&(my_pos + thumb.position), &thumb.size, &self.thumb_color);
+        }
+        display_list.pop_clip();
+    }
+
+    fn get_clip(&self, parent_position: &Position) -> Option<Rect> {
+        return Some(Rect::new(
+            parent_position.clone() + self.widget_data.position.clone(),
+            self.widget_data.size.clone(),
+        ));
+    }
+
+    /// The scrollbar is drawn over the content, so events on it skip the
+    /// content and come straight here
+    fn on_pointer_event_anywhere(
+        &mut self,
+        parent_position: Position,
+        event: &PointerEvent,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        let bounds = self.get_clip(&parent_position).unwrap();
+        if !bounds.contains(event.position()) {
+            return false;
+        }
+        let my_pos = bounds.position;
+        let is_on_scrollbar = match self.track_rect() {
+            Some(track) => {
+                Rect::new(my_pos.clone() + track.position, track.size).contains(event.position())
+            }
+            None => false,
+        };
+        if !is_on_scrollbar
+            && self.child.on_pointer_event_anywhere(
+                my_pos.clone(),
+                event,
+                platform,
+                handled_event_info,
+            )
+        {
+            return true;
+        }
+        return dispatch_pointer_event(self, my_pos, event, platform, handled_event_info);
+    }
+
+    fn on_mouse_button(
+        &mut self,
+        event: &MouseButtonEvent,
+        _platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        let offset = self.offset();
+        let (track, thumb) = match (self.track_rect(), self.thumb_rect(offset)) {
+            (Some(track), Some(thumb)) => (track, thumb),
+            _ => return false,
+        };
+        return match event.kind {
+            MouseButtonEventKind::Down => {
+                if !track.contains(&event.position) {
+                    return false;
+                }
+                // Clicking the track either side of the thumb pages towards
+                // the pointer
+                let pointer = self.distance_along(&event.position);
+                let page = self.length(&self.widget_data.size);
+                if pointer < self.distance_along(&thumb.position) {
+                    self.scroll_to(offset - page, handled_event_info);
+                } else if pointer > self.distance_along(&thumb.position) + self.thumb_length() {
+                    self.scroll_to(offset + page, handled_event_info);
+                }
+                true
+            }
+            MouseButtonEventKind::DragStart => {
+                if !thumb.contains(&event.position) {
+                    return false;
+                }
+                let pointer = self.distance_along(&event.position);
+                self.with_state(|state| state.thumb_drag_start = Some((pointer, offset)));
+                true
+            }
+            MouseButtonEventKind::Drag => {
+                let (start_pointer, start_offset) = match self.with_state(|x| x.thumb_drag_start) {
+                    Some(x) => x,
+                    None => return false,
+                };
+                let travel = self.thumb_travel();
+                if travel > 0. {
+                    let moved = self.distance_along(&event.position) - start_pointer;
+                    self.scroll_to(
+                        start_offset + moved * self.max_offset() / travel,
+                        handled_event_info,
+                    );
+                }
+                true
+            }
+            MouseButtonEventKind::DragEnd => self
+                .with_state(|state| state.thumb_drag_start.take())
+                .is_some(),
+            _ => false,
+        };
+    }
+
+    fn on_mouse_wheel(
+        &mut self,
+        event: &WheelEvent,
+        _platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        // Ordinary mouse wheels only scroll vertically, so they scroll
+        // horizontal views too
+        let delta = match self.axis {
+            ScrollAxis::Vertical => event.delta_y,
+            ScrollAxis::Horizontal if event.delta_x != 0. => event.delta_x,
+            ScrollAxis::Horizontal => event.delta_y,
+        };
+        // Only capture the event if we actually scrolled, so that a scroll
+        // view inside another one hands over once it reaches the end
+        return self.scroll_to(self.offset() + delta, handled_event_info);
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        return vec![self.child.as_mut()];
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        return vec![self.child.as_ref()];
+    }
+}
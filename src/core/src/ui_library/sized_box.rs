@@ -4,18 +4,20 @@ use key_segment::KeySegment;
 use key_segment_derive::KeySegment;
 
 use crate::{
-    graphics::{Position, Size},
+    graphics::{display_list::DisplayList, Position, Rect, Size},
+    platform::Platform,
     state::StateManager,
     widget_default_methods,
 };
 
-use super::{Key, Widget, WidgetData};
+use super::{constraints::BoxConstraints, draw_clipped, Key, Overflow, Widget, WidgetData};
 
 #[derive(Debug, KeySegment)]
 pub struct SizedBox {
     widget_data: WidgetData,
     size: Size,
     child: Box<dyn Widget>,
+    overflow: Overflow,
 }
 
 impl SizedBox {
@@ -24,8 +26,14 @@ impl SizedBox {
             widget_data: WidgetData::new(),
             size,
             child,
+            overflow: Overflow::Visible,
         });
     }
+
+    pub fn with_overflow(mut self: Box<Self>, overflow: Overflow) -> Box<Self> {
+        self.overflow = overflow;
+        return self;
+    }
 }
 
 impl Widget for SizedBox {
@@ -35,25 +43,29 @@ impl Widget for SizedBox {
         return false;
     }
 
-    fn set_layout(&mut self, position: Position, available_space: Size) {
-        self.widget_data.position = position;
-        self.widget_data.available_space = available_space;
-        self.child.set_layout(Position::origin(), self.size.clone());
+    /// Asks for exactly `size`, as far as the parent's constraints allow
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        let child_constraints = constraints.tighten(&self.size);
+        self.child.set_layout(&child_constraints, platform);
+        self.child.set_position(Position::origin());
+        return child_constraints.smallest();
     }
 
-    fn draw(&self, parent_position: Position, platform: &dyn crate::platform::Platform) -> () {
-        self.child.draw(
-            parent_position + self.widget_data.position.clone(),
-            platform,
-        );
-    }
-
-    fn get_width(&self, _available_space: &Size) -> f64 {
-        return self.child.get_width(&self.size);
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        let clip = self.get_clip(&parent_position);
+        draw_clipped(clip.as_ref(), display_list, |display_list| {
+            self.child.paint(
+                parent_position + self.widget_data.position.clone(),
+                display_list,
+            );
+        });
     }
 
-    fn get_height(&self, _available_space: &Size) -> f64 {
-        return self.child.get_height(&self.size);
+    fn get_clip(&self, parent_position: &Position) -> Option<Rect> {
+        return self.overflow.clip(Rect::new(
+            parent_position.clone() + self.widget_data.position.clone(),
+            self.widget_data.size.clone(),
+        ));
     }
 
     fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
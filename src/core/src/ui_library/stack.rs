@@ -0,0 +1,208 @@
+use std::{cell::RefCell, rc::Weak};
+
+use crate::{
+    event_handlers::{HandledEventInfo, PointerEvent},
+    graphics::{display_list::DisplayList, Position, Rect, Size},
+    platform::Platform,
+    state::StateManager,
+    widget_default_methods,
+};
+
+use super::{
+    constraints::BoxConstraints, dispatch_pointer_event, draw_clipped, Alignment, Key, Overflow,
+    Widget, WidgetData,
+};
+
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StackPlacement {
+    /// Aligned within the stack, which is sized to fit the largest of these
+    Aligned(Alignment),
+    /// With its top-left corner at this position relative to the stack, at
+    /// whatever size it likes. These don't affect the stack's size.
+    At(Position),
+}
+
+#[derive(Debug)]
+pub struct StackChild {
+    placement: StackPlacement,
+    child: Box<dyn Widget>,
+}
+
+impl StackChild {
+    pub fn aligned(alignment: Alignment, child: Box<dyn Widget>) -> Self {
+        return Self {
+            placement: StackPlacement::Aligned(alignment),
+            child,
+        };
+    }
+
+    pub fn at(position: Position, child: Box<dyn Widget>) -> Self {
+        return Self {
+            placement: StackPlacement::At(position),
+            child,
+        };
+    }
+}
+
+/// Draws its children on top of each other, the first at the bottom. The
+/// pointer goes to the topmost child under it first, and the children below
+/// only see it if that one doesn't capture it. If there are no aligned
+/// children the stack fills the space it's given.
+#[derive(Debug, KeySegment)]
+pub struct Stack {
+    widget_data: WidgetData,
+    children: Vec<StackChild>,
+    overflow: Overflow,
+}
+
+impl Stack {
+    pub fn new(children: Vec<StackChild>) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            children,
+            overflow: Overflow::Visible,
+        });
+    }
+
+    pub fn with_overflow(mut self: Box<Self>, overflow: Overflow) -> Box<Self> {
+        self.overflow = overflow;
+        return self;
+    }
+}
+
+impl Widget for Stack {
+    widget_default_methods!();
+
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        let mut child_sizes: Vec<Size> = vec![];
+        let mut largest: Option<Size> = None;
+        for stack_child in &mut self.children {
+            let child_size = match stack_child.placement {
+                StackPlacement::Aligned(_) => {
+                    let child_size = stack_child
+                        .child
+                        .set_layout(&constraints.loosen(), platform);
+                    largest = Some(match largest {
+                        Some(largest) => Size {
+                            width: largest.width.max(child_size.width),
+                            height: largest.height.max(child_size.height),
+                        },
+                        None => child_size.clone(),
+                    });
+                    child_size
+                }
+                StackPlacement::At(_) => stack_child
+                    .child
+                    .set_layout(&BoxConstraints::unbounded(), platform),
+            };
+            child_sizes.push(child_size);
+        }
+
+        let size = match largest {
+            Some(largest) => constraints.constrain(&largest),
+            None => constraints.biggest(),
+        };
+        for (stack_child, child_size) in self.children.iter_mut().zip(child_sizes) {
+            let position = match &stack_child.placement {
+                StackPlacement::Aligned(alignment) => alignment.offset(&size, &child_size),
+                StackPlacement::At(position) => position.clone(),
+            };
+            stack_child.child.set_position(position);
+        }
+        return size;
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        let my_pos = parent_position.clone() + self.widget_data.position.clone();
+        let clip = self.get_clip(&parent_position);
+        draw_clipped(clip.as_ref(), display_list, |display_list| {
+            for stack_child in &self.children {
+                stack_child.child.paint(my_pos.clone(), display_list);
+            }
+        });
+    }
+
+    fn get_clip(&self, parent_position: &Position) -> Option<Rect> {
+        return self.overflow.clip(Rect::new(
+            parent_position.clone() + self.widget_data.position.clone(),
+            self.widget_data.size.clone(),
+        ));
+    }
+
+    /// Only the topmost child under the pointer is hit, so the ones below it
+    /// aren't hovered through it
+    fn hit_test(
+        &self,
+        parent_position: Position,
+        position: &Position,
+        platform: &dyn Platform,
+        hits: &mut Vec<Key>,
+    ) {
+        if let Some(clip) = self.get_clip(&parent_position) {
+            if !clip.contains(position) {
+                return;
+            }
+        }
+        let my_pos = self.widget_data.position.clone() + parent_position;
+        if Rect::new(my_pos.clone(), self.widget_data.size.clone()).contains(position) {
+            hits.push(self.get_key().clone());
+        }
+        for stack_child in self.children.iter().rev() {
+            let hit_count = hits.len();
+            stack_child
+                .child
+                .hit_test(my_pos.clone(), position, platform, hits);
+            if hits.len() > hit_count {
+                return;
+            }
+        }
+    }
+
+    fn on_pointer_event_anywhere(
+        &mut self,
+        parent_position: Position,
+        event: &PointerEvent,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        if let Some(clip) = self.get_clip(&parent_position) {
+            if !clip.contains(event.position()) {
+                return false;
+            }
+        }
+        let my_pos = self.widget_data.position.clone() + parent_position;
+        for stack_child in self.children.iter_mut().rev() {
+            let captured = stack_child.child.on_pointer_event_anywhere(
+                my_pos.clone(),
+                event,
+                platform,
+                handled_event_info,
+            );
+            if captured {
+                return true;
+            }
+        }
+        if Rect::new(my_pos.clone(), self.widget_data.size.clone()).contains(event.position()) {
+            return dispatch_pointer_event(self, my_pos, event, platform, handled_event_info);
+        }
+        return false;
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        let mut result: Vec<&mut dyn Widget> = vec![];
+        for stack_child in &mut self.children {
+            result.push(stack_child.child.as_mut());
+        }
+        return result;
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        let mut result: Vec<&dyn Widget> = vec![];
+        for stack_child in &self.children {
+            result.push(stack_child.child.as_ref());
+        }
+        return result;
+    }
+}
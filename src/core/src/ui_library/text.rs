@@ -0,0 +1,57 @@
+use std::{cell::RefCell, rc::Weak};
+
+use crate::{
+    graphics::{display_list::DisplayList, text::TextStyle, Position, Size},
+    platform::Platform,
+    state::StateManager,
+    widget_default_methods,
+};
+
+use super::{constraints::BoxConstraints, Key, Widget, WidgetData};
+
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+/// A single line of text, sized to fit it exactly
+#[derive(Debug, KeySegment)]
+pub struct Text {
+    widget_data: WidgetData,
+    text: String,
+    style: TextStyle,
+}
+
+impl Text {
+    pub fn new(text: &str, style: TextStyle) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            text: text.into(),
+            style,
+        });
+    }
+}
+
+impl Widget for Text {
+    widget_default_methods!();
+
+    fn layout(&mut self, _constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        return platform
+            .graphics()
+            .measure_text(&self.text, &self.style)
+            .size();
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        display_list.fill_text(
+            &(parent_position + self.widget_data.position.clone()),
+            &self.text,
+            &self.style,
+        );
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        return vec![];
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        return vec![];
+    }
+}
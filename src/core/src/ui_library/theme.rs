@@ -0,0 +1,223 @@
+use std::rc::Rc;
+
+use crate::graphics::{
+    text::{TextStyle, DEFAULT_FONT_FAMILY},
+    Color,
+};
+
+use super::{provider::lookup, Widget};
+
+/// The colours widgets are drawn with. Each `on_` colour is for text and
+/// icons drawn over the colour it's named after.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    pub primary: Color,
+    pub primary_hovered: Color,
+    pub primary_pressed: Color,
+    pub on_primary: Color,
+    /// Behind everything else
+    pub background: Color,
+    pub on_background: Color,
+    /// Toolbars, panels and the like, which sit on top of the background
+    pub surface: Color,
+    pub on_surface: Color,
+    pub border: Color,
+    /// Controls which can't be used at the moment
+    pub disabled: Color,
+    pub on_disabled: Color,
+    pub scrollbar_track: Color,
+    pub scrollbar_thumb: Color,
+}
+
+/// The text sizes, in pixels, and font used throughout
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typography {
+    pub font_family: String,
+    pub title_size: f64,
+    pub body_size: f64,
+    pub label_size: f64,
+}
+
+impl Typography {
+    pub fn new() -> Self {
+        return Self {
+            font_family: DEFAULT_FONT_FAMILY.into(),
+            title_size: 20.,
+            body_size: 16.,
+            label_size: 14.,
+        };
+    }
+}
+
+impl Default for Typography {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// The gaps used between and around widgets, in pixels
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spacing {
+    pub small: f64,
+    pub medium: f64,
+    pub large: f64,
+}
+
+impl Spacing {
+    pub fn new() -> Self {
+        return Self {
+            small: 4.,
+            medium: 8.,
+            large: 16.,
+        };
+    }
+}
+
+impl Default for Spacing {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// The colours of wires in the circuit view, by the signal on them
+#[derive(Debug, Clone, PartialEq)]
+pub struct WireColors {
+    pub low: Color,
+    pub high: Color,
+    /// Not driven by anything
+    pub floating: Color,
+    /// Driven high and low at once
+    pub conflict: Color,
+    pub selected: Color,
+}
+
+/// How the whole app looks. The root of the tree provides the current theme,
+/// so any widget's `build` can read it with `Theme::of`, and it's switched
+/// with `HandledEventInfo::set_theme` or `AppRunner::set_theme`, which
+/// rebuild the widgets that read it. A `Provider` of a `Theme` restyles just
+/// the part of the tree below it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub palette: Palette,
+    pub typography: Typography,
+    pub spacing: Spacing,
+    pub wires: WireColors,
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        return Self {
+            palette: Palette {
+                primary: Color::rgb(37, 99, 235),
+                primary_hovered: Color::rgb(59, 130, 246),
+                primary_pressed: Color::rgb(30, 64, 175),
+                on_primary: Color::rgb(255, 255, 255),
+                background: Color::rgb(248, 250, 252),
+                on_background: Color::rgb(15, 23, 42),
+                surface: Color::rgb(226, 232, 240),
+                on_surface: Color::rgb(15, 23, 42),
+                border: Color::rgb(148, 163, 184),
+                disabled: Color::rgb(203, 213, 225),
+                on_disabled: Color::rgb(100, 116, 139),
+                scrollbar_track: Color::rgba(0, 0, 0, 32),
+                scrollbar_thumb: Color::rgba(0, 0, 0, 128),
+            },
+            typography: Typography::new(),
+            spacing: Spacing::new(),
+            wires: WireColors {
+                low: Color::rgb(21, 128, 61),
+                high: Color::rgb(34, 197, 94),
+                floating: Color::rgb(37, 99, 235),
+                conflict: Color::rgb(220, 38, 38),
+                selected: Color::rgb(234, 179, 8),
+            },
+        };
+    }
+
+    pub fn dark() -> Self {
+        return Self {
+            palette: Palette {
+                primary: Color::rgb(59, 130, 246),
+                primary_hovered: Color::rgb(96, 165, 250),
+                primary_pressed: Color::rgb(37, 99, 235),
+                on_primary: Color::rgb(255, 255, 255),
+                background: Color::rgb(15, 23, 42),
+                on_background: Color::rgb(226, 232, 240),
+                surface: Color::rgb(30, 41, 59),
+                on_surface: Color::rgb(226, 232, 240),
+                border: Color::rgb(71, 85, 105),
+                disabled: Color::rgb(51, 65, 85),
+                on_disabled: Color::rgb(148, 163, 184),
+                scrollbar_track: Color::rgba(255, 255, 255, 24),
+                scrollbar_thumb: Color::rgba(255, 255, 255, 96),
+            },
+            typography: Typography::new(),
+            spacing: Spacing::new(),
+            wires: WireColors {
+                low: Color::rgb(22, 101, 52),
+                high: Color::rgb(74, 222, 128),
+                floating: Color::rgb(96, 165, 250),
+                conflict: Color::rgb(248, 113, 113),
+                selected: Color::rgb(250, 204, 21),
+            },
+        };
+    }
+
+    /// Black and white with bright accents, and larger text
+    pub fn high_contrast() -> Self {
+        return Self {
+            palette: Palette {
+                primary: Color::rgb(255, 255, 0),
+                primary_hovered: Color::rgb(255, 255, 255),
+                primary_pressed: Color::rgb(0, 255, 255),
+                on_primary: Color::rgb(0, 0, 0),
+                background: Color::rgb(0, 0, 0),
+                on_background: Color::rgb(255, 255, 255),
+                surface: Color::rgb(0, 0, 0),
+                on_surface: Color::rgb(255, 255, 255),
+                border: Color::rgb(255, 255, 255),
+                disabled: Color::rgb(64, 64, 64),
+                on_disabled: Color::rgb(192, 192, 192),
+                scrollbar_track: Color::rgb(64, 64, 64),
+                scrollbar_thumb: Color::rgb(255, 255, 255),
+            },
+            typography: Typography {
+                title_size: 24.,
+                body_size: 20.,
+                label_size: 18.,
+                ..Typography::new()
+            },
+            spacing: Spacing::new(),
+            wires: WireColors {
+                low: Color::rgb(0, 128, 255),
+                high: Color::rgb(255, 255, 0),
+                floating: Color::rgb(255, 255, 255),
+                conflict: Color::rgb(255, 0, 0),
+                selected: Color::rgb(0, 255, 255),
+            },
+        };
+    }
+
+    /// The theme in use by the tree `widget` is in, or the light theme if
+    /// it isn't in one yet
+    pub fn of(widget: &dyn Widget) -> Rc<Theme> {
+        return lookup::<Theme>(widget).unwrap_or_else(|| Rc::new(Theme::light()));
+    }
+
+    pub fn title_style(&self) -> TextStyle {
+        return self.text_style(self.typography.title_size, &self.palette.on_surface);
+    }
+
+    pub fn body_style(&self) -> TextStyle {
+        return self.text_style(self.typography.body_size, &self.palette.on_background);
+    }
+
+    pub fn label_style(&self) -> TextStyle {
+        return self.text_style(self.typography.label_size, &self.palette.on_primary);
+    }
+
+    fn text_style(&self, font_size: f64, color: &Color) -> TextStyle {
+        return TextStyle::new(font_size, color.clone())
+            .with_font_family(&self.typography.font_family);
+    }
+}
@@ -0,0 +1,195 @@
+use std::{cell::RefCell, rc::Weak};
+
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+use crate::{
+    event_handlers::{HandledEventInfo, KeyboardEvent, PointerEvent},
+    graphics::{display_list::DisplayList, Position, Rect, Size, Transform},
+    platform::Platform,
+    state::StateManager,
+    widget_default_methods,
+};
+
+use super::{constraints::BoxConstraints, Key, Widget, WidgetData};
+
+/// Draws its child through `transform`, e.g. to zoom and pan a canvas. Like a
+/// CSS transform it doesn't affect layout: the child is laid out as if it
+/// weren't there, and then drawn scaled and translated about this widget's
+/// top-left corner. Pointer events are mapped into the child's coordinates,
+/// so the child never needs to know about the transform.
+#[derive(Debug, KeySegment)]
+pub struct Transformed {
+    widget_data: WidgetData,
+    transform: Transform,
+    child: Box<dyn Widget>,
+}
+
+impl Transformed {
+    pub fn new(transform: Transform, child: Box<dyn Widget>) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            transform,
+            child,
+        });
+    }
+
+    /// Maps the child's coordinates into the same coordinates as
+    /// `parent_position`
+    fn child_to_parent(&self, parent_position: &Position) -> Transform {
+        return self.transform.then(&Transform::translate(
+            parent_position.clone() + self.widget_data.position.clone(),
+        ));
+    }
+
+    /// Calls `handler` on the child, converting any dirty regions it marks
+    /// back out of the child's coordinates
+    fn with_child<R>(
+        &mut self,
+        parent_position: &Position,
+        handled_event_info: &mut HandledEventInfo,
+        handler: impl FnOnce(&mut dyn Widget, &Transform, &mut HandledEventInfo) -> R,
+    ) -> R {
+        let to_parent = self.child_to_parent(parent_position);
+        let first_new_dirty_region = handled_event_info.dirty_regions.len();
+        let result = handler(
+            self.child.as_mut(),
+            &to_parent.inverse(),
+            handled_event_info,
+        );
+        handled_event_info.transform_dirty_regions_from(first_new_dirty_region, &to_parent);
+        return result;
+    }
+
+    fn is_on_path_to(&self, key: &Key) -> bool {
+        return self.child.get_key() == key || self.child.get_key().is_ancestor_of(key);
+    }
+}
+
+impl Widget for Transformed {
+    widget_default_methods!();
+
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        let size = self.child.set_layout(constraints, platform);
+        self.child.set_position(Position::origin());
+        return size;
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        display_list.push_transform(&self.child_to_parent(&parent_position));
+        self.child.paint(Position::origin(), display_list);
+        display_list.pop_transform();
+    }
+
+    fn get_paint_bounds(&self) -> Rect {
+        return Rect::from_size(self.widget_data.size.clone())
+            .union(&self.transform.apply_rect(&self.child.get_paint_bounds()));
+    }
+
+    fn hit_test(
+        &self,
+        parent_position: Position,
+        position: &Position,
+        platform: &dyn Platform,
+        hits: &mut Vec<Key>,
+    ) {
+        let my_pos = self.widget_data.position.clone() + parent_position.clone();
+        let my_size = self.widget_data.size.clone();
+        if Rect::new(my_pos, my_size).contains(position) {
+            hits.push(self.get_key().clone());
+        }
+        let to_child = self.child_to_parent(&parent_position).inverse();
+        self.child.hit_test(
+            Position::origin(),
+            &to_child.apply(position),
+            platform,
+            hits,
+        );
+    }
+
+    fn on_pointer_event_anywhere(
+        &mut self,
+        parent_position: Position,
+        event: &PointerEvent,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        return self.with_child(
+            &parent_position,
+            handled_event_info,
+            |child, to_child, info| {
+                child.on_pointer_event_anywhere(
+                    Position::origin(),
+                    &event.transformed(to_child),
+                    platform,
+                    info,
+                )
+            },
+        );
+    }
+
+    fn on_pointer_event_for_key(
+        &mut self,
+        key: &Key,
+        parent_position: Position,
+        event: &PointerEvent,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        if !self.is_on_path_to(key) {
+            return false;
+        }
+        return self.with_child(
+            &parent_position,
+            handled_event_info,
+            |child, to_child, info| {
+                child.on_pointer_event_for_key(
+                    key,
+                    Position::origin(),
+                    &event.transformed(to_child),
+                    platform,
+                    info,
+                )
+            },
+        );
+    }
+
+    fn on_keyboard_event_for_key(
+        &mut self,
+        key: &Key,
+        parent_position: Position,
+        event: &KeyboardEvent,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        if !self.is_on_path_to(key) {
+            return false;
+        }
+        return self.with_child(&parent_position, handled_event_info, |child, _, info| {
+            child.on_keyboard_event_for_key(key, Position::origin(), event, platform, info)
+        });
+    }
+
+    fn set_focus_for_key(
+        &mut self,
+        key: &Key,
+        is_focused: bool,
+        parent_position: Position,
+        platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> () {
+        if !self.is_on_path_to(key) {
+            return;
+        }
+        self.with_child(&parent_position, handled_event_info, |child, _, info| {
+            child.set_focus_for_key(key, is_focused, Position::origin(), platform, info)
+        });
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        return vec![self.child.as_mut()];
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        return vec![self.child.as_ref()];
+    }
+}
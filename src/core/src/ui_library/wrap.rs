@@ -0,0 +1,207 @@
+use std::{cell::RefCell, rc::Weak};
+
+use crate::{
+    graphics::{display_list::DisplayList, Position, Size},
+    platform::Platform,
+    state::StateManager,
+    widget_default_methods,
+};
+
+use super::{
+    constraints::BoxConstraints,
+    list::{CrossAxisAlignment, ListDirection, MainAxisAlignment},
+    Key, Widget, WidgetData,
+};
+
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+/// Lays its children out one after another like a `List`, but starts a new
+/// run (a row, or a column if the direction is `Column`) whenever the next
+/// child wouldn't fit in the current one. `alignment` places the children
+/// within each run, `run_alignment` places the runs within the wrap and
+/// `cross_axis_alignment` places each child within its run.
+#[derive(Debug, KeySegment)]
+pub struct Wrap {
+    widget_data: WidgetData,
+    direction: ListDirection,
+    spacing: f64,
+    run_spacing: f64,
+    alignment: MainAxisAlignment,
+    run_alignment: MainAxisAlignment,
+    cross_axis_alignment: CrossAxisAlignment,
+    children: Vec<Box<dyn Widget>>,
+}
+
+impl Wrap {
+    pub fn new(
+        direction: ListDirection,
+        spacing: f64,
+        run_spacing: f64,
+        children: Vec<Box<dyn Widget>>,
+    ) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            direction,
+            spacing,
+            run_spacing,
+            alignment: MainAxisAlignment::Start,
+            run_alignment: MainAxisAlignment::Start,
+            cross_axis_alignment: CrossAxisAlignment::Start,
+            children,
+        });
+    }
+
+    pub fn with_alignment(
+        mut self: Box<Self>,
+        alignment: MainAxisAlignment,
+        run_alignment: MainAxisAlignment,
+        cross_axis_alignment: CrossAxisAlignment,
+    ) -> Box<Self> {
+        self.alignment = alignment;
+        self.run_alignment = run_alignment;
+        self.cross_axis_alignment = cross_axis_alignment;
+        return self;
+    }
+
+    fn main_axis_length(&self, size: &Size) -> f64 {
+        return match self.direction {
+            ListDirection::Column => size.height,
+            ListDirection::Row => size.width,
+        };
+    }
+
+    fn cross_axis_length(&self, size: &Size) -> f64 {
+        return match self.direction {
+            ListDirection::Column => size.width,
+            ListDirection::Row => size.height,
+        };
+    }
+
+    fn size_from_axes(&self, main: f64, cross: f64) -> Size {
+        return match self.direction {
+            ListDirection::Column => Size {
+                width: cross,
+                height: main,
+            },
+            ListDirection::Row => Size {
+                width: main,
+                height: cross,
+            },
+        };
+    }
+
+    fn position_from_axes(&self, main: f64, cross: f64) -> Position {
+        return match self.direction {
+            ListDirection::Column => Position { x: cross, y: main },
+            ListDirection::Row => Position { x: main, y: cross },
+        };
+    }
+}
+
+/// The children in one run, by index
+struct Run {
+    children: Vec<usize>,
+    main_axis_length: f64,
+    cross_axis_length: f64,
+}
+
+impl Widget for Wrap {
+    widget_default_methods!();
+
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        let max_main = self.main_axis_length(&Size {
+            width: constraints.max_width,
+            height: constraints.max_height,
+        });
+        // Each child may be as long as a whole run, and as thick as it likes
+        let child_constraints =
+            BoxConstraints::loose(&self.size_from_axes(max_main, f64::INFINITY));
+        let child_sizes: Vec<Size> = self
+            .children
+            .iter_mut()
+            .map(|child| child.set_layout(&child_constraints, platform))
+            .collect();
+
+        let mut runs: Vec<Run> = vec![];
+        for (i, child_size) in child_sizes.iter().enumerate() {
+            let child_main = self.main_axis_length(child_size);
+            let child_cross = self.cross_axis_length(child_size);
+            match runs.last_mut() {
+                Some(run) if run.main_axis_length + self.spacing + child_main <= max_main => {
+                    run.children.push(i);
+                    run.main_axis_length += self.spacing + child_main;
+                    run.cross_axis_length = run.cross_axis_length.max(child_cross);
+                }
+                _ => runs.push(Run {
+                    children: vec![i],
+                    main_axis_length: child_main,
+                    cross_axis_length: child_cross,
+                }),
+            }
+        }
+
+        let run_crosses: Vec<f64> = runs.iter().map(|run| run.cross_axis_length).collect();
+        let size = constraints.constrain(
+            &self.size_from_axes(
+                runs.iter()
+                    .map(|run| run.main_axis_length)
+                    .fold(0., f64::max),
+                run_crosses.iter().sum::<f64>()
+                    + self.run_spacing * run_crosses.len().saturating_sub(1) as f64,
+            ),
+        );
+        let main_size = self.main_axis_length(&size);
+        let run_starts = self.run_alignment.place(
+            &run_crosses,
+            self.run_spacing,
+            self.cross_axis_length(&size),
+        );
+
+        let mut child_positions: Vec<Position> = vec![Position::origin(); self.children.len()];
+        for (run, run_start) in runs.iter().zip(run_starts) {
+            let child_mains: Vec<f64> = run
+                .children
+                .iter()
+                .map(|i| self.main_axis_length(&child_sizes[*i]))
+                .collect();
+            let child_starts = self.alignment.place(&child_mains, self.spacing, main_size);
+            for (i, child_start) in run.children.iter().zip(child_starts) {
+                let cross = run_start
+                    + self.cross_axis_alignment.offset(
+                        run.cross_axis_length,
+                        self.cross_axis_length(&child_sizes[*i]),
+                    );
+                child_positions[*i] = self.position_from_axes(child_start, cross);
+            }
+        }
+        for (child, position) in self.children.iter_mut().zip(child_positions) {
+            child.set_position(position);
+        }
+        return size;
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        for child in &self.children {
+            child.paint(
+                parent_position.clone() + self.widget_data.position.clone(),
+                display_list,
+            );
+        }
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        let mut result: Vec<&mut dyn Widget> = vec![];
+        for child in &mut self.children {
+            result.push(child.as_mut());
+        }
+        return result;
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        let mut result: Vec<&dyn Widget> = vec![];
+        for child in &self.children {
+            result.push(child.as_ref());
+        }
+        return result;
+    }
+}
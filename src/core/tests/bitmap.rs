@@ -0,0 +1,22 @@
+use std::sync::{Arc, Mutex};
+
+use casserole_core::graphics::bitmap::Bitmap;
+
+#[test]
+fn release_handler_runs_when_the_last_clone_is_dropped() {
+    let released: Arc<Mutex<Vec<usize>>> = Arc::new(Mutex::new(vec![]));
+    let bitmap = Bitmap::new(1, 1, vec![0, 0, 0, 255]);
+    let id = bitmap.id();
+    let handler_released = released.clone();
+    bitmap.set_release_handler(Box::new(move |id| {
+        handler_released.lock().unwrap().push(id);
+    }));
+    // Only the first handler is kept
+    bitmap.set_release_handler(Box::new(|_| panic!("Second handler called")));
+
+    let clone = bitmap.clone();
+    drop(bitmap);
+    assert!(released.lock().unwrap().is_empty());
+    drop(clone);
+    assert_eq!(*released.lock().unwrap(), vec![id]);
+}
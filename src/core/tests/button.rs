@@ -0,0 +1,227 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+};
+
+use casserole_core::{
+    event_handlers::{
+        HandledEventInfo, Modifiers, MouseButton, MouseButtonEvent, MouseButtonEventKind,
+    },
+    graphics::{display_list::DisplayList, Color, Position, Size},
+    headless::HeadlessPlatform,
+    platform::Platform,
+    state::StateManager,
+    ui_library::{
+        align::Center, compound::button::Button, constraints::BoxConstraints, expanded::Expanded,
+        sized_box::SizedBox, theme::Theme, Key, Widget, WidgetData,
+    },
+    widget_default_methods, AppRunner,
+};
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+type Caught = Rc<RefCell<Vec<MouseButtonEventKind>>>;
+
+/// Captures and records every mouse button event which its child lets
+/// through
+#[derive(Debug, KeySegment)]
+struct Catcher {
+    widget_data: WidgetData,
+    caught: Caught,
+    child: Box<dyn Widget>,
+}
+
+impl Widget for Catcher {
+    widget_default_methods!();
+
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        let size = self.child.set_layout(constraints, platform);
+        self.child.set_position(Position::origin());
+        return size;
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        self.child.paint(
+            parent_position + self.widget_data.position.clone(),
+            display_list,
+        );
+    }
+
+    fn on_mouse_button(
+        &mut self,
+        event: &MouseButtonEvent,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        self.caught.borrow_mut().push(event.kind.clone());
+        return true;
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        return vec![self.child.as_mut()];
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        return vec![self.child.as_ref()];
+    }
+}
+
+/// A button around a 40x20 space, which with the theme's padding makes it
+/// 72x36, in the middle of a 200x100 screen. Whatever it doesn't capture
+/// goes to `caught`.
+fn runner(
+    presses: &Rc<Cell<usize>>,
+    caught: &Caught,
+    is_disabled: bool,
+) -> AppRunner<HeadlessPlatform, Catcher> {
+    let presses = presses.clone();
+    let button = Button::new(Box::new(|| {
+        SizedBox::new(
+            Size {
+                width: 40.,
+                height: 20.,
+            },
+            Expanded::new(),
+        )
+    }))
+    .with_on_pressed(Box::new(move |_key, _handled_event_info| {
+        presses.set(presses.get() + 1);
+    }))
+    .with_disabled(is_disabled);
+    let app = Catcher {
+        widget_data: WidgetData::new(),
+        caught: caught.clone(),
+        child: Center::new(button),
+    };
+    let runner = AppRunner::new(
+        HeadlessPlatform::new(Size {
+            width: 200.,
+            height: 100.,
+        }),
+        app,
+        Theme::light(),
+    );
+    runner.draw_frame();
+    return runner;
+}
+
+fn center() -> Position {
+    return Position { x: 100., y: 50. };
+}
+
+#[test]
+fn button_is_filled_by_its_rounded_decoration() {
+    let presses = Rc::new(Cell::new(0));
+    let caught: Caught = Rc::new(RefCell::new(vec![]));
+    let runner = runner(&presses, &caught, false);
+    let palette = Theme::light().palette;
+    assert_eq!(runner.platform.graphics.get_pixel(100, 50), palette.primary);
+    assert_eq!(runner.platform.graphics.get_pixel(66, 40), palette.primary);
+    // Cut off by the rounded corner
+    assert_eq!(
+        runner.platform.graphics.get_pixel(64, 32),
+        Color::rgb(0, 0, 0)
+    );
+}
+
+#[test]
+fn clicking_presses_the_button() {
+    let presses = Rc::new(Cell::new(0));
+    let caught: Caught = Rc::new(RefCell::new(vec![]));
+    let mut runner = runner(&presses, &caught, false);
+    let palette = Theme::light().palette;
+
+    runner.on_mouse_move(center());
+    assert_eq!(
+        runner.platform.graphics.get_pixel(100, 50),
+        palette.primary_hovered
+    );
+
+    runner.on_mouse_down(center(), MouseButton::Left, Modifiers::none());
+    assert_eq!(
+        runner.platform.graphics.get_pixel(100, 50),
+        palette.primary_pressed
+    );
+    assert_eq!(presses.get(), 0);
+
+    runner.on_mouse_up(center(), MouseButton::Left, Modifiers::none(), 1);
+    assert_eq!(presses.get(), 1);
+    assert_eq!(
+        runner.platform.graphics.get_pixel(100, 50),
+        palette.primary_hovered
+    );
+    assert!(caught.borrow().is_empty());
+}
+
+#[test]
+fn disabled_button_ignores_clicks() {
+    let presses = Rc::new(Cell::new(0));
+    let caught: Caught = Rc::new(RefCell::new(vec![]));
+    let mut runner = runner(&presses, &caught, true);
+    let palette = Theme::light().palette;
+    assert_eq!(
+        runner.platform.graphics.get_pixel(100, 50),
+        palette.disabled
+    );
+
+    runner.on_mouse_move(center());
+    runner.on_mouse_down(center(), MouseButton::Left, Modifiers::none());
+    runner.on_mouse_up(center(), MouseButton::Left, Modifiers::none(), 1);
+    assert_eq!(presses.get(), 0);
+    assert_eq!(
+        runner.platform.graphics.get_pixel(100, 50),
+        palette.disabled
+    );
+    assert_eq!(
+        *caught.borrow(),
+        vec![
+            MouseButtonEventKind::Down,
+            MouseButtonEventKind::Up,
+            MouseButtonEventKind::Click,
+        ]
+    );
+}
+
+#[test]
+fn dragging_off_the_button_cancels_the_press() {
+    let presses = Rc::new(Cell::new(0));
+    let caught: Caught = Rc::new(RefCell::new(vec![]));
+    let mut runner = runner(&presses, &caught, false);
+    let outside = Position { x: 10., y: 10. };
+
+    runner.on_mouse_move(center());
+    runner.on_mouse_down(center(), MouseButton::Left, Modifiers::none());
+    runner.on_mouse_move(outside.clone());
+    runner.on_mouse_up(outside, MouseButton::Left, Modifiers::none(), 1);
+    assert_eq!(presses.get(), 0);
+    assert_eq!(
+        runner.platform.graphics.get_pixel(100, 50),
+        Theme::light().palette.primary
+    );
+}
+
+#[test]
+fn drags_which_start_on_the_button_go_to_its_ancestors() {
+    let presses = Rc::new(Cell::new(0));
+    let caught: Caught = Rc::new(RefCell::new(vec![]));
+    let mut runner = runner(&presses, &caught, false);
+    let moved = Position { x: 110., y: 50. };
+
+    runner.on_mouse_move(center());
+    runner.on_mouse_down(center(), MouseButton::Left, Modifiers::none());
+    runner.on_mouse_move(moved.clone());
+    runner.on_mouse_up(moved, MouseButton::Left, Modifiers::none(), 1);
+    assert_eq!(presses.get(), 0);
+    assert_eq!(
+        *caught.borrow(),
+        vec![
+            MouseButtonEventKind::DragStart,
+            MouseButtonEventKind::Drag,
+            MouseButtonEventKind::DragEnd,
+        ]
+    );
+    // The button still saw the release, so it isn't left looking pressed
+    assert_eq!(
+        runner.platform.graphics.get_pixel(100, 50),
+        Theme::light().palette.primary_hovered
+    );
+}
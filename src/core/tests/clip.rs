@@ -0,0 +1,106 @@
+use casserole_core::{
+    graphics::{Color, Size},
+    headless::{graphics::Framebuffer, snapshot::render_widget},
+    ui_library::{
+        container::Container,
+        expanded::Expanded,
+        list::{
+            CrossAxisAlignment, CrossAxisSize, List, ListDirection, MainAxisAlignment, MainAxisSize,
+        },
+        padding::{Inset, Padding},
+        sized_box::SizedBox,
+        Overflow, Widget,
+    },
+};
+
+fn red() -> Color {
+    return Color::rgb(255, 0, 0);
+}
+
+/// Renders `widget` in the top-left corner of a 100x100 screen
+fn render(widget: Box<dyn Widget>) -> Framebuffer {
+    return render_widget(
+        list(ListDirection::Column, vec![widget]),
+        Size {
+            width: 100.,
+            height: 100.,
+        },
+    );
+}
+
+fn square(size: f64, child: Box<dyn Widget>) -> Box<SizedBox> {
+    return SizedBox::new(
+        Size {
+            width: size,
+            height: size,
+        },
+        child,
+    );
+}
+
+fn list(direction: ListDirection, children: Vec<Box<dyn Widget>>) -> Box<dyn Widget> {
+    return List::new(
+        direction,
+        MainAxisAlignment::Start,
+        CrossAxisAlignment::Start,
+        MainAxisSize::Min,
+        CrossAxisSize::Min,
+        children,
+    );
+}
+
+/// A 2x2 grid of red squares, each three quarters of `size` across, so
+/// that together they overflow a `size` box by half in each direction
+fn overflowing_child(size: f64) -> Box<dyn Widget> {
+    let cell =
+        || -> Box<dyn Widget> { square(size * 0.75, Container::new(red(), Expanded::new())) };
+    let row = || list(ListDirection::Row, vec![cell(), cell()]);
+    return list(ListDirection::Column, vec![row(), row()]);
+}
+
+fn is_red(framebuffer: &Framebuffer, x: usize, y: usize) -> bool {
+    return framebuffer.get_pixel(x, y) == red();
+}
+
+#[test]
+fn overflowing_children_are_drawn_unless_clipped() {
+    let visible = render(square(40., overflowing_child(40.)));
+    assert!(is_red(&visible, 30, 30));
+    assert!(is_red(&visible, 55, 55));
+    assert!(!is_red(&visible, 65, 65));
+
+    let clipped = render(square(40., overflowing_child(40.)).with_overflow(Overflow::Clip));
+    assert!(is_red(&clipped, 0, 0));
+    assert!(is_red(&clipped, 39, 39));
+    assert!(!is_red(&clipped, 40, 20));
+    assert!(!is_red(&clipped, 20, 40));
+    assert!(!is_red(&clipped, 55, 55));
+}
+
+#[test]
+fn nested_clips_intersect() {
+    // The outer clip covers (0, 0) to (60, 60) and the inner one (30, 30) to
+    // (90, 90), so only (30, 30) to (60, 60) is left
+    let framebuffer = render(
+        square(
+            60.,
+            Padding::new(
+                Inset {
+                    left: 30.,
+                    top: 30.,
+                    right: 0.,
+                    bottom: 0.,
+                },
+                Some(square(60., overflowing_child(60.)).with_overflow(Overflow::Clip)),
+            ),
+        )
+        .with_overflow(Overflow::Clip),
+    );
+    assert!(is_red(&framebuffer, 30, 30));
+    assert!(is_red(&framebuffer, 59, 59));
+    assert!(!is_red(&framebuffer, 29, 45));
+    assert!(!is_red(&framebuffer, 45, 29));
+    assert!(!is_red(&framebuffer, 75, 45));
+    assert!(!is_red(&framebuffer, 45, 75));
+    assert!(!is_red(&framebuffer, 95, 95));
+}
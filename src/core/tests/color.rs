@@ -0,0 +1,102 @@
+use casserole_core::{
+    graphics::{Color, Position, Size},
+    headless::graphics::Framebuffer,
+};
+
+#[test]
+fn from_hex_reads_short_long_and_alpha_forms() {
+    assert_eq!(Color::from_hex("#f80"), Some(Color::rgb(255, 136, 0)));
+    assert_eq!(Color::from_hex("ff8800"), Some(Color::rgb(255, 136, 0)));
+    assert_eq!(
+        Color::from_hex("#f808"),
+        Some(Color::rgba(255, 136, 0, 136))
+    );
+    assert_eq!(
+        Color::from_hex("#ff880080"),
+        Some(Color::rgba(255, 136, 0, 128))
+    );
+    assert_eq!(Color::from_hex("#ff880080").unwrap().to_hex(), "#ff880080");
+    assert_eq!(Color::rgb(255, 136, 0).to_hex(), "#ff8800");
+}
+
+#[test]
+fn from_hex_rejects_invalid_input() {
+    assert_eq!(Color::from_hex(""), None);
+    assert_eq!(Color::from_hex("#ff88000"), None);
+    assert_eq!(Color::from_hex("#gg0000"), None);
+    assert_eq!(Color::from_hex("#ff88é"), None);
+}
+
+#[test]
+fn opaque_and_transparent_sources_short_circuit_blending() {
+    let background = Color::rgb(0, 0, 255);
+    let red = Color::rgb(255, 0, 0);
+    assert_eq!(red.blend_over(&background), red);
+    assert_eq!(red.with_alpha(0).blend_over(&background), background);
+    assert_eq!(
+        red.with_alpha(1).blend_over(&background),
+        Color::rgb(1, 0, 254)
+    );
+}
+
+#[test]
+fn blend_over_mixes_by_the_source_alpha() {
+    let half_red = Color::rgba(255, 0, 0, 128);
+    assert_eq!(
+        half_red.blend_over(&Color::rgb(0, 0, 255)),
+        Color::rgb(128, 0, 127)
+    );
+    // Over a transparent background the source is unchanged
+    assert_eq!(half_red.blend_over(&Color::transparent()), half_red);
+    // Two half-transparent layers add up to three quarters
+    let blended = half_red.blend_over(&Color::rgba(0, 0, 255, 128));
+    assert_eq!(blended.a, 192);
+    assert!(blended.r > blended.b);
+}
+
+#[test]
+fn lerp_clamps_and_interpolates_every_channel() {
+    let from = Color::rgba(0, 100, 200, 0);
+    let to = Color::rgba(200, 100, 0, 255);
+    assert_eq!(from.lerp(&to, 0.), from);
+    assert_eq!(from.lerp(&to, 1.), to);
+    assert_eq!(from.lerp(&to, 0.5), Color::rgba(100, 100, 100, 128));
+    assert_eq!(from.lerp(&to, -1.), from);
+    assert_eq!(from.lerp(&to, 2.), to);
+}
+
+#[test]
+fn lighten_and_darken_keep_the_alpha() {
+    let color = Color::rgba(100, 50, 200, 77);
+    assert_eq!(color.lighten(0.), color);
+    assert_eq!(color.lighten(1.), Color::rgba(255, 255, 255, 77));
+    assert_eq!(color.lighten(0.5), Color::rgba(178, 153, 228, 77));
+    assert_eq!(color.darken(1.), Color::rgba(0, 0, 0, 77));
+    assert_eq!(color.darken(0.5), Color::rgba(50, 25, 100, 77));
+}
+
+#[test]
+fn framebuffer_blends_translucent_fills_over_what_is_there() {
+    let mut framebuffer = Framebuffer::new(4, 1);
+    let size = Size {
+        width: 4.,
+        height: 1.,
+    };
+    framebuffer.fill_rect(&Position::origin(), &size, &Color::rgb(0, 0, 255));
+    framebuffer.fill_rect(
+        &Position::origin(),
+        &Size {
+            width: 2.,
+            height: 1.,
+        },
+        &Color::rgba(255, 0, 0, 128),
+    );
+    framebuffer.fill_rect(&Position::origin(), &size, &Color::transparent());
+    assert_eq!(framebuffer.get_pixel(0, 0), Color::rgb(128, 0, 127));
+    assert_eq!(framebuffer.get_pixel(1, 0), Color::rgb(128, 0, 127));
+    assert_eq!(framebuffer.get_pixel(2, 0), Color::rgb(0, 0, 255));
+
+    // Opaque fills still replace the pixel outright
+    framebuffer.fill_rect(&Position::origin(), &size, &Color::rgb(0, 255, 0));
+    assert_eq!(framebuffer.get_pixel(0, 0), Color::rgb(0, 255, 0));
+}
@@ -0,0 +1,96 @@
+use casserole_core::{graphics::Size, ui_library::constraints::BoxConstraints};
+
+fn size(width: f64, height: f64) -> Size {
+    return Size { width, height };
+}
+
+fn constraints(min_width: f64, max_width: f64, min_height: f64, max_height: f64) -> BoxConstraints {
+    return BoxConstraints {
+        min_width,
+        max_width,
+        min_height,
+        max_height,
+    };
+}
+
+#[test]
+fn constrain_clamps_each_axis() {
+    let range = constraints(10., 100., 20., 50.);
+    assert_eq!(range.constrain(&size(50., 30.)), size(50., 30.));
+    assert_eq!(range.constrain(&size(5., 80.)), size(10., 50.));
+    assert_eq!(range.constrain(&size(500., 0.)), size(100., 20.));
+
+    let tight = BoxConstraints::tight(&size(40., 60.));
+    assert_eq!(tight.constrain(&size(0., 1000.)), size(40., 60.));
+}
+
+#[test]
+fn constrain_passes_anything_through_an_infinite_max() {
+    let unbounded = BoxConstraints::unbounded();
+    assert_eq!(unbounded.constrain(&size(1e9, 3.)), size(1e9, 3.));
+    // Even infinity itself, which is for the parent to deal with
+    assert_eq!(
+        unbounded.constrain(&size(f64::INFINITY, 3.)),
+        size(f64::INFINITY, 3.)
+    );
+    assert_eq!(
+        constraints(10., f64::INFINITY, 0., 50.).constrain(&size(0., 100.)),
+        size(10., 50.)
+    );
+}
+
+#[test]
+fn biggest_falls_back_to_the_min_on_an_infinite_axis() {
+    assert_eq!(constraints(10., 100., 20., 50.).biggest(), size(100., 50.));
+    assert_eq!(
+        constraints(10., f64::INFINITY, 20., 50.).biggest(),
+        size(10., 50.)
+    );
+    assert_eq!(BoxConstraints::unbounded().biggest(), size(0., 0.));
+    assert_eq!(constraints(10., 100., 20., 50.).smallest(), size(10., 20.));
+}
+
+#[test]
+fn loosen_drops_the_minimums() {
+    assert_eq!(
+        BoxConstraints::tight(&size(40., 60.)).loosen(),
+        BoxConstraints::loose(&size(40., 60.))
+    );
+    let loosened = constraints(10., f64::INFINITY, 20., 50.).loosen();
+    assert_eq!(loosened, constraints(0., f64::INFINITY, 0., 50.));
+    assert!(!loosened.is_tight());
+}
+
+#[test]
+fn tighten_picks_the_closest_allowed_size() {
+    let range = constraints(10., 100., 20., 50.);
+    let tightened = range.tighten(&size(60., 30.));
+    assert_eq!(tightened, BoxConstraints::tight(&size(60., 30.)));
+    assert!(tightened.is_tight());
+    assert_eq!(
+        range.tighten(&size(500., 0.)),
+        BoxConstraints::tight(&size(100., 20.))
+    );
+
+    // An infinite max lets any size through
+    assert_eq!(
+        BoxConstraints::unbounded().tighten(&size(1e9, 3.)),
+        BoxConstraints::tight(&size(1e9, 3.))
+    );
+    assert_eq!(
+        constraints(10., f64::INFINITY, 0., 50.).tighten(&size(0., 100.)),
+        BoxConstraints::tight(&size(10., 50.))
+    );
+}
+
+#[test]
+fn deflate_never_goes_below_zero() {
+    assert_eq!(
+        constraints(10., 100., 20., f64::INFINITY).deflate(&size(30., 10.)),
+        constraints(0., 70., 10., f64::INFINITY)
+    );
+    assert_eq!(
+        BoxConstraints::tight(&size(20., 20.)).deflate(&size(30., 30.)),
+        BoxConstraints::tight(&size(0., 0.))
+    );
+}
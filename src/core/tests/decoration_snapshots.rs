@@ -0,0 +1,165 @@
+use std::path::PathBuf;
+
+use casserole_core::{
+    graphics::{Color, Position, Size},
+    headless::snapshot::assert_snapshot,
+    ui_library::{
+        align::Center,
+        container::Container,
+        decoration::{Border, BorderSide, BoxDecoration, BoxGradient, BoxShadow},
+        expanded::Expanded,
+        sized_box::SizedBox,
+        Alignment,
+    },
+};
+
+fn background() -> Color {
+    return Color::rgb(229, 231, 235);
+}
+
+fn fill() -> Color {
+    return Color::rgb(255, 255, 255);
+}
+
+fn red() -> Color {
+    return Color::rgb(220, 38, 38);
+}
+
+fn green() -> Color {
+    return Color::rgb(34, 197, 94);
+}
+
+fn blue() -> Color {
+    return Color::rgb(37, 99, 235);
+}
+
+fn dark() -> Color {
+    return Color::rgb(31, 41, 55);
+}
+
+/// A border which is a different width and colour on every side
+fn uneven_border() -> Border {
+    return Border::new(
+        BorderSide::new(2., red()),
+        BorderSide::new(4., green()),
+        BorderSide::new(6., blue()),
+        BorderSide::new(8., dark()),
+    );
+}
+
+/// Checks a 60x40 box decorated with `decoration`, in the middle of a 100x80
+/// screen, against `tests/snapshots/decoration/<name>.png`
+fn assert_decoration_snapshot(name: &str, decoration: BoxDecoration) {
+    let app = Container::new(
+        background(),
+        Center::new(SizedBox::new(
+            Size {
+                width: 60.,
+                height: 40.,
+            },
+            Container::new(fill(), Expanded::new()).with_decoration(decoration),
+        )),
+    );
+    assert_snapshot(
+        app,
+        Size {
+            width: 100.,
+            height: 80.,
+        },
+        &PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/snapshots/decoration")
+            .join(format!("{}.png", name)),
+    );
+}
+
+#[test]
+fn borders() {
+    assert_decoration_snapshot(
+        "border_uniform",
+        BoxDecoration::new(fill()).with_border(Border::all(BorderSide::new(3., red()))),
+    );
+    assert_decoration_snapshot(
+        "border_uneven",
+        BoxDecoration::new(fill()).with_border(uneven_border()),
+    );
+    // Only the sides which are visible are drawn
+    assert_decoration_snapshot(
+        "border_one_side",
+        BoxDecoration::new(fill()).with_border(Border::new(
+            BorderSide::none(),
+            BorderSide::none(),
+            BorderSide::new(4., blue()),
+            BorderSide::none(),
+        )),
+    );
+}
+
+#[test]
+fn radii() {
+    assert_decoration_snapshot("radius", BoxDecoration::new(blue()).with_border_radius(12.));
+    // Too big for the box, so it is reduced to make the ends round
+    assert_decoration_snapshot(
+        "radius_clamped",
+        BoxDecoration::new(blue()).with_border_radius(100.),
+    );
+    assert_decoration_snapshot(
+        "radius_uniform_border",
+        BoxDecoration::new(fill())
+            .with_border(Border::all(BorderSide::new(3., red())))
+            .with_border_radius(12.),
+    );
+    assert_decoration_snapshot(
+        "radius_uneven_border",
+        BoxDecoration::new(fill())
+            .with_border(uneven_border())
+            .with_border_radius(12.),
+    );
+}
+
+#[test]
+fn gradients() {
+    assert_decoration_snapshot(
+        "gradient_horizontal",
+        BoxDecoration::new(fill()).with_gradient(BoxGradient::new(
+            Alignment::center_left(),
+            Alignment::center_right(),
+            red(),
+            blue(),
+        )),
+    );
+    assert_decoration_snapshot(
+        "gradient_diagonal_rounded",
+        BoxDecoration::new(fill())
+            .with_gradient(BoxGradient::new(
+                Alignment::top_left(),
+                Alignment::bottom_right(),
+                green(),
+                blue().with_alpha(0),
+            ))
+            .with_border_radius(12.),
+    );
+}
+
+#[test]
+fn shadows() {
+    assert_decoration_snapshot(
+        "shadow_hard",
+        BoxDecoration::new(fill()).with_shadow(BoxShadow::new(
+            Position { x: 6., y: 4. },
+            0.,
+            0.,
+            dark(),
+        )),
+    );
+    assert_decoration_snapshot(
+        "shadow_blurred_rounded",
+        BoxDecoration::new(fill())
+            .with_border_radius(8.)
+            .with_shadow(BoxShadow::new(
+                Position { x: 0., y: 4. },
+                8.,
+                2.,
+                dark().with_alpha(128),
+            )),
+    );
+}
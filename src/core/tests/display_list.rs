@@ -0,0 +1,137 @@
+use casserole_core::{
+    graphics::{
+        display_list::{DisplayList, DrawOp},
+        Color, Position, Rect, Size,
+    },
+    headless::HeadlessPlatform,
+    ui_library::{
+        compound::hoverable::Hoverable,
+        container::Container,
+        expanded::Expanded,
+        list::{
+            CrossAxisAlignment, CrossAxisSize, List, ListDirection, MainAxisAlignment, MainAxisSize,
+        },
+        sized_box::SizedBox,
+        theme::Theme,
+        Widget,
+    },
+    AppRunner,
+};
+
+fn idle_color() -> Color {
+    return Color::rgb(0, 0, 255);
+}
+
+fn hovered_color() -> Color {
+    return Color::rgb(255, 0, 0);
+}
+
+/// A green 200x100 screen with a 40x20 box in its top left corner which
+/// changes colour while the pointer is over it
+fn runner() -> AppRunner<HeadlessPlatform, Container> {
+    let app = *Container::new(
+        Color::rgb(0, 255, 0),
+        List::new(
+            ListDirection::Column,
+            MainAxisAlignment::Start,
+            CrossAxisAlignment::Start,
+            MainAxisSize::Max,
+            CrossAxisSize::Max,
+            vec![Hoverable::new(Box::new(|is_hovered| {
+                SizedBox::new(
+                    Size {
+                        width: 40.,
+                        height: 20.,
+                    },
+                    Container::new(
+                        if is_hovered {
+                            hovered_color()
+                        } else {
+                            idle_color()
+                        },
+                        Expanded::new(),
+                    ),
+                )
+            }))],
+        ),
+    );
+    return AppRunner::new(
+        HeadlessPlatform::new(Size {
+            width: 200.,
+            height: 100.,
+        }),
+        app,
+        Theme::light(),
+    );
+}
+
+fn record(runner: &AppRunner<HeadlessPlatform, Container>) -> DisplayList {
+    let mut display_list = DisplayList::new();
+    runner.app.draw(Position::origin(), &mut display_list);
+    return display_list;
+}
+
+#[test]
+fn identical_frames_record_equal_lists() {
+    let runner = runner();
+    let frame = record(&runner);
+    assert_eq!(
+        frame.ops,
+        vec![
+            DrawOp::FillRect {
+                rect: Rect::new(
+                    Position::origin(),
+                    Size {
+                        width: 200.,
+                        height: 100.,
+                    },
+                ),
+                color: Color::rgb(0, 255, 0),
+            },
+            DrawOp::FillRect {
+                rect: Rect::new(
+                    Position::origin(),
+                    Size {
+                        width: 40.,
+                        height: 20.,
+                    },
+                ),
+                color: idle_color(),
+            },
+        ]
+    );
+    assert_eq!(record(&runner), frame);
+}
+
+#[test]
+fn hovering_changes_only_the_hovered_op() {
+    let mut runner = runner();
+    let before = record(&runner);
+
+    runner.on_mouse_move(Position { x: 10., y: 10. });
+    let after = record(&runner);
+    assert_ne!(after, before);
+    assert_eq!(after.len(), before.len());
+    let changed: Vec<(&DrawOp, &DrawOp)> = before
+        .ops
+        .iter()
+        .zip(&after.ops)
+        .filter(|(a, b)| a != b)
+        .collect();
+    assert_eq!(
+        changed,
+        vec![(
+            &before.ops[1],
+            &DrawOp::FillRect {
+                rect: Rect::new(
+                    Position::origin(),
+                    Size {
+                        width: 40.,
+                        height: 20.,
+                    },
+                ),
+                color: hovered_color(),
+            }
+        )]
+    );
+}
@@ -0,0 +1,105 @@
+use casserole_core::{
+    graphics::Size,
+    headless::HeadlessPlatform,
+    ui_library::{
+        expanded::Expanded,
+        list::{
+            CrossAxisAlignment, CrossAxisSize, List, ListDirection, MainAxisAlignment, MainAxisSize,
+        },
+        sized_box::SizedBox,
+        theme::Theme,
+        Widget,
+    },
+    AppRunner,
+};
+
+/// Lays out a 300x50 row of `children` and returns where each one starts
+/// along the row and how wide it is
+fn layout_row(main_align: MainAxisAlignment, children: Vec<Box<dyn Widget>>) -> Vec<(f64, f64)> {
+    let app = *List::new(
+        ListDirection::Row,
+        main_align,
+        CrossAxisAlignment::Start,
+        MainAxisSize::Max,
+        CrossAxisSize::Max,
+        children,
+    );
+    let runner = AppRunner::new(
+        HeadlessPlatform::new(Size {
+            width: 300.,
+            height: 50.,
+        }),
+        app,
+        Theme::light(),
+    );
+    return runner
+        .app
+        .get_children()
+        .iter()
+        .map(|x| (x.get_position().x, x.get_size().width))
+        .collect();
+}
+
+fn fixed(width: f64) -> Box<dyn Widget> {
+    return SizedBox::new(Size { width, height: 10. }, Expanded::new());
+}
+
+#[test]
+fn two_children_share_by_factor() {
+    let placed = layout_row(
+        MainAxisAlignment::Start,
+        vec![fixed(60.), Expanded::new(), Expanded::new().with_flex(2.)],
+    );
+    assert_eq!(placed, vec![(0., 60.), (60., 80.), (140., 160.)]);
+}
+
+#[test]
+fn three_children_share_by_factor() {
+    let placed = layout_row(
+        MainAxisAlignment::Start,
+        vec![
+            Expanded::new(),
+            Expanded::new().with_flex(2.),
+            Expanded::new().with_flex(3.),
+        ],
+    );
+    assert_eq!(placed, vec![(0., 50.), (50., 100.), (150., 150.)]);
+}
+
+#[test]
+fn child_clamped_by_max_gives_the_rest_away() {
+    let placed = layout_row(
+        MainAxisAlignment::Start,
+        vec![
+            Expanded::new().with_max_size(40.),
+            Expanded::new(),
+            Expanded::new(),
+        ],
+    );
+    assert_eq!(placed, vec![(0., 40.), (40., 130.), (170., 130.)]);
+}
+
+#[test]
+fn child_forced_up_by_min_takes_from_the_rest() {
+    let placed = layout_row(
+        MainAxisAlignment::Start,
+        vec![
+            fixed(100.),
+            Expanded::new().with_min_size(80.),
+            Expanded::new().with_flex(3.),
+        ],
+    );
+    assert_eq!(placed, vec![(0., 100.), (100., 80.), (180., 120.)]);
+}
+
+#[test]
+fn space_left_after_every_child_is_frozen_is_aligned() {
+    let placed = layout_row(
+        MainAxisAlignment::Center,
+        vec![
+            Expanded::new().with_max_size(50.),
+            Expanded::new().with_max_size(50.),
+        ],
+    );
+    assert_eq!(placed, vec![(100., 50.), (150., 50.)]);
+}
@@ -0,0 +1,266 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use casserole_core::{
+    compound_widget_default_methods,
+    event_handlers::{KeyCode, KeyboardEvent, Modifiers, MouseButton},
+    graphics::{Color, Position, Size},
+    headless::HeadlessPlatform,
+    state::StateManager,
+    ui_library::{
+        compound::focusable::Focusable,
+        constraints::BoxConstraints,
+        container::Container,
+        expanded::Expanded,
+        list::{
+            CrossAxisAlignment, CrossAxisSize, List, ListDirection, MainAxisAlignment, MainAxisSize,
+        },
+        provider::{lookup, Provider},
+        sized_box::SizedBox,
+        theme::Theme,
+        CompoundWidget, CompoundWidgetData, Key, Widget,
+    },
+    widget_default_methods, AppRunner,
+};
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+fn idle_color() -> Color {
+    return Color::rgb(0, 0, 255);
+}
+
+fn focused_color() -> Color {
+    return Color::rgb(255, 0, 0);
+}
+
+/// The keys typed while each square was focused
+type Typed = Rc<RefCell<Vec<KeyCode>>>;
+
+/// A 50x50 square which is red while focused and captures the letter keys
+fn square(typed: &Typed) -> Box<dyn Widget> {
+    let typed = typed.clone();
+    return Focusable::new(
+        Box::new(|is_focused| {
+            SizedBox::new(
+                Size {
+                    width: 50.,
+                    height: 50.,
+                },
+                Container::new(
+                    if is_focused {
+                        focused_color()
+                    } else {
+                        idle_color()
+                    },
+                    Expanded::new(),
+                ),
+            )
+        }),
+        Some(Box::new(move |_key, event, _handled_event_info| {
+            if let KeyboardEvent::KeyDown(x) = event {
+                if let KeyCode::Char(_) = x.key {
+                    typed.borrow_mut().push(x.key.clone());
+                    return true;
+                }
+            }
+            return false;
+        })),
+    );
+}
+
+/// Two squares side by side, the left one from x = 0 to 50 and the right one
+/// from x = 50 to 100
+fn runner(left: &Typed, right: &Typed) -> AppRunner<HeadlessPlatform, List> {
+    let app = *List::new(
+        ListDirection::Row,
+        MainAxisAlignment::Start,
+        CrossAxisAlignment::Start,
+        MainAxisSize::Max,
+        CrossAxisSize::Max,
+        vec![square(left), square(right)],
+    );
+    let runner = AppRunner::new(
+        HeadlessPlatform::new(Size {
+            width: 200.,
+            height: 100.,
+        }),
+        app,
+        Theme::light(),
+    );
+    runner.draw_frame();
+    return runner;
+}
+
+fn press(runner: &mut AppRunner<HeadlessPlatform, List>, key: KeyCode, modifiers: Modifiers) {
+    runner.on_key_down(key.clone(), modifiers.clone(), false);
+    runner.on_key_up(key, modifiers);
+}
+
+/// Which of the two squares is drawn as focused
+fn focused_pixels(runner: &AppRunner<HeadlessPlatform, List>) -> (bool, bool) {
+    return (
+        runner.platform.graphics.get_pixel(25, 25) == focused_color(),
+        runner.platform.graphics.get_pixel(75, 25) == focused_color(),
+    );
+}
+
+#[test]
+fn tab_and_shift_tab_move_focus_and_wrap_around() {
+    let typed: Typed = Rc::new(RefCell::new(vec![]));
+    let mut runner = runner(&typed, &typed);
+    assert_eq!(runner.focus_manager.focused(), None);
+    assert_eq!(focused_pixels(&runner), (false, false));
+
+    press(&mut runner, KeyCode::Tab, Modifiers::none());
+    assert_eq!(focused_pixels(&runner), (true, false));
+    press(&mut runner, KeyCode::Tab, Modifiers::none());
+    assert_eq!(focused_pixels(&runner), (false, true));
+    press(&mut runner, KeyCode::Tab, Modifiers::none());
+    assert_eq!(focused_pixels(&runner), (true, false));
+
+    let shift = Modifiers {
+        shift: true,
+        ..Modifiers::none()
+    };
+    press(&mut runner, KeyCode::Tab, shift.clone());
+    assert_eq!(focused_pixels(&runner), (false, true));
+    press(&mut runner, KeyCode::Tab, shift);
+    assert_eq!(focused_pixels(&runner), (true, false));
+}
+
+#[test]
+fn keys_go_to_the_focused_widget() {
+    let left: Typed = Rc::new(RefCell::new(vec![]));
+    let right: Typed = Rc::new(RefCell::new(vec![]));
+    let mut runner = runner(&left, &right);
+
+    // Nothing has focus, so nobody gets it
+    press(&mut runner, KeyCode::Char('a'), Modifiers::none());
+    press(&mut runner, KeyCode::Tab, Modifiers::none());
+    press(&mut runner, KeyCode::Char('b'), Modifiers::none());
+    press(&mut runner, KeyCode::Tab, Modifiers::none());
+    press(&mut runner, KeyCode::Char('c'), Modifiers::none());
+    assert_eq!(*left.borrow(), vec![KeyCode::Char('b')]);
+    assert_eq!(*right.borrow(), vec![KeyCode::Char('c')]);
+}
+
+#[test]
+fn clicking_focuses_what_is_under_the_pointer() {
+    let typed: Typed = Rc::new(RefCell::new(vec![]));
+    let mut runner = runner(&typed, &typed);
+    let click = |runner: &mut AppRunner<HeadlessPlatform, List>, x: f64| {
+        let position = Position { x, y: 25. };
+        runner.on_mouse_down(position.clone(), MouseButton::Left, Modifiers::none());
+        runner.on_mouse_up(position, MouseButton::Left, Modifiers::none(), 1);
+    };
+
+    click(&mut runner, 75.);
+    assert_eq!(focused_pixels(&runner), (false, true));
+    assert_eq!(
+        runner.focus_manager.focused(),
+        Some(runner.app.get_children()[1].get_key())
+    );
+
+    // Clicking on nothing focusable clears focus
+    click(&mut runner, 150.);
+    assert_eq!(runner.focus_manager.focused(), None);
+    assert_eq!(focused_pixels(&runner), (false, false));
+}
+
+/// A plain 50x50 square which can't be focused
+fn plain_square() -> Box<dyn Widget> {
+    return SizedBox::new(
+        Size {
+            width: 50.,
+            height: 50.,
+        },
+        Container::new(idle_color(), Expanded::new()),
+    );
+}
+
+/// Shows a focusable square while the provided `bool` is true. Pressing
+/// Escape on the square replaces it with false, which removes the square.
+#[derive(Debug, KeySegment)]
+struct Removable {
+    widget_data: CompoundWidgetData,
+    /// The keys the Escape handler was called with
+    escapes: Rc<RefCell<Vec<Key>>>,
+}
+
+impl CompoundWidget for Removable {
+    compound_widget_default_methods!();
+
+    fn build(&self) -> Box<dyn Widget> {
+        if !*lookup::<bool>(self).unwrap() {
+            return plain_square();
+        }
+        let escapes = self.escapes.clone();
+        return Focusable::new(
+            Box::new(|_is_focused| plain_square()),
+            Some(Box::new(move |key, event, handled_event_info| {
+                if let KeyboardEvent::KeyDown(x) = event {
+                    if x.key == KeyCode::Escape {
+                        escapes.borrow_mut().push(key.clone());
+                        handled_event_info.update_context(key, false);
+                        return true;
+                    }
+                }
+                return false;
+            })),
+        );
+    }
+}
+
+fn removable_runner(
+    escapes: &Rc<RefCell<Vec<Key>>>,
+) -> AppRunner<HeadlessPlatform, Provider<bool>> {
+    let app = *Provider::new(
+        true,
+        Box::new(Removable {
+            widget_data: CompoundWidgetData::new(),
+            escapes: escapes.clone(),
+        }),
+    );
+    let runner = AppRunner::new(
+        HeadlessPlatform::new(Size {
+            width: 100.,
+            height: 100.,
+        }),
+        app,
+        Theme::light(),
+    );
+    runner.draw_frame();
+    return runner;
+}
+
+#[test]
+fn keyboard_handler_gets_key_and_event_info() {
+    let escapes = Rc::new(RefCell::new(vec![]));
+    let mut runner = removable_runner(&escapes);
+    runner.on_key_down(KeyCode::Tab, Modifiers::none(), false);
+    let focused = runner.focus_manager.focused().unwrap().clone();
+
+    runner.on_key_down(KeyCode::Escape, Modifiers::none(), false);
+    assert_eq!(*escapes.borrow(), vec![focused]);
+    // The handler replaced the provided value
+    assert!(!*lookup::<bool>(&runner.app).unwrap());
+}
+
+#[test]
+fn focus_is_dropped_when_the_focused_widget_is_removed() {
+    let escapes = Rc::new(RefCell::new(vec![]));
+    let mut runner = removable_runner(&escapes);
+    runner.on_key_down(KeyCode::Tab, Modifiers::none(), false);
+    assert!(runner.focus_manager.focused().is_some());
+
+    runner.on_key_down(KeyCode::Escape, Modifiers::none(), false);
+    assert_eq!(runner.focus_manager.focused(), None);
+
+    // Tab finds nothing to focus rather than stepping from a missing key
+    runner.on_key_down(KeyCode::Tab, Modifiers::none(), false);
+    assert_eq!(runner.focus_manager.focused(), None);
+    runner.on_key_down(KeyCode::Escape, Modifiers::none(), false);
+    assert_eq!(escapes.borrow().len(), 1);
+}
@@ -0,0 +1,112 @@
+use casserole_core::{
+    graphics::Size,
+    headless::HeadlessPlatform,
+    ui_library::{
+        expanded::Expanded,
+        grid::{Grid, GridCell, GridTrack},
+        scroll_view::{ScrollAxis, ScrollView},
+        sized_box::SizedBox,
+        theme::Theme,
+        Widget,
+    },
+    AppRunner,
+};
+
+fn sized(width: f64, height: f64) -> Box<dyn Widget> {
+    return SizedBox::new(Size { width, height }, Expanded::new());
+}
+
+fn platform() -> HeadlessPlatform {
+    return HeadlessPlatform::new(Size {
+        width: 300.,
+        height: 200.,
+    });
+}
+
+fn warnings(platform: &HeadlessPlatform) -> usize {
+    return platform
+        .logger
+        .messages()
+        .iter()
+        .filter(|x| x.starts_with("Warning"))
+        .count();
+}
+
+/// Where each of `grid`'s cells was put, in the grid's coordinates
+fn cell_positions(grid: &dyn Widget) -> Vec<(f64, f64)> {
+    return grid
+        .get_children()
+        .iter()
+        .map(|x| (x.get_position().x, x.get_position().y))
+        .collect();
+}
+
+#[test]
+fn spanning_cell_widens_auto_column() {
+    let app = *Grid::new(
+        vec![GridTrack::Auto, GridTrack::Fixed(40.)],
+        vec![GridTrack::Auto, GridTrack::Auto],
+        vec![
+            GridCell::new(0, 0, sized(30., 10.)),
+            GridCell::new(0, 1, sized(10., 10.)),
+            // Needs 60 more than the fixed column gives it, which only the
+            // auto column can make up
+            GridCell::new(1, 0, sized(100., 20.)).with_span(1, 2),
+        ],
+    );
+    let runner = AppRunner::new(platform(), app, Theme::light());
+    assert_eq!(
+        cell_positions(&runner.app),
+        vec![(0., 0.), (60., 0.), (0., 10.)]
+    );
+}
+
+#[test]
+fn fraction_tracks_are_auto_on_an_unbounded_axis() {
+    let grid = || {
+        return Grid::new(
+            vec![GridTrack::Fraction(1.), GridTrack::Fraction(2.)],
+            vec![GridTrack::Auto],
+            vec![
+                GridCell::new(0, 0, sized(30., 10.)),
+                GridCell::new(0, 1, sized(50., 10.)),
+            ],
+        );
+    };
+
+    let bounded = AppRunner::new(platform(), *grid(), Theme::light());
+    assert_eq!(cell_positions(&bounded.app), vec![(0., 0.), (100., 0.)]);
+
+    // A horizontal scroll view gives its child as much width as it likes
+    let unbounded = AppRunner::new(
+        platform(),
+        *ScrollView::new(ScrollAxis::Horizontal, grid()),
+        Theme::light(),
+    );
+    assert_eq!(
+        cell_positions(unbounded.app.get_children()[0]),
+        vec![(0., 0.), (30., 0.)]
+    );
+}
+
+#[test]
+fn cells_outside_the_grid_add_auto_tracks() {
+    let app = *Grid::new(
+        vec![GridTrack::Fixed(50.)],
+        vec![GridTrack::Fixed(20.)],
+        vec![
+            GridCell::new(0, 0, sized(10., 10.)),
+            GridCell::new(1, 1, sized(30., 15.)).with_span(1, 2),
+        ],
+    );
+    let mut runner = AppRunner::new(platform(), app, Theme::light());
+    assert_eq!(cell_positions(&runner.app), vec![(0., 0.), (50., 20.)]);
+    assert_eq!(warnings(&runner.platform), 1);
+
+    runner.platform.graphics.set_screen_dimensions(Size {
+        width: 200.,
+        height: 100.,
+    });
+    runner.on_window_resize();
+    assert_eq!(warnings(&runner.platform), 1);
+}
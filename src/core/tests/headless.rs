@@ -0,0 +1,467 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+};
+
+use casserole_core::{
+    event_handlers::{Modifiers, MouseButton},
+    graphics::{display_list::DisplayList, Color, Position, Rect, Size},
+    headless::HeadlessPlatform,
+    platform::Platform,
+    state::StateManager,
+    ui_library::{
+        compound::hoverable::Hoverable,
+        constraints::BoxConstraints,
+        container::Container,
+        expanded::Expanded,
+        list::{
+            CrossAxisAlignment, CrossAxisSize, List, ListDirection, MainAxisAlignment, MainAxisSize,
+        },
+        scroll_view::{ScrollAxis, ScrollView},
+        sized_box::SizedBox,
+        theme::Theme,
+        Key, Widget, WidgetData,
+    },
+    widget_default_methods, AppRunner,
+};
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+fn idle_color() -> Color {
+    return Color::rgb(0, 0, 255);
+}
+
+fn hovered_color() -> Color {
+    return Color::rgb(255, 0, 0);
+}
+
+/// A 100x50 box in the middle of the screen which changes colour while the
+/// pointer is over it
+fn hover_box() -> List {
+    return *List::new(
+        ListDirection::Column,
+        MainAxisAlignment::Center,
+        CrossAxisAlignment::Center,
+        MainAxisSize::Max,
+        CrossAxisSize::Max,
+        vec![Hoverable::new(Box::new(|is_hovered| {
+            SizedBox::new(
+                Size {
+                    width: 100.,
+                    height: 50.,
+                },
+                Container::new(
+                    if is_hovered {
+                        hovered_color()
+                    } else {
+                        idle_color()
+                    },
+                    Expanded::new(),
+                ),
+            )
+        }))],
+    );
+}
+
+fn runner(width: f64, height: f64) -> AppRunner<HeadlessPlatform, List> {
+    let runner = AppRunner::new(
+        HeadlessPlatform::new(Size { width, height }),
+        hover_box(),
+        Theme::light(),
+    );
+    runner.draw_frame();
+    return runner;
+}
+
+fn background_color() -> Color {
+    return Color::rgb(0, 255, 0);
+}
+
+#[test]
+fn hovering_repaints_and_logs() {
+    let mut runner = runner(400., 300.);
+    assert_eq!(runner.platform.graphics.get_pixel(200, 150), idle_color());
+    assert_eq!(
+        runner.platform.graphics.get_pixel(10, 10),
+        Color::rgb(0, 0, 0)
+    );
+    runner.platform.logger.clear();
+
+    runner.on_mouse_move(Position { x: 200., y: 150. });
+    assert_eq!(
+        runner.platform.graphics.get_pixel(200, 150),
+        hovered_color()
+    );
+    assert_eq!(
+        runner.platform.graphics.get_pixel(151, 126),
+        hovered_color()
+    );
+    assert_eq!(
+        runner.platform.graphics.get_pixel(149, 124),
+        Color::rgb(0, 0, 0)
+    );
+    assert!(runner
+        .platform
+        .logger
+        .messages()
+        .iter()
+        .any(|x| x.starts_with("Mouse entered hoverable")));
+
+    runner.platform.logger.clear();
+    runner.on_mouse_move(Position { x: 10., y: 10. });
+    assert_eq!(runner.platform.graphics.get_pixel(200, 150), idle_color());
+    assert!(runner
+        .platform
+        .logger
+        .messages()
+        .iter()
+        .any(|x| x.starts_with("Mouse left hoverable")));
+}
+
+#[test]
+fn leaving_the_window_unhovers() {
+    let mut runner = runner(400., 300.);
+    runner.on_mouse_move(Position { x: 200., y: 150. });
+    assert_eq!(
+        runner.platform.graphics.get_pixel(200, 150),
+        hovered_color()
+    );
+
+    runner.on_mouse_leave_window();
+    assert_eq!(runner.platform.graphics.get_pixel(200, 150), idle_color());
+}
+
+#[test]
+fn moving_within_a_widget_does_not_repaint() {
+    let mut runner = runner(400., 300.);
+    runner.on_mouse_move(Position { x: 200., y: 150. });
+    let frames = runner.platform.graphics.frame_count();
+
+    runner.on_mouse_move(Position { x: 210., y: 155. });
+    assert_eq!(runner.platform.graphics.frame_count(), frames);
+}
+
+#[test]
+fn hovering_repaints_only_the_hovered_widget() {
+    let mut runner = AppRunner::new(
+        HeadlessPlatform::new(Size {
+            width: 400.,
+            height: 300.,
+        }),
+        *Container::new(background_color(), Box::new(hover_box())),
+        Theme::light(),
+    );
+    runner.draw_frame();
+    assert_eq!(
+        runner.platform.graphics.get_pixel(10, 10),
+        background_color()
+    );
+    // Blanks the framebuffer, so that only what's repainted from here on
+    // shows up
+    runner.platform.graphics.set_screen_dimensions(Size {
+        width: 400.,
+        height: 300.,
+    });
+
+    runner.on_mouse_move(Position { x: 200., y: 150. });
+    assert_eq!(
+        runner.platform.graphics.get_pixel(200, 150),
+        hovered_color()
+    );
+    assert_eq!(
+        runner.platform.graphics.get_pixel(151, 126),
+        hovered_color()
+    );
+    assert_eq!(
+        runner.platform.graphics.get_pixel(10, 10),
+        Color::rgb(0, 0, 0)
+    );
+    assert_eq!(
+        runner.platform.graphics.get_pixel(149, 124),
+        Color::rgb(0, 0, 0)
+    );
+}
+
+#[test]
+fn resizing_lays_out_again() {
+    let mut runner = runner(400., 300.);
+    runner.platform.logger.clear();
+
+    runner.platform.graphics.set_screen_dimensions(Size {
+        width: 200.,
+        height: 100.,
+    });
+    runner.on_window_resize();
+
+    assert_eq!(
+        runner.platform.logger.messages(),
+        vec!["width: 200, height: 100"]
+    );
+    assert_eq!(runner.platform.graphics.get_pixel(51, 26), idle_color());
+    assert_eq!(runner.platform.graphics.get_pixel(148, 73), idle_color());
+    assert_eq!(
+        runner.platform.graphics.get_pixel(49, 24),
+        Color::rgb(0, 0, 0)
+    );
+    assert_eq!(
+        runner.platform.graphics.get_pixel(151, 76),
+        Color::rgb(0, 0, 0)
+    );
+
+    runner.on_mouse_move(Position { x: 100., y: 50. });
+    assert_eq!(runner.platform.graphics.get_pixel(100, 50), hovered_color());
+}
+
+/// A 50x50 square which counts how many times it's drawn
+#[derive(Debug, KeySegment)]
+struct CountingBox {
+    widget_data: WidgetData,
+    draws: Rc<Cell<usize>>,
+}
+
+impl CountingBox {
+    fn new(draws: &Rc<Cell<usize>>) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            draws: draws.clone(),
+        });
+    }
+}
+
+impl Widget for CountingBox {
+    widget_default_methods!();
+
+    fn layout(&mut self, _constraints: &BoxConstraints, _platform: &dyn Platform) -> Size {
+        return Size {
+            width: 50.,
+            height: 50.,
+        };
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        self.draws.set(self.draws.get() + 1);
+        display_list.fill_rect(
+            &(parent_position + self.widget_data.position.clone()),
+            &self.widget_data.size,
+            &idle_color(),
+        );
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        return vec![];
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        return vec![];
+    }
+}
+
+#[test]
+fn partial_repaint_skips_widgets_outside_dirty_regions() {
+    let draws = [
+        Rc::new(Cell::new(0)),
+        Rc::new(Cell::new(0)),
+        Rc::new(Cell::new(0)),
+    ];
+    let app = *List::new(
+        ListDirection::Row,
+        MainAxisAlignment::SpaceBetween,
+        CrossAxisAlignment::Start,
+        MainAxisSize::Max,
+        CrossAxisSize::Max,
+        draws
+            .iter()
+            .map(|x| CountingBox::new(x) as Box<dyn Widget>)
+            .collect(),
+    );
+    let runner = AppRunner::new(
+        HeadlessPlatform::new(Size {
+            width: 300.,
+            height: 50.,
+        }),
+        app,
+        Theme::light(),
+    );
+    runner.draw_frame();
+    let counts = || draws.iter().map(|x| x.get()).collect::<Vec<usize>>();
+    assert_eq!(counts(), vec![1, 1, 1]);
+
+    // Opposite ends of the screen are repainted separately rather than as
+    // one rect spanning the middle box too
+    runner.redraw_dirty_regions(&[
+        Rect::new(
+            Position { x: 10., y: 10. },
+            Size {
+                width: 10.,
+                height: 10.,
+            },
+        ),
+        Rect::new(
+            Position { x: 260., y: 10. },
+            Size {
+                width: 10.,
+                height: 10.,
+            },
+        ),
+    ]);
+    assert_eq!(counts(), vec![2, 1, 2]);
+    assert_eq!(runner.platform.graphics.get_pixel(15, 15), idle_color());
+    assert_eq!(runner.platform.graphics.get_pixel(265, 15), idle_color());
+}
+
+#[test]
+fn overlapping_dirty_regions_are_merged() {
+    let rect = |x: f64, y: f64, width: f64, height: f64| {
+        return Rect::new(Position { x, y }, Size { width, height });
+    };
+    let merged = Rect::merge_overlapping(&[
+        rect(0., 0., 10., 10.),
+        rect(100., 0., 10., 10.),
+        rect(5., 5., 10., 10.),
+        rect(50., 50., 0., 10.),
+        // Overlaps only the union of the first and third
+        rect(12., 12., 10., 10.),
+    ]);
+    assert_eq!(
+        merged,
+        vec![rect(100., 0., 10., 10.), rect(0., 0., 22., 22.)]
+    );
+}
+
+fn stripe_colors() -> Vec<Color> {
+    return vec![
+        Color::rgb(255, 0, 0),
+        Color::rgb(0, 255, 0),
+        Color::rgb(0, 0, 255),
+    ];
+}
+
+/// A 100x100 vertical scroll view over three 100x100 stripes
+fn scroll_runner() -> AppRunner<HeadlessPlatform, ScrollView> {
+    let stripes: Vec<Box<dyn Widget>> = stripe_colors()
+        .into_iter()
+        .map(|color| -> Box<dyn Widget> {
+            SizedBox::new(
+                Size {
+                    width: 100.,
+                    height: 100.,
+                },
+                Container::new(color, Expanded::new()),
+            )
+        })
+        .collect();
+    let runner = AppRunner::new(
+        HeadlessPlatform::new(Size {
+            width: 100.,
+            height: 100.,
+        }),
+        *ScrollView::new(
+            ScrollAxis::Vertical,
+            List::new(
+                ListDirection::Column,
+                MainAxisAlignment::Start,
+                CrossAxisAlignment::Start,
+                MainAxisSize::Min,
+                CrossAxisSize::Max,
+                stripes,
+            ),
+        ),
+        Theme::light(),
+    );
+    runner.draw_frame();
+    return runner;
+}
+
+/// The stripe showing at the top of the scroll view
+fn top_stripe(runner: &AppRunner<HeadlessPlatform, ScrollView>) -> usize {
+    let color = runner.platform.graphics.get_pixel(20, 5);
+    return stripe_colors().iter().position(|x| *x == color).unwrap();
+}
+
+#[test]
+fn wheel_scrolls_until_the_end_of_the_content() {
+    let mut runner = scroll_runner();
+    assert_eq!(top_stripe(&runner), 0);
+
+    let position = Position { x: 50., y: 50. };
+    runner.on_mouse_wheel(position.clone(), 0., 100., Modifiers::none());
+    assert_eq!(top_stripe(&runner), 1);
+    runner.on_mouse_wheel(position.clone(), 0., 500., Modifiers::none());
+    assert_eq!(top_stripe(&runner), 2);
+    assert_eq!(
+        runner.platform.graphics.get_pixel(20, 95),
+        stripe_colors()[2]
+    );
+    runner.on_mouse_wheel(position, 0., -150., Modifiers::none());
+    assert_eq!(top_stripe(&runner), 0);
+    assert_eq!(
+        runner.platform.graphics.get_pixel(20, 55),
+        stripe_colors()[1]
+    );
+}
+
+#[test]
+fn clicking_the_track_pages_towards_the_pointer() {
+    let mut runner = scroll_runner();
+    // The scrollbar runs down the right edge, and its thumb is a third of
+    // the track long
+    let click = |runner: &mut AppRunner<HeadlessPlatform, ScrollView>, y: f64| {
+        let position = Position { x: 96., y };
+        runner.on_mouse_down(position.clone(), MouseButton::Left, Modifiers::none());
+        runner.on_mouse_up(position, MouseButton::Left, Modifiers::none(), 1);
+    };
+
+    click(&mut runner, 90.);
+    assert_eq!(top_stripe(&runner), 1);
+    click(&mut runner, 90.);
+    assert_eq!(top_stripe(&runner), 2);
+    // The thumb is at the bottom now, so clicking there does nothing
+    click(&mut runner, 90.);
+    assert_eq!(top_stripe(&runner), 2);
+    click(&mut runner, 10.);
+    assert_eq!(top_stripe(&runner), 1);
+}
+
+#[test]
+fn scrollbar_colours_follow_the_theme() {
+    let app = *ScrollView::new(
+        ScrollAxis::Vertical,
+        SizedBox::new(
+            Size {
+                width: 100.,
+                height: 400.,
+            },
+            Container::new(idle_color(), Expanded::new()),
+        ),
+    );
+    let mut runner = AppRunner::new(
+        HeadlessPlatform::new(Size {
+            width: 100.,
+            height: 100.,
+        }),
+        app,
+        Theme::light(),
+    );
+    runner.set_theme(Theme::high_contrast());
+    let palette = Theme::high_contrast().palette;
+    assert_eq!(
+        runner.platform.graphics.get_pixel(96, 5),
+        palette.scrollbar_thumb
+    );
+    assert_eq!(
+        runner.platform.graphics.get_pixel(96, 90),
+        palette.scrollbar_track
+    );
+}
+
+#[test]
+fn initial_theme_is_provided_at_the_root() {
+    let runner = AppRunner::new(
+        HeadlessPlatform::new(Size {
+            width: 100.,
+            height: 100.,
+        }),
+        hover_box(),
+        Theme::high_contrast(),
+    );
+    assert_eq!(*Theme::of(&runner.app), Theme::high_contrast());
+}
@@ -0,0 +1,141 @@
+use casserole_core::{
+    graphics::{bitmap::Bitmap, Color, Size},
+    headless::{graphics::Framebuffer, snapshot::render_widget, HeadlessPlatform},
+    ui_library::{
+        constraints::BoxConstraints,
+        image::{Image, ImageFit},
+        list::{
+            CrossAxisAlignment, CrossAxisSize, List, ListDirection, MainAxisAlignment, MainAxisSize,
+        },
+        sized_box::SizedBox,
+        Widget,
+    },
+};
+
+fn size(width: f64, height: f64) -> Size {
+    return Size { width, height };
+}
+
+fn red() -> Color {
+    return Color::rgb(255, 0, 0);
+}
+
+fn blue() -> Color {
+    return Color::rgb(0, 0, 255);
+}
+
+/// A bitmap whose left half is red and right half is blue
+fn halves(width: usize, height: usize) -> Bitmap {
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for _ in 0..height {
+        for x in 0..width {
+            let color = if x < width / 2 { red() } else { blue() };
+            pixels.extend([color.r, color.g, color.b, color.a]);
+        }
+    }
+    return Bitmap::new(width, height, pixels);
+}
+
+fn lay_out(mut image: Box<dyn Widget>, constraints: BoxConstraints) -> Size {
+    let platform = HeadlessPlatform::new(size(100., 100.));
+    return image.set_layout(&constraints, &platform);
+}
+
+/// Renders `widget` in the top-left corner of a 100x100 screen
+fn render(widget: Box<dyn Widget>) -> Framebuffer {
+    return render_widget(
+        List::new(
+            ListDirection::Column,
+            MainAxisAlignment::Start,
+            CrossAxisAlignment::Start,
+            MainAxisSize::Max,
+            CrossAxisSize::Max,
+            vec![widget],
+        ),
+        size(100., 100.),
+    );
+}
+
+#[test]
+fn image_takes_the_size_of_its_bitmap() {
+    assert_eq!(
+        lay_out(
+            Image::new(halves(40, 20)),
+            BoxConstraints::loose(&size(100., 100.))
+        ),
+        size(40., 20.)
+    );
+}
+
+#[test]
+fn image_keeps_its_aspect_ratio_within_constraints() {
+    let image = || Image::new(halves(40, 20));
+    assert_eq!(
+        lay_out(image(), BoxConstraints::loose(&size(20., 100.))),
+        size(20., 10.)
+    );
+    let tall_minimum = BoxConstraints {
+        min_width: 0.,
+        max_width: 100.,
+        min_height: 30.,
+        max_height: 100.,
+    };
+    assert_eq!(lay_out(image(), tall_minimum), size(60., 30.));
+    // Tight constraints are followed even if they stretch the image
+    assert_eq!(
+        lay_out(image(), BoxConstraints::tight(&size(30., 30.))),
+        size(30., 30.)
+    );
+}
+
+#[test]
+fn fit_scales_the_bitmap_to_the_box() {
+    let bitmap = size(40., 20.);
+    let image = size(30., 30.);
+    assert_eq!(ImageFit::Contain.apply(&bitmap, &image), size(30., 15.));
+    assert_eq!(ImageFit::Cover.apply(&bitmap, &image), size(60., 30.));
+    assert_eq!(ImageFit::Fill.apply(&bitmap, &image), size(30., 30.));
+}
+
+#[test]
+fn bitmap_is_stretched_over_the_image() {
+    let framebuffer = render(Image::new(halves(2, 1)).with_fit(ImageFit::Fill));
+    // Nothing sizes the image, so it takes the bitmap's own size
+    assert_eq!(framebuffer.get_pixel(0, 0), red());
+    assert_eq!(framebuffer.get_pixel(1, 0), blue());
+
+    let framebuffer = render(SizedBox::new(
+        size(40., 20.),
+        Image::new(halves(2, 1)).with_fit(ImageFit::Fill),
+    ));
+    assert_eq!(framebuffer.get_pixel(5, 5), red());
+    assert_eq!(framebuffer.get_pixel(19, 19), red());
+    assert_eq!(framebuffer.get_pixel(20, 0), blue());
+    assert_eq!(framebuffer.get_pixel(39, 19), blue());
+    assert_ne!(framebuffer.get_pixel(40, 10), blue());
+    assert_ne!(framebuffer.get_pixel(10, 20), red());
+}
+
+#[test]
+fn contained_bitmap_is_aligned_in_the_image() {
+    // Drawn 40x20 in the middle of the 40x40 image
+    let framebuffer = render(SizedBox::new(size(40., 40.), Image::new(halves(2, 1))));
+    assert_ne!(framebuffer.get_pixel(5, 5), red());
+    assert_eq!(framebuffer.get_pixel(5, 10), red());
+    assert_eq!(framebuffer.get_pixel(35, 29), blue());
+    assert_ne!(framebuffer.get_pixel(35, 30), blue());
+}
+
+#[test]
+fn covering_bitmap_is_clipped_to_the_image() {
+    // Drawn 40x20 from (-10, 0), so only the middle half of it shows
+    let framebuffer = render(SizedBox::new(
+        size(20., 20.),
+        Image::new(halves(2, 1)).with_fit(ImageFit::Cover),
+    ));
+    assert_eq!(framebuffer.get_pixel(0, 10), red());
+    assert_eq!(framebuffer.get_pixel(9, 10), red());
+    assert_eq!(framebuffer.get_pixel(10, 10), blue());
+    assert_eq!(framebuffer.get_pixel(19, 10), blue());
+    assert_ne!(framebuffer.get_pixel(25, 10), blue());
+}
@@ -0,0 +1,209 @@
+use casserole_core::{
+    graphics::{Position, Size},
+    headless::HeadlessPlatform,
+    ui_library::{
+        align::{Align, Center},
+        aspect_ratio::AspectRatio,
+        constrained_box::ConstrainedBox,
+        constraints::BoxConstraints,
+        expanded::Expanded,
+        fractionally_sized_box::FractionallySizedBox,
+        sized_box::SizedBox,
+        Alignment, Widget,
+    },
+};
+
+fn size(width: f64, height: f64) -> Size {
+    return Size { width, height };
+}
+
+fn position(x: f64, y: f64) -> Position {
+    return Position { x, y };
+}
+
+fn sized(width: f64, height: f64) -> Box<dyn Widget> {
+    return SizedBox::new(size(width, height), Expanded::new());
+}
+
+fn tight(width: f64, height: f64) -> BoxConstraints {
+    return BoxConstraints::tight(&size(width, height));
+}
+
+fn loose(width: f64, height: f64) -> BoxConstraints {
+    return BoxConstraints::loose(&size(width, height));
+}
+
+/// Lays out `widget` within `constraints` and returns its size, along with
+/// where its only child went and how big it is
+fn lay_out(mut widget: Box<dyn Widget>, constraints: BoxConstraints) -> (Size, Position, Size) {
+    let platform = HeadlessPlatform::new(size(100., 100.));
+    let own_size = widget.set_layout(&constraints, &platform);
+    let child = widget.get_children()[0];
+    return (
+        own_size,
+        child.get_position().clone(),
+        child.get_size().clone(),
+    );
+}
+
+#[test]
+fn align_fills_bounded_space_and_places_its_child() {
+    let align = || Align::new(Alignment::bottom_right(), sized(20., 10.));
+    assert_eq!(
+        lay_out(align(), tight(100., 80.)),
+        (size(100., 80.), position(80., 70.), size(20., 10.))
+    );
+    assert_eq!(
+        lay_out(align(), loose(100., 80.)),
+        (size(100., 80.), position(80., 70.), size(20., 10.))
+    );
+    // The child gets loose constraints, which an expanding child still fills
+    assert_eq!(
+        lay_out(
+            Align::new(Alignment::top_left(), Expanded::new()),
+            tight(100., 80.)
+        )
+        .2,
+        size(100., 80.)
+    );
+}
+
+#[test]
+fn align_fits_its_child_along_an_unbounded_axis() {
+    let constraints = BoxConstraints {
+        min_width: 0.,
+        max_width: f64::INFINITY,
+        min_height: 0.,
+        max_height: 80.,
+    };
+    assert_eq!(
+        lay_out(
+            Align::new(Alignment::bottom_right(), sized(20., 10.)),
+            constraints
+        ),
+        (size(20., 80.), position(0., 70.), size(20., 10.))
+    );
+}
+
+#[test]
+fn center_puts_its_child_in_the_middle() {
+    assert_eq!(
+        lay_out(Center::new(sized(20., 10.)), tight(100., 80.)),
+        (size(100., 80.), position(40., 35.), size(20., 10.))
+    );
+    assert_eq!(
+        lay_out(Center::new(sized(20., 10.)), loose(60., 30.)),
+        (size(60., 30.), position(20., 10.), size(20., 10.))
+    );
+}
+
+#[test]
+fn aspect_ratio_is_as_large_as_fits() {
+    let wide = || AspectRatio::new(2., Expanded::new());
+    assert_eq!(
+        lay_out(wide(), loose(100., 100.)),
+        (size(100., 50.), position(0., 0.), size(100., 50.))
+    );
+    assert_eq!(lay_out(wide(), loose(100., 30.)).0, size(60., 30.));
+    let unbounded_width = BoxConstraints {
+        min_width: 0.,
+        max_width: f64::INFINITY,
+        min_height: 0.,
+        max_height: 40.,
+    };
+    assert_eq!(lay_out(wide(), unbounded_width).0, size(80., 40.));
+}
+
+#[test]
+fn aspect_ratio_gives_way_to_tight_and_minimum_constraints() {
+    let wide = || AspectRatio::new(2., Expanded::new());
+    assert_eq!(
+        lay_out(wide(), tight(100., 100.)),
+        (size(100., 100.), position(0., 0.), size(100., 100.))
+    );
+    let tall_minimum = BoxConstraints {
+        min_width: 0.,
+        max_width: 100.,
+        min_height: 80.,
+        max_height: 100.,
+    };
+    assert_eq!(lay_out(wide(), tall_minimum).0, size(100., 80.));
+}
+
+#[test]
+fn constrained_box_narrows_its_parents_constraints() {
+    let limits = BoxConstraints {
+        min_width: 50.,
+        max_width: 80.,
+        min_height: 0.,
+        max_height: f64::INFINITY,
+    };
+    assert_eq!(
+        lay_out(
+            ConstrainedBox::new(limits.clone(), Expanded::new()),
+            loose(100., 100.)
+        ),
+        (size(80., 100.), position(0., 0.), size(80., 100.))
+    );
+    assert_eq!(
+        lay_out(
+            ConstrainedBox::new(limits.clone(), sized(20., 20.)),
+            loose(100., 100.)
+        )
+        .0,
+        size(50., 20.)
+    );
+    // The parent's constraints win where the two disagree
+    assert_eq!(
+        lay_out(
+            ConstrainedBox::new(limits, sized(20., 20.)),
+            tight(100., 100.)
+        )
+        .0,
+        size(100., 100.)
+    );
+}
+
+#[test]
+fn fractionally_sized_box_sizes_its_child_to_a_fraction() {
+    assert_eq!(
+        lay_out(
+            FractionallySizedBox::new(Some(0.5), None, Expanded::new()),
+            tight(100., 80.)
+        ),
+        (size(100., 80.), position(25., 0.), size(50., 80.))
+    );
+    // The height is left to the child, which is loose as the box is
+    assert_eq!(
+        lay_out(
+            FractionallySizedBox::new(Some(0.5), None, sized(10., 10.))
+                .with_alignment(Alignment::top_left()),
+            loose(100., 80.)
+        ),
+        (size(100., 80.), position(0., 0.), size(50., 10.))
+    );
+    assert_eq!(
+        lay_out(
+            FractionallySizedBox::new(Some(0.25), Some(0.5), Expanded::new()),
+            loose(100., 80.)
+        ),
+        (size(100., 80.), position(37.5, 20.), size(25., 40.))
+    );
+}
+
+#[test]
+fn fractionally_sized_box_ignores_its_factor_along_an_unbounded_axis() {
+    let unbounded_height = BoxConstraints {
+        min_width: 0.,
+        max_width: 100.,
+        min_height: 0.,
+        max_height: f64::INFINITY,
+    };
+    assert_eq!(
+        lay_out(
+            FractionallySizedBox::new(Some(0.5), Some(0.5), sized(10., 30.)),
+            unbounded_height
+        ),
+        (size(100., 30.), position(25., 0.), size(50., 30.))
+    );
+}
@@ -0,0 +1,165 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+};
+
+use casserole_core::{
+    graphics::{display_list::DisplayList, Position, Size},
+    headless::HeadlessPlatform,
+    platform::Platform,
+    state::StateManager,
+    ui_library::{
+        constraints::BoxConstraints,
+        list::{
+            CrossAxisAlignment, CrossAxisSize, List, ListDirection, MainAxisAlignment, MainAxisSize,
+        },
+        sized_box::SizedBox,
+        theme::Theme,
+        Key, Widget, WidgetData,
+    },
+    widget_default_methods, AppRunner,
+};
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+/// Counts how many times it's laid out. Without a child it's a 50x50 box,
+/// otherwise it's as big as its child.
+#[derive(Debug, KeySegment)]
+struct Counter {
+    widget_data: WidgetData,
+    layouts: Rc<Cell<usize>>,
+    child: Option<Box<dyn Widget>>,
+}
+
+impl Counter {
+    fn new(layouts: &Rc<Cell<usize>>, child: Option<Box<dyn Widget>>) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            layouts: layouts.clone(),
+            child,
+        });
+    }
+}
+
+impl Widget for Counter {
+    widget_default_methods!();
+
+    fn layout(&mut self, constraints: &BoxConstraints, platform: &dyn Platform) -> Size {
+        self.layouts.set(self.layouts.get() + 1);
+        return match &mut self.child {
+            Some(child) => {
+                let size = child.set_layout(constraints, platform);
+                child.set_position(Position::origin());
+                size
+            }
+            None => Size {
+                width: 50.,
+                height: 50.,
+            },
+        };
+    }
+
+    fn draw(&self, parent_position: Position, display_list: &mut DisplayList) -> () {
+        if let Some(child) = &self.child {
+            child.paint(
+                parent_position + self.widget_data.position.clone(),
+                display_list,
+            );
+        }
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        return match &mut self.child {
+            Some(x) => vec![x.as_mut()],
+            None => vec![],
+        };
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        return match &self.child {
+            Some(x) => vec![x.as_ref()],
+            None => vec![],
+        };
+    }
+}
+
+fn counters(n: usize) -> Vec<Rc<Cell<usize>>> {
+    return (0..n).map(|_| Rc::new(Cell::new(0))).collect();
+}
+
+fn counts(counters: &[Rc<Cell<usize>>]) -> Vec<usize> {
+    return counters.iter().map(|x| x.get()).collect();
+}
+
+fn column(children: Vec<Box<dyn Widget>>) -> Box<List> {
+    return List::new(
+        ListDirection::Column,
+        MainAxisAlignment::Start,
+        CrossAxisAlignment::Start,
+        MainAxisSize::Max,
+        CrossAxisSize::Max,
+        children,
+    );
+}
+
+fn platform() -> HeadlessPlatform {
+    return HeadlessPlatform::new(Size {
+        width: 200.,
+        height: 200.,
+    });
+}
+
+#[test]
+fn unchanged_constraints_skip_layout() {
+    let layouts = counters(2);
+    let app = *column(vec![
+        // Always laid out exactly 50x50, whatever the screen size
+        SizedBox::new(
+            Size {
+                width: 50.,
+                height: 50.,
+            },
+            Counter::new(&layouts[0], None),
+        ),
+        Counter::new(&layouts[1], None),
+    ]);
+    let mut runner = AppRunner::new(platform(), app, Theme::light());
+    assert_eq!(counts(&layouts), vec![1, 1]);
+
+    runner.on_window_resize();
+    assert_eq!(counts(&layouts), vec![1, 1]);
+
+    runner.platform.graphics.set_screen_dimensions(Size {
+        width: 300.,
+        height: 250.,
+    });
+    runner.on_window_resize();
+    assert_eq!(counts(&layouts), vec![1, 2]);
+}
+
+#[test]
+fn invalidating_a_key_lays_out_only_it_and_its_ancestors() {
+    let layouts = counters(4);
+    let app = *Counter::new(
+        &layouts[0],
+        Some(column(vec![
+            Counter::new(&layouts[1], None),
+            Counter::new(&layouts[2], Some(Counter::new(&layouts[3], None))),
+        ])),
+    );
+    let mut runner = AppRunner::new(platform(), app, Theme::light());
+    assert_eq!(counts(&layouts), vec![1, 1, 1, 1]);
+
+    let key = runner.app.get_children()[0].get_children()[1]
+        .get_key()
+        .clone();
+    runner.app.invalidate_layout_for_key(&key);
+    runner.app.set_layout(
+        &BoxConstraints::tight(&Size {
+            width: 200.,
+            height: 200.,
+        }),
+        &runner.platform,
+    );
+    // Its sibling and its child get the same constraints as before
+    assert_eq!(counts(&layouts), vec![2, 1, 2, 1]);
+}
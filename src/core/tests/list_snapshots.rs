@@ -0,0 +1,146 @@
+use std::path::PathBuf;
+
+use casserole_core::{
+    graphics::{Color, Size},
+    headless::snapshot::assert_snapshot,
+    ui_library::{
+        container::Container,
+        expanded::Expanded,
+        list::{
+            CrossAxisAlignment, CrossAxisSize, List, ListDirection, MainAxisAlignment, MainAxisSize,
+        },
+        sized_box::SizedBox,
+        Widget,
+    },
+};
+
+const MAIN_AXIS_ALIGNMENTS: [&str; 5] = ["start", "center", "end", "space_between", "space_around"];
+const CROSS_AXIS_ALIGNMENTS: [&str; 3] = ["start", "center", "end"];
+
+fn main_axis_alignment(name: &str) -> MainAxisAlignment {
+    return match name {
+        "start" => MainAxisAlignment::Start,
+        "center" => MainAxisAlignment::Center,
+        "end" => MainAxisAlignment::End,
+        "space_between" => MainAxisAlignment::SpaceBetween,
+        "space_around" => MainAxisAlignment::SpaceAround,
+        _ => unreachable!(),
+    };
+}
+
+fn cross_axis_alignment(name: &str) -> CrossAxisAlignment {
+    return match name {
+        "start" => CrossAxisAlignment::Start,
+        "center" => CrossAxisAlignment::Center,
+        "end" => CrossAxisAlignment::End,
+        _ => unreachable!(),
+    };
+}
+
+fn colored_box(width: f64, height: f64, color: Color) -> Box<dyn Widget> {
+    return SizedBox::new(
+        Size { width, height },
+        Container::new(color, Expanded::new()),
+    );
+}
+
+/// Three differently sized and coloured boxes, so that both where each one
+/// goes and which way round they are show up in the snapshot
+fn list(
+    direction: ListDirection,
+    main_axis_alignment: MainAxisAlignment,
+    cross_axis_alignment: CrossAxisAlignment,
+) -> Box<dyn Widget> {
+    return List::new(
+        direction,
+        main_axis_alignment,
+        cross_axis_alignment,
+        MainAxisSize::Max,
+        CrossAxisSize::Max,
+        vec![
+            colored_box(20., 10., Color::rgb(220, 38, 38)),
+            colored_box(30., 20., Color::rgb(34, 197, 94)),
+            colored_box(10., 30., Color::rgb(37, 99, 235)),
+        ],
+    );
+}
+
+/// Three boxes which together are longer than the 100x80 screen, in either
+/// direction
+fn overflowing_list(
+    direction: ListDirection,
+    main_axis_alignment: MainAxisAlignment,
+) -> Box<dyn Widget> {
+    return List::new(
+        direction,
+        main_axis_alignment,
+        CrossAxisAlignment::Start,
+        MainAxisSize::Max,
+        CrossAxisSize::Max,
+        vec![
+            colored_box(50., 40., Color::rgb(220, 38, 38)),
+            colored_box(40., 30., Color::rgb(34, 197, 94)),
+            colored_box(30., 20., Color::rgb(37, 99, 235)),
+        ],
+    );
+}
+
+fn snapshot_path(name: &str) -> PathBuf {
+    return PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots/list")
+        .join(format!("{}.png", name));
+}
+
+fn assert_list_snapshots(direction_name: &str, direction: fn() -> ListDirection) {
+    for main in MAIN_AXIS_ALIGNMENTS {
+        for cross in CROSS_AXIS_ALIGNMENTS {
+            assert_snapshot(
+                list(
+                    direction(),
+                    main_axis_alignment(main),
+                    cross_axis_alignment(cross),
+                ),
+                Size {
+                    width: 100.,
+                    height: 80.,
+                },
+                &snapshot_path(&format!("{}_{}_{}", direction_name, main, cross)),
+            );
+        }
+    }
+}
+
+#[test]
+fn row_alignments() {
+    assert_list_snapshots("row", || ListDirection::Row);
+}
+
+#[test]
+fn column_alignments() {
+    assert_list_snapshots("column", || ListDirection::Column);
+}
+
+fn assert_overflow_snapshots(direction_name: &str, direction: fn() -> ListDirection) {
+    for main in MAIN_AXIS_ALIGNMENTS {
+        assert_snapshot(
+            overflowing_list(direction(), main_axis_alignment(main)),
+            Size {
+                width: 100.,
+                height: 80.,
+            },
+            &snapshot_path(&format!("{}_overflow_{}", direction_name, main)),
+        );
+    }
+}
+
+/// The space left over is negative, so `Center` and `End` push the first
+/// children off the start, and the spaced alignments overlap the children
+#[test]
+fn row_overflow() {
+    assert_overflow_snapshots("row", || ListDirection::Row);
+}
+
+#[test]
+fn column_overflow() {
+    assert_overflow_snapshots("column", || ListDirection::Column);
+}
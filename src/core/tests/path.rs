@@ -0,0 +1,172 @@
+use std::f64::consts::PI;
+
+use casserole_core::{
+    graphics::{
+        display_list::DisplayList,
+        path::{LineCap, LineJoin, Path, StrokeStyle},
+        Color, GraphicsLibrary, Position, Size,
+    },
+    headless::graphics::HeadlessGraphicsLibrary,
+};
+
+fn ink() -> Color {
+    return Color::rgb(255, 255, 255);
+}
+
+fn p(x: f64, y: f64) -> Position {
+    return Position { x, y };
+}
+
+/// Rasterizes whatever `record` draws onto a blank 100x100 screen
+fn render(record: impl FnOnce(&mut DisplayList)) -> HeadlessGraphicsLibrary {
+    let graphics = HeadlessGraphicsLibrary::new(Size {
+        width: 100.,
+        height: 100.,
+    });
+    let mut display_list = DisplayList::new();
+    record(&mut display_list);
+    graphics.draw_display_list(&display_list);
+    return graphics;
+}
+
+fn is_inked(graphics: &HeadlessGraphicsLibrary, x: usize, y: usize) -> bool {
+    return graphics.get_pixel(x, y) == ink();
+}
+
+#[test]
+fn filled_polygon_covers_the_pixels_whose_centres_it_contains() {
+    let graphics = render(|dl| {
+        dl.fill_path(
+            &Path::polygon(&[p(10., 10.), p(30., 10.), p(30., 30.), p(10., 30.)]),
+            &ink(),
+        )
+    });
+    assert!(is_inked(&graphics, 10, 10));
+    assert!(is_inked(&graphics, 29, 29));
+    assert!(!is_inked(&graphics, 9, 10));
+    assert!(!is_inked(&graphics, 30, 29));
+    assert!(!is_inked(&graphics, 29, 30));
+}
+
+#[test]
+fn fill_uses_the_nonzero_winding_rule() {
+    // A pentagram's centre is wound twice, so even-odd would leave it empty
+    let points: Vec<Position> = (0..5)
+        .map(|i| {
+            let angle = -PI / 2. + i as f64 * 4. * PI / 5.;
+            p(50. + 40. * angle.cos(), 50. + 40. * angle.sin())
+        })
+        .collect();
+    let graphics = render(|dl| dl.fill_path(&Path::polygon(&points), &ink()));
+    assert!(is_inked(&graphics, 50, 50));
+    // One of the star's points
+    assert!(is_inked(&graphics, 50, 15));
+    // Between two points, outside the star
+    assert!(!is_inked(&graphics, 80, 20));
+}
+
+#[test]
+fn filled_circle_stays_within_its_radius() {
+    let graphics = render(|dl| dl.fill_path(&Path::circle(p(50., 50.), 10.), &ink()));
+    assert!(is_inked(&graphics, 50, 50));
+    assert!(is_inked(&graphics, 58, 50));
+    assert!(!is_inked(&graphics, 50, 61));
+    assert!(!is_inked(&graphics, 58, 58));
+}
+
+fn stroked_line(cap: LineCap) -> HeadlessGraphicsLibrary {
+    return render(|dl| {
+        dl.stroke_path(
+            &Path::line(p(10., 20.), p(50., 20.)),
+            &StrokeStyle::new(4., ink()).with_cap(cap),
+        )
+    });
+}
+
+#[test]
+fn stroke_covers_half_its_width_on_each_side() {
+    let graphics = stroked_line(LineCap::Butt);
+    assert!(is_inked(&graphics, 30, 18));
+    assert!(is_inked(&graphics, 30, 21));
+    assert!(!is_inked(&graphics, 30, 17));
+    assert!(!is_inked(&graphics, 30, 22));
+}
+
+#[test]
+fn caps_extend_the_stroke_past_its_end_points() {
+    let butt = stroked_line(LineCap::Butt);
+    assert!(is_inked(&butt, 10, 20));
+    assert!(!is_inked(&butt, 9, 20));
+    assert!(!is_inked(&butt, 50, 20));
+
+    let square = stroked_line(LineCap::Square);
+    assert!(is_inked(&square, 8, 20));
+    assert!(is_inked(&square, 8, 18));
+    assert!(!is_inked(&square, 7, 20));
+    assert!(is_inked(&square, 51, 21));
+    assert!(!is_inked(&square, 52, 20));
+
+    let round = stroked_line(LineCap::Round);
+    assert!(is_inked(&round, 8, 20));
+    assert!(!is_inked(&round, 8, 18));
+    assert!(!is_inked(&round, 7, 20));
+}
+
+/// A right-angled corner at (50, 50), stroked 6 wide
+fn stroked_corner(join: LineJoin) -> HeadlessGraphicsLibrary {
+    return render(|dl| {
+        dl.stroke_path(
+            &Path::polyline(&[p(10., 50.), p(50., 50.), p(50., 10.)]),
+            &StrokeStyle::new(6., ink()).with_join(join),
+        )
+    });
+}
+
+#[test]
+fn joins_shape_the_outside_of_a_corner() {
+    // The miter reaches the outer corner at (53, 53)
+    let miter = stroked_corner(LineJoin::Miter);
+    assert!(is_inked(&miter, 52, 52));
+
+    // The bevel and the round join both cut that corner off
+    let bevel = stroked_corner(LineJoin::Bevel);
+    assert!(!is_inked(&bevel, 52, 52));
+    assert!(is_inked(&bevel, 52, 49));
+    assert!(is_inked(&bevel, 49, 52));
+
+    let round = stroked_corner(LineJoin::Round);
+    assert!(!is_inked(&round, 52, 52));
+    assert!(is_inked(&round, 51, 51));
+}
+
+#[test]
+fn sharp_miters_beyond_the_limit_fall_back_to_bevels() {
+    // The corner at (90, 20) is so sharp that a miter would reach far past
+    // four half-widths
+    let graphics = render(|dl| {
+        dl.stroke_path(
+            &Path::polyline(&[p(10., 10.), p(90., 20.), p(10., 30.)]),
+            &StrokeStyle::new(4., ink()).with_join(LineJoin::Miter),
+        )
+    });
+    assert!(is_inked(&graphics, 89, 20));
+    assert!(!is_inked(&graphics, 93, 20));
+    assert!(!is_inked(&graphics, 98, 20));
+}
+
+#[test]
+fn stroked_arc_follows_the_circle_between_its_angles() {
+    // A half circle through the bottom, from (70, 50) round to (30, 50)
+    let graphics = render(|dl| {
+        dl.stroke_path(
+            &Path::arc(p(50., 50.), 20., 0., PI),
+            &StrokeStyle::new(2., ink()),
+        )
+    });
+    assert!(is_inked(&graphics, 50, 70));
+    assert!(is_inked(&graphics, 64, 64));
+    assert!(is_inked(&graphics, 69, 50));
+    assert!(!is_inked(&graphics, 50, 50));
+    assert!(!is_inked(&graphics, 50, 29));
+    assert!(!is_inked(&graphics, 70, 48));
+}
@@ -0,0 +1,293 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use casserole_core::{
+    event_handlers::{
+        HandledEventInfo, Modifiers, MouseButton, MouseButtonEvent, MouseButtonEventKind,
+    },
+    graphics::{display_list::DisplayList, Position, Size, Transform},
+    headless::HeadlessPlatform,
+    platform::Platform,
+    state::StateManager,
+    ui_library::{
+        constraints::BoxConstraints,
+        list::{
+            CrossAxisAlignment, CrossAxisSize, List, ListDirection, MainAxisAlignment, MainAxisSize,
+        },
+        stack::{Stack, StackChild},
+        theme::Theme,
+        transformed::Transformed,
+        Key, Widget, WidgetData,
+    },
+    widget_default_methods, AppRunner,
+};
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+type Events = Rc<RefCell<Vec<MouseButtonEvent>>>;
+
+/// A 50x50 box which captures every mouse button event and records it
+#[derive(Debug, KeySegment)]
+struct Recorder {
+    widget_data: WidgetData,
+    events: Events,
+}
+
+impl Recorder {
+    fn new(events: &Events) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: WidgetData::new(),
+            events: events.clone(),
+        });
+    }
+}
+
+impl Widget for Recorder {
+    widget_default_methods!();
+
+    fn layout(&mut self, _constraints: &BoxConstraints, _platform: &dyn Platform) -> Size {
+        return Size {
+            width: 50.,
+            height: 50.,
+        };
+    }
+
+    fn draw(&self, _parent_position: Position, _display_list: &mut DisplayList) -> () {}
+
+    fn on_mouse_button(
+        &mut self,
+        event: &MouseButtonEvent,
+        _platform: &dyn Platform,
+        _handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        self.events.borrow_mut().push(event.clone());
+        return true;
+    }
+
+    fn get_children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        return vec![];
+    }
+    fn get_children(&self) -> Vec<&dyn Widget> {
+        return vec![];
+    }
+}
+
+fn kinds(events: &Events) -> Vec<MouseButtonEventKind> {
+    return events.borrow().iter().map(|x| x.kind.clone()).collect();
+}
+
+/// Two recorders side by side, the left one from x = 0 to 50 and the right
+/// one from x = 50 to 100
+fn row(left: &Events, right: &Events) -> Box<List> {
+    return List::new(
+        ListDirection::Row,
+        MainAxisAlignment::Start,
+        CrossAxisAlignment::Start,
+        MainAxisSize::Max,
+        CrossAxisSize::Max,
+        vec![Recorder::new(left), Recorder::new(right)],
+    );
+}
+
+fn platform() -> HeadlessPlatform {
+    return HeadlessPlatform::new(Size {
+        width: 200.,
+        height: 100.,
+    });
+}
+
+fn runner(left: &Events, right: &Events) -> AppRunner<HeadlessPlatform, List> {
+    return AppRunner::new(platform(), *row(left, right), Theme::light());
+}
+
+#[test]
+fn press_and_release_is_a_click() {
+    let left: Events = Rc::new(RefCell::new(vec![]));
+    let right: Events = Rc::new(RefCell::new(vec![]));
+    let mut runner = runner(&left, &right);
+    let shift = Modifiers {
+        shift: true,
+        ..Modifiers::none()
+    };
+
+    runner.on_mouse_down(
+        Position { x: 20., y: 20. },
+        MouseButton::Right,
+        shift.clone(),
+    );
+    runner.on_mouse_up(
+        Position { x: 21., y: 20. },
+        MouseButton::Right,
+        shift.clone(),
+        1,
+    );
+    assert_eq!(
+        kinds(&left),
+        vec![
+            MouseButtonEventKind::Down,
+            MouseButtonEventKind::Up,
+            MouseButtonEventKind::Click,
+        ]
+    );
+    for event in left.borrow().iter() {
+        assert_eq!(event.button, MouseButton::Right);
+        assert_eq!(event.modifiers, shift);
+    }
+    assert_eq!(left.borrow()[2].position, Position { x: 21., y: 20. });
+    assert!(right.borrow().is_empty());
+}
+
+#[test]
+fn second_click_is_also_a_double_click() {
+    let left: Events = Rc::new(RefCell::new(vec![]));
+    let right: Events = Rc::new(RefCell::new(vec![]));
+    let mut runner = runner(&left, &right);
+    let position = Position { x: 70., y: 20. };
+
+    runner.on_mouse_down(position.clone(), MouseButton::Left, Modifiers::none());
+    runner.on_mouse_up(position.clone(), MouseButton::Left, Modifiers::none(), 1);
+    runner.on_mouse_down(position.clone(), MouseButton::Left, Modifiers::none());
+    runner.on_mouse_up(position, MouseButton::Left, Modifiers::none(), 2);
+    assert_eq!(
+        kinds(&right),
+        vec![
+            MouseButtonEventKind::Down,
+            MouseButtonEventKind::Up,
+            MouseButtonEventKind::Click,
+            MouseButtonEventKind::Down,
+            MouseButtonEventKind::Up,
+            MouseButtonEventKind::Click,
+            MouseButtonEventKind::DoubleClick,
+        ]
+    );
+    assert!(left.borrow().is_empty());
+}
+
+#[test]
+fn releasing_over_another_widget_is_not_a_click() {
+    let left: Events = Rc::new(RefCell::new(vec![]));
+    let right: Events = Rc::new(RefCell::new(vec![]));
+    let mut runner = runner(&left, &right);
+
+    runner.on_mouse_down(
+        Position { x: 45., y: 20. },
+        MouseButton::Left,
+        Modifiers::none(),
+    );
+    // Without a move in between this isn't a drag either
+    runner.on_mouse_up(
+        Position { x: 55., y: 20. },
+        MouseButton::Left,
+        Modifiers::none(),
+        2,
+    );
+    assert_eq!(kinds(&left), vec![MouseButtonEventKind::Down]);
+    assert_eq!(kinds(&right), vec![MouseButtonEventKind::Up]);
+}
+
+#[test]
+fn drag_stays_with_the_widget_it_started_on() {
+    let left: Events = Rc::new(RefCell::new(vec![]));
+    let right: Events = Rc::new(RefCell::new(vec![]));
+    let mut runner = runner(&left, &right);
+
+    runner.on_mouse_down(
+        Position { x: 20., y: 20. },
+        MouseButton::Left,
+        Modifiers::none(),
+    );
+    // Within the drag threshold, so still a potential click
+    runner.on_mouse_move(Position { x: 22., y: 20. });
+    assert_eq!(kinds(&left), vec![MouseButtonEventKind::Down]);
+
+    runner.on_mouse_move(Position { x: 30., y: 20. });
+    runner.on_mouse_move(Position { x: 70., y: 20. });
+    runner.on_mouse_up(
+        Position { x: 70., y: 20. },
+        MouseButton::Left,
+        Modifiers::none(),
+        1,
+    );
+    assert_eq!(
+        kinds(&left),
+        vec![
+            MouseButtonEventKind::Down,
+            MouseButtonEventKind::DragStart,
+            MouseButtonEventKind::Drag,
+            MouseButtonEventKind::Drag,
+            MouseButtonEventKind::DragEnd,
+        ]
+    );
+    // The drag starts where the button was pressed
+    assert_eq!(left.borrow()[1].position, Position { x: 20., y: 20. });
+    assert_eq!(left.borrow()[3].position, Position { x: 70., y: 20. });
+    // The release isn't a click, wherever it is
+    assert_eq!(kinds(&right), vec![MouseButtonEventKind::Up]);
+}
+
+#[test]
+fn clicks_on_a_scaled_child_are_mapped_into_its_coordinates() {
+    let left: Events = Rc::new(RefCell::new(vec![]));
+    let right: Events = Rc::new(RefCell::new(vec![]));
+    // Doubled in size, the left recorder covers x = 0 to 100 on screen
+    let mut runner = AppRunner::new(
+        platform(),
+        *Transformed::new(Transform::scale(2.), row(&left, &right)),
+        Theme::light(),
+    );
+    let position = Position { x: 70., y: 20. };
+
+    runner.on_mouse_down(position.clone(), MouseButton::Left, Modifiers::none());
+    runner.on_mouse_up(position, MouseButton::Left, Modifiers::none(), 1);
+    assert_eq!(
+        kinds(&left),
+        vec![
+            MouseButtonEventKind::Down,
+            MouseButtonEventKind::Up,
+            MouseButtonEventKind::Click,
+        ]
+    );
+    assert_eq!(left.borrow()[2].position, Position { x: 35., y: 10. });
+    assert!(right.borrow().is_empty());
+
+    runner.on_mouse_down(
+        Position { x: 150., y: 20. },
+        MouseButton::Left,
+        Modifiers::none(),
+    );
+    assert_eq!(kinds(&right), vec![MouseButtonEventKind::Down]);
+    // Positions are relative to the recorder, which starts at x = 50 before
+    // scaling
+    assert_eq!(right.borrow()[0].position, Position { x: 25., y: 10. });
+}
+
+#[test]
+fn clicks_on_overlapping_stack_children_go_to_the_topmost() {
+    let below: Events = Rc::new(RefCell::new(vec![]));
+    let above: Events = Rc::new(RefCell::new(vec![]));
+    // The recorder above overlaps the right half of the one below
+    let mut runner = AppRunner::new(
+        platform(),
+        *Stack::new(vec![
+            StackChild::at(Position::origin(), Recorder::new(&below)),
+            StackChild::at(Position { x: 25., y: 0. }, Recorder::new(&above)),
+        ]),
+        Theme::light(),
+    );
+    let click = |runner: &mut AppRunner<HeadlessPlatform, Stack>, x: f64| {
+        let position = Position { x, y: 20. };
+        runner.on_mouse_down(position.clone(), MouseButton::Left, Modifiers::none());
+        runner.on_mouse_up(position, MouseButton::Left, Modifiers::none(), 1);
+    };
+
+    click(&mut runner, 40.);
+    assert!(below.borrow().is_empty());
+    assert_eq!(kinds(&above).last(), Some(&MouseButtonEventKind::Click));
+
+    above.borrow_mut().clear();
+    click(&mut runner, 10.);
+    assert_eq!(kinds(&below).last(), Some(&MouseButtonEventKind::Click));
+    assert!(above.borrow().is_empty());
+}
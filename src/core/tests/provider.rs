@@ -0,0 +1,203 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+};
+
+use casserole_core::{
+    compound_widget_default_methods,
+    event_handlers::{
+        HandledEventInfo, Modifiers, MouseButton, MouseButtonEvent, MouseButtonEventKind,
+    },
+    graphics::{Color, Position, Size},
+    headless::HeadlessPlatform,
+    platform::Platform,
+    state::StateManager,
+    ui_library::{
+        constraints::BoxConstraints,
+        container::Container,
+        expanded::Expanded,
+        list::{
+            CrossAxisAlignment, CrossAxisSize, List, ListDirection, MainAxisAlignment, MainAxisSize,
+        },
+        provider::{lookup, Provider},
+        sized_box::SizedBox,
+        theme::Theme,
+        CompoundWidget, CompoundWidgetData, Key, Widget,
+    },
+    widget_default_methods, AppRunner,
+};
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+fn red() -> Color {
+    return Color::rgb(255, 0, 0);
+}
+
+fn green() -> Color {
+    return Color::rgb(0, 255, 0);
+}
+
+fn blue() -> Color {
+    return Color::rgb(0, 0, 255);
+}
+
+fn black() -> Color {
+    return Color::rgb(0, 0, 0);
+}
+
+/// A 50x50 box filled with the nearest provided colour, or black if there
+/// isn't one, which counts how many times it's built and provides `blue`
+/// in place of that colour when clicked
+#[derive(Debug, KeySegment)]
+struct Reader {
+    widget_data: CompoundWidgetData,
+    builds: Rc<Cell<usize>>,
+}
+
+impl Reader {
+    fn new(builds: &Rc<Cell<usize>>) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: CompoundWidgetData::new(),
+            builds: builds.clone(),
+        });
+    }
+}
+
+impl CompoundWidget for Reader {
+    compound_widget_default_methods!();
+
+    fn build(&self) -> Box<dyn Widget> {
+        self.builds.set(self.builds.get() + 1);
+        let color = match lookup::<Color>(self) {
+            Some(x) => (*x).clone(),
+            None => black(),
+        };
+        return SizedBox::new(
+            Size {
+                width: 50.,
+                height: 50.,
+            },
+            Container::new(color, Expanded::new()),
+        );
+    }
+
+    fn on_mouse_button(
+        &mut self,
+        event: &MouseButtonEvent,
+        _platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        if event.kind == MouseButtonEventKind::Click {
+            handled_event_info.update_context(CompoundWidget::get_key(self), blue());
+        }
+        return true;
+    }
+}
+
+fn row(children: Vec<Box<dyn Widget>>) -> Box<List> {
+    return List::new(
+        ListDirection::Row,
+        MainAxisAlignment::Start,
+        CrossAxisAlignment::Start,
+        MainAxisSize::Min,
+        CrossAxisSize::Min,
+        children,
+    );
+}
+
+fn counters(n: usize) -> Vec<Rc<Cell<usize>>> {
+    return (0..n).map(|_| Rc::new(Cell::new(0))).collect();
+}
+
+fn counts(counters: &[Rc<Cell<usize>>]) -> Vec<usize> {
+    return counters.iter().map(|x| x.get()).collect();
+}
+
+fn runner(app: List) -> AppRunner<HeadlessPlatform, List> {
+    let runner = AppRunner::new(
+        HeadlessPlatform::new(Size {
+            width: 150.,
+            height: 50.,
+        }),
+        app,
+        Theme::light(),
+    );
+    runner.draw_frame();
+    return runner;
+}
+
+fn click(runner: &mut AppRunner<HeadlessPlatform, List>, x: f64) {
+    let position = Position { x, y: 25. };
+    runner.on_mouse_down(position.clone(), MouseButton::Left, Modifiers::none());
+    runner.on_mouse_up(position, MouseButton::Left, Modifiers::none(), 1);
+}
+
+#[test]
+fn the_nearest_provider_of_a_type_is_found() {
+    let builds = counters(3);
+    let runner = runner(*row(vec![
+        Provider::new(
+            red(),
+            Provider::new(
+                // Not a colour, so it's skipped
+                1usize,
+                row(vec![
+                    Reader::new(&builds[0]),
+                    Provider::new(green(), Reader::new(&builds[1])),
+                ]),
+            ),
+        ),
+        Reader::new(&builds[2]),
+    ]));
+    let pixel = |x| runner.platform.graphics.get_pixel(x, 25);
+    assert_eq!(pixel(25), red());
+    assert_eq!(pixel(75), green());
+    assert_eq!(pixel(125), black());
+}
+
+#[test]
+fn updating_a_context_rebuilds_only_its_dependents() {
+    let builds = counters(3);
+    let mut runner = runner(*row(vec![
+        Provider::new(
+            red(),
+            row(vec![Reader::new(&builds[0]), Reader::new(&builds[1])]),
+        ),
+        Provider::new(green(), Reader::new(&builds[2])),
+    ]));
+    assert_eq!(counts(&builds), vec![1, 1, 1]);
+
+    // Both readers below the first provider see the new colour
+    click(&mut runner, 25.);
+    assert_eq!(counts(&builds), vec![2, 2, 1]);
+    let pixel = |x| runner.platform.graphics.get_pixel(x, 25);
+    assert_eq!(pixel(25), blue());
+    assert_eq!(pixel(75), blue());
+    assert_eq!(pixel(125), green());
+}
+
+#[test]
+fn updates_without_a_provider_are_ignored() {
+    let builds = counters(1);
+    let mut runner = runner(*row(vec![Reader::new(&builds[0])]));
+    click(&mut runner, 25.);
+    assert_eq!(counts(&builds), vec![1]);
+    assert_eq!(runner.platform.graphics.get_pixel(25, 25), black());
+}
+
+#[test]
+fn a_theme_provider_restyles_only_the_tree_below_it() {
+    let square = || {
+        SizedBox::new(
+            Size {
+                width: 50.,
+                height: 50.,
+            },
+            Expanded::new(),
+        )
+    };
+    let runner = runner(*row(vec![Provider::new(Theme::dark(), square()), square()]));
+    let children = runner.app.get_children();
+    assert_eq!(*Theme::of(children[0].get_children()[0]), Theme::dark());
+    assert_eq!(*Theme::of(children[1]), Theme::light());
+}
@@ -0,0 +1,162 @@
+use casserole_core::{
+    graphics::{Color, Position, Size},
+    headless::HeadlessPlatform,
+    ui_library::{
+        compound::hoverable::Hoverable,
+        container::Container,
+        expanded::Expanded,
+        list::{
+            CrossAxisAlignment, CrossAxisSize, List, ListDirection, MainAxisAlignment, MainAxisSize,
+        },
+        sized_box::SizedBox,
+        stack::{Stack, StackChild},
+        theme::Theme,
+        Alignment, Widget,
+    },
+    AppRunner,
+};
+
+fn platform() -> HeadlessPlatform {
+    return HeadlessPlatform::new(size(100., 100.));
+}
+
+fn size(width: f64, height: f64) -> Size {
+    return Size { width, height };
+}
+
+fn position(x: f64, y: f64) -> Position {
+    return Position { x, y };
+}
+
+fn sized(width: f64, height: f64) -> Box<dyn Widget> {
+    return SizedBox::new(size(width, height), Expanded::new());
+}
+
+/// `stack` in the top-left corner, where it's free to pick its own size
+fn loosely(stack: Box<Stack>) -> List {
+    return *List::new(
+        ListDirection::Column,
+        MainAxisAlignment::Start,
+        CrossAxisAlignment::Start,
+        MainAxisSize::Max,
+        CrossAxisSize::Max,
+        vec![stack],
+    );
+}
+
+/// The stack's size, and the position and size of each of its children
+fn placement(runner: &AppRunner<HeadlessPlatform, List>) -> (Size, Vec<(Position, Size)>) {
+    let stack = runner.app.get_children()[0];
+    return (
+        stack.get_size().clone(),
+        stack
+            .get_children()
+            .iter()
+            .map(|x| (x.get_position().clone(), x.get_size().clone()))
+            .collect(),
+    );
+}
+
+#[test]
+fn stack_fits_its_largest_aligned_children() {
+    let runner = AppRunner::new(
+        platform(),
+        loosely(Stack::new(vec![
+            StackChild::aligned(Alignment::top_left(), sized(60., 40.)),
+            StackChild::aligned(Alignment::center(), sized(20., 80.)),
+            StackChild::aligned(Alignment::bottom_right(), sized(20., 20.)),
+        ])),
+        Theme::light(),
+    );
+    assert_eq!(
+        placement(&runner),
+        (
+            size(60., 80.),
+            vec![
+                (position(0., 0.), size(60., 40.)),
+                (position(20., 0.), size(20., 80.)),
+                (position(40., 60.), size(20., 20.)),
+            ]
+        )
+    );
+}
+
+#[test]
+fn absolute_children_take_any_size_without_resizing_the_stack() {
+    let runner = AppRunner::new(
+        platform(),
+        loosely(Stack::new(vec![
+            StackChild::aligned(Alignment::center(), sized(30., 30.)),
+            StackChild::at(position(5., 70.), sized(200., 10.)),
+        ])),
+        Theme::light(),
+    );
+    assert_eq!(
+        placement(&runner),
+        (
+            size(30., 30.),
+            vec![
+                (position(0., 0.), size(30., 30.)),
+                (position(5., 70.), size(200., 10.)),
+            ]
+        )
+    );
+}
+
+#[test]
+fn stack_without_aligned_children_fills_the_space() {
+    let runner = AppRunner::new(
+        platform(),
+        loosely(Stack::new(vec![StackChild::at(
+            position(10., 10.),
+            sized(20., 20.),
+        )])),
+        Theme::light(),
+    );
+    assert_eq!(placement(&runner).0, size(100., 100.));
+}
+
+fn idle_color() -> Color {
+    return Color::rgb(0, 0, 255);
+}
+
+fn hover_square(length: f64, hovered_color: Color) -> Box<dyn Widget> {
+    return Hoverable::new(Box::new(move |is_hovered| {
+        SizedBox::new(
+            size(length, length),
+            Container::new(
+                if is_hovered {
+                    hovered_color.clone()
+                } else {
+                    idle_color()
+                },
+                Expanded::new(),
+            ),
+        )
+    }));
+}
+
+#[test]
+fn only_the_topmost_child_under_the_pointer_is_hovered() {
+    let green = Color::rgb(0, 255, 0);
+    let red = Color::rgb(255, 0, 0);
+    let mut runner = AppRunner::new(
+        platform(),
+        loosely(Stack::new(vec![
+            StackChild::aligned(Alignment::top_left(), hover_square(60., green.clone())),
+            StackChild::aligned(Alignment::top_left(), hover_square(30., red.clone())),
+        ])),
+        Theme::light(),
+    );
+    runner.draw_frame();
+
+    // Over both, but the small one is on top
+    runner.on_mouse_move(position(10., 10.));
+    assert_eq!(runner.platform.graphics.get_pixel(10, 10), red);
+    assert_eq!(runner.platform.graphics.get_pixel(50, 50), idle_color());
+
+    // Only over the big one
+    runner.on_mouse_move(position(50., 50.));
+    assert_eq!(runner.platform.graphics.get_pixel(10, 10), idle_color());
+    assert_eq!(runner.platform.graphics.get_pixel(50, 50), green);
+}
@@ -0,0 +1,186 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+};
+
+use casserole_core::{
+    compound_widget_default_methods,
+    event_handlers::{KeyCode, KeyboardEvent, Modifiers},
+    graphics::{Color, Position, Size},
+    headless::HeadlessPlatform,
+    state::StateManager,
+    ui_library::{
+        compound::focusable::Focusable,
+        constraints::BoxConstraints,
+        container::Container,
+        expanded::Expanded,
+        list::{
+            CrossAxisAlignment, CrossAxisSize, List, ListDirection, MainAxisAlignment, MainAxisSize,
+        },
+        provider::{lookup, Provider},
+        sized_box::SizedBox,
+        theme::Theme,
+        CompoundWidget, CompoundWidgetData, Key, Widget,
+    },
+    widget_default_methods, AppRunner,
+};
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+/// What `Counter` was built with each time: the provided count and whether
+/// its `Focusable` thought it was focused
+type Builds = Rc<RefCell<Vec<(u32, bool)>>>;
+
+fn square() -> Box<dyn Widget> {
+    return SizedBox::new(
+        Size {
+            width: 50.,
+            height: 50.,
+        },
+        Container::new(Color::rgb(0, 0, 255), Expanded::new()),
+    );
+}
+
+/// Shows the provided count, which Enter increments while it's focused
+#[derive(Debug, KeySegment)]
+struct Counter {
+    widget_data: CompoundWidgetData,
+    builds: Builds,
+}
+
+impl CompoundWidget for Counter {
+    compound_widget_default_methods!();
+
+    fn build(&self) -> Box<dyn Widget> {
+        let count = *lookup::<u32>(self).unwrap();
+        let builds = self.builds.clone();
+        return Focusable::new(
+            Box::new(move |is_focused| {
+                builds.borrow_mut().push((count, is_focused));
+                return square();
+            }),
+            Some(Box::new(move |key, event, handled_event_info| {
+                if let KeyboardEvent::KeyDown(x) = event {
+                    if x.key == KeyCode::Enter {
+                        handled_event_info.update_context(key, count + 1);
+                        return true;
+                    }
+                }
+                return false;
+            })),
+        );
+    }
+}
+
+/// Provides a count to a `Counter`, but only with the light theme, so that
+/// switching themes removes them and adds them back
+#[derive(Debug, KeySegment)]
+struct LightOnly {
+    widget_data: CompoundWidgetData,
+    builds: Builds,
+}
+
+impl CompoundWidget for LightOnly {
+    compound_widget_default_methods!();
+
+    fn build(&self) -> Box<dyn Widget> {
+        if *Theme::of(self) != Theme::light() {
+            return square();
+        }
+        return Provider::new(
+            0u32,
+            Box::new(Counter {
+                widget_data: CompoundWidgetData::new(),
+                builds: self.builds.clone(),
+            }),
+        );
+    }
+}
+
+#[test]
+fn removed_widgets_lose_their_state_and_provided_values() {
+    let builds: Builds = Rc::new(RefCell::new(vec![]));
+    let mut runner = AppRunner::new(
+        HeadlessPlatform::new(Size {
+            width: 100.,
+            height: 100.,
+        }),
+        LightOnly {
+            widget_data: CompoundWidgetData::new(),
+            builds: builds.clone(),
+        },
+        Theme::light(),
+    );
+    runner.on_key_down(KeyCode::Tab, Modifiers::none(), false);
+    runner.on_key_down(KeyCode::Enter, Modifiers::none(), false);
+    assert_eq!(*builds.borrow(), vec![(0, false), (0, true), (1, true)]);
+    builds.borrow_mut().clear();
+
+    runner.set_theme(Theme::dark());
+    runner.set_theme(Theme::light());
+    // Back from the initial values, rather than those of the removed widgets
+    assert_eq!(*builds.borrow(), vec![(0, false)]);
+
+    runner.on_key_down(KeyCode::Tab, Modifiers::none(), false);
+    runner.on_key_down(KeyCode::Enter, Modifiers::none(), false);
+    assert_eq!(*builds.borrow(), vec![(0, false), (0, true), (1, true)]);
+}
+
+/// Records the count provided to it when it's built
+#[derive(Debug, KeySegment)]
+struct Reader {
+    widget_data: CompoundWidgetData,
+    count: Rc<Cell<u32>>,
+}
+
+impl Reader {
+    fn new(count: &Rc<Cell<u32>>) -> Box<Self> {
+        return Box::new(Self {
+            widget_data: CompoundWidgetData::new(),
+            count: count.clone(),
+        });
+    }
+}
+
+impl CompoundWidget for Reader {
+    compound_widget_default_methods!();
+
+    fn build(&self) -> Box<dyn Widget> {
+        self.count.set(*lookup::<u32>(self).unwrap());
+        return square();
+    }
+}
+
+#[test]
+fn nearest_provider_of_the_type_wins() {
+    let outer = Rc::new(Cell::new(0));
+    let inner = Rc::new(Cell::new(0));
+    let app = *Provider::new(
+        1u32,
+        List::new(
+            ListDirection::Row,
+            MainAxisAlignment::Start,
+            CrossAxisAlignment::Start,
+            MainAxisSize::Max,
+            CrossAxisSize::Max,
+            vec![
+                Reader::new(&outer),
+                // Providers of other types around it are skipped over
+                Provider::new(
+                    true,
+                    Provider::new(2u32, Provider::new(false, Reader::new(&inner))),
+                ),
+            ],
+        ),
+    );
+    AppRunner::new(
+        HeadlessPlatform::new(Size {
+            width: 100.,
+            height: 100.,
+        }),
+        app,
+        Theme::light(),
+    );
+    assert_eq!(outer.get(), 1);
+    assert_eq!(inner.get(), 2);
+}
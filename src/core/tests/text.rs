@@ -0,0 +1,125 @@
+use casserole_core::{
+    graphics::{
+        text::{for_each_bitmap_text_rect, measure_bitmap_text, TextMetrics, TextStyle},
+        Color, Position, Rect, Size,
+    },
+    headless::snapshot::render_widget,
+    ui_library::{
+        container::Container,
+        expanded::Expanded,
+        list::{
+            CrossAxisAlignment, CrossAxisSize, List, ListDirection, MainAxisAlignment, MainAxisSize,
+        },
+        sized_box::SizedBox,
+        text::Text,
+    },
+};
+use font8x8::{UnicodeFonts, BASIC_FONTS};
+
+fn white() -> Color {
+    return Color::rgb(255, 255, 255);
+}
+
+#[test]
+fn bitmap_text_is_one_square_cell_per_character() {
+    assert_eq!(
+        measure_bitmap_text("abc", &TextStyle::new(16., white())),
+        TextMetrics {
+            width: 48.,
+            height: 16.,
+            baseline: 14.,
+        }
+    );
+    assert_eq!(
+        measure_bitmap_text("", &TextStyle::new(16., white())).width,
+        0.
+    );
+}
+
+#[test]
+fn bitmap_text_rects_cover_the_glyph_pixels() {
+    let style = TextStyle::new(16., white());
+    let glyph = BASIC_FONTS.get('A').unwrap();
+    let set_pixels: u32 = glyph.iter().map(|x| x.count_ones()).sum();
+
+    let mut rects: Vec<Rect> = vec![];
+    for_each_bitmap_text_rect(&Position { x: 10., y: 5. }, "AA", &style, |x| rects.push(x));
+    let area: f64 = rects.iter().map(|x| x.size.width * x.size.height).sum();
+    assert_eq!(area, 2. * set_pixels as f64 * 4.);
+    // Each row of a glyph is one pixel of the font scaled up to 2x2
+    assert!(rects.iter().all(|x| x.size.height == 2.));
+
+    let (first, second): (Vec<Rect>, Vec<Rect>) =
+        rects.into_iter().partition(|x| x.position.x < 26.);
+    assert!(first
+        .iter()
+        .all(|x| x.position.x >= 10. && x.position.x + x.size.width <= 26.));
+    // The second glyph is the first one moved along by a cell
+    assert_eq!(
+        second,
+        first
+            .iter()
+            .map(|x| Rect::new(
+                Position {
+                    x: x.position.x + 16.,
+                    y: x.position.y,
+                },
+                x.size.clone(),
+            ))
+            .collect::<Vec<Rect>>()
+    );
+}
+
+#[test]
+fn text_widget_draws_the_glyph_pixels() {
+    let framebuffer = render_widget(
+        Text::new("A", TextStyle::new(8., white())),
+        Size {
+            width: 10.,
+            height: 10.,
+        },
+    );
+    let glyph = BASIC_FONTS.get('A').unwrap();
+    for (y, bits) in glyph.iter().enumerate() {
+        for x in 0..8 {
+            let expected = if bits & (1 << x) != 0 {
+                white()
+            } else {
+                Color::rgb(0, 0, 0)
+            };
+            assert_eq!(framebuffer.get_pixel(x, y), expected, "at ({}, {})", x, y);
+        }
+    }
+}
+
+#[test]
+fn text_is_laid_out_at_its_measured_size() {
+    let blue = Color::rgb(0, 0, 255);
+    let framebuffer = render_widget(
+        List::new(
+            ListDirection::Row,
+            MainAxisAlignment::Start,
+            CrossAxisAlignment::Start,
+            MainAxisSize::Max,
+            CrossAxisSize::Max,
+            vec![
+                Text::new("Hi", TextStyle::new(10., white())),
+                SizedBox::new(
+                    Size {
+                        width: 10.,
+                        height: 10.,
+                    },
+                    Container::new(blue.clone(), Expanded::new()),
+                ),
+            ],
+        ),
+        Size {
+            width: 50.,
+            height: 20.,
+        },
+    );
+    assert_eq!(framebuffer.get_pixel(19, 5), Color::rgb(0, 0, 0));
+    assert_eq!(framebuffer.get_pixel(20, 5), blue);
+    assert_eq!(framebuffer.get_pixel(29, 5), blue);
+    assert_eq!(framebuffer.get_pixel(30, 5), Color::rgb(0, 0, 0));
+}
@@ -0,0 +1,111 @@
+use std::{cell::RefCell, rc::Weak};
+
+use casserole_core::{
+    compound_widget_default_methods,
+    event_handlers::{
+        HandledEventInfo, Modifiers, MouseButton, MouseButtonEvent, MouseButtonEventKind,
+    },
+    graphics::{Position, Size},
+    headless::HeadlessPlatform,
+    platform::Platform,
+    state::StateManager,
+    ui_library::{
+        constraints::BoxConstraints, container::Container, expanded::Expanded, theme::Theme,
+        CompoundWidget, CompoundWidgetData, Key, Widget,
+    },
+    widget_default_methods, AppRunner,
+};
+use key_segment::KeySegment;
+use key_segment_derive::KeySegment;
+
+/// Fills itself with the theme's background, and switches to the dark theme
+/// when clicked
+#[derive(Debug, KeySegment)]
+struct Swatch {
+    widget_data: CompoundWidgetData,
+}
+
+impl Swatch {
+    fn new() -> Box<Self> {
+        return Box::new(Self {
+            widget_data: CompoundWidgetData::new(),
+        });
+    }
+}
+
+impl CompoundWidget for Swatch {
+    compound_widget_default_methods!();
+
+    fn build(&self) -> Box<dyn Widget> {
+        return Container::new(Theme::of(self).palette.background.clone(), Expanded::new());
+    }
+
+    fn on_mouse_button(
+        &mut self,
+        event: &MouseButtonEvent,
+        _platform: &dyn Platform,
+        handled_event_info: &mut HandledEventInfo,
+    ) -> bool {
+        if event.kind == MouseButtonEventKind::Click {
+            handled_event_info.set_theme(Theme::dark());
+        }
+        return true;
+    }
+}
+
+fn runner() -> AppRunner<HeadlessPlatform, Swatch> {
+    let runner = AppRunner::new(
+        HeadlessPlatform::new(Size {
+            width: 100.,
+            height: 100.,
+        }),
+        *Swatch::new(),
+        Theme::light(),
+    );
+    runner.draw_frame();
+    return runner;
+}
+
+#[test]
+fn widgets_outside_a_tree_get_the_light_theme() {
+    assert_eq!(*Theme::of(Swatch::new().as_ref()), Theme::light());
+}
+
+#[test]
+fn set_theme_rebuilds_with_the_new_colours() {
+    let mut runner = runner();
+    assert_eq!(
+        runner.platform.graphics.get_pixel(50, 50),
+        Theme::light().palette.background
+    );
+
+    runner.set_theme(Theme::dark());
+    assert_eq!(*Theme::of(&runner.app), Theme::dark());
+    assert_eq!(
+        runner.platform.graphics.get_pixel(50, 50),
+        Theme::dark().palette.background
+    );
+}
+
+#[test]
+fn handlers_can_switch_the_theme() {
+    let mut runner = runner();
+    let position = Position { x: 50., y: 50. };
+    runner.on_mouse_down(position.clone(), MouseButton::Left, Modifiers::none());
+    runner.on_mouse_up(position, MouseButton::Left, Modifiers::none(), 1);
+    assert_eq!(*Theme::of(&runner.app), Theme::dark());
+    assert_eq!(
+        runner.platform.graphics.get_pixel(50, 50),
+        Theme::dark().palette.background
+    );
+}
+
+#[test]
+fn text_styles_use_the_typography() {
+    let theme = Theme::high_contrast();
+    let body = theme.body_style();
+    assert_eq!(body.font_size, theme.typography.body_size);
+    assert_eq!(body.font_family, theme.typography.font_family);
+    assert_eq!(body.color, theme.palette.on_background);
+    assert!(theme.typography.body_size > Theme::light().typography.body_size);
+}
@@ -0,0 +1,68 @@
+use casserole_core::{
+    graphics::{Color, Position, Size, Transform},
+    headless::{graphics::Framebuffer, snapshot::render_widget},
+    ui_library::{
+        container::Container,
+        expanded::Expanded,
+        list::{
+            CrossAxisAlignment, CrossAxisSize, List, ListDirection, MainAxisAlignment, MainAxisSize,
+        },
+        sized_box::SizedBox,
+        transformed::Transformed,
+    },
+};
+
+fn red() -> Color {
+    return Color::rgb(255, 0, 0);
+}
+
+fn is_red(framebuffer: &Framebuffer, x: usize, y: usize) -> bool {
+    return framebuffer.get_pixel(x, y) == red();
+}
+
+/// A 20x20 red square in the top-left corner, drawn through `transform`
+fn render(transform: Transform) -> Framebuffer {
+    return render_widget(
+        Transformed::new(
+            transform,
+            List::new(
+                ListDirection::Column,
+                MainAxisAlignment::Start,
+                CrossAxisAlignment::Start,
+                MainAxisSize::Max,
+                CrossAxisSize::Max,
+                vec![SizedBox::new(
+                    Size {
+                        width: 20.,
+                        height: 20.,
+                    },
+                    Container::new(red(), Expanded::new()),
+                )],
+            ),
+        ),
+        Size {
+            width: 100.,
+            height: 100.,
+        },
+    );
+}
+
+#[test]
+fn scaled_child_is_painted_bigger() {
+    let framebuffer = render(Transform::scale(2.5));
+    assert!(is_red(&framebuffer, 0, 0));
+    assert!(is_red(&framebuffer, 49, 49));
+    assert!(!is_red(&framebuffer, 50, 25));
+    assert!(!is_red(&framebuffer, 25, 50));
+}
+
+#[test]
+fn scale_is_applied_before_translation() {
+    let framebuffer =
+        render(Transform::scale(2.).then(&Transform::translate(Position { x: 10., y: 30. })));
+    assert!(!is_red(&framebuffer, 9, 35));
+    assert!(is_red(&framebuffer, 10, 30));
+    assert!(is_red(&framebuffer, 49, 69));
+    assert!(!is_red(&framebuffer, 50, 50));
+    assert!(!is_red(&framebuffer, 30, 70));
+}
@@ -0,0 +1,178 @@
+use casserole_core::{
+    graphics::{Position, Size},
+    headless::HeadlessPlatform,
+    ui_library::{
+        constraints::BoxConstraints,
+        expanded::Expanded,
+        list::{CrossAxisAlignment, ListDirection, MainAxisAlignment},
+        sized_box::SizedBox,
+        wrap::Wrap,
+        Widget,
+    },
+};
+
+fn size(width: f64, height: f64) -> Size {
+    return Size { width, height };
+}
+
+fn position(x: f64, y: f64) -> Position {
+    return Position { x, y };
+}
+
+fn sized(width: f64, height: f64) -> Box<dyn Widget> {
+    return SizedBox::new(size(width, height), Expanded::new());
+}
+
+/// Four children which take two runs to fit in a length of 100, with 10
+/// between each child and 5 between the runs: the first run is 90 long and
+/// 20 thick, and the second 80 long and 10 thick
+fn wrap(direction: ListDirection) -> Box<Wrap> {
+    let children = match direction {
+        ListDirection::Row => vec![
+            sized(40., 10.),
+            sized(40., 20.),
+            sized(40., 10.),
+            sized(30., 10.),
+        ],
+        ListDirection::Column => vec![
+            sized(10., 40.),
+            sized(20., 40.),
+            sized(10., 40.),
+            sized(10., 30.),
+        ],
+    };
+    return Wrap::new(direction, 10., 5., children);
+}
+
+/// Lays out `wrap` and returns its size and where each child went
+fn lay_out(mut wrap: Box<dyn Widget>, constraints: BoxConstraints) -> (Size, Vec<Position>) {
+    let platform = HeadlessPlatform::new(size(100., 100.));
+    let wrap_size = wrap.set_layout(&constraints, &platform);
+    return (
+        wrap_size,
+        wrap.get_children()
+            .iter()
+            .map(|x| x.get_position().clone())
+            .collect(),
+    );
+}
+
+#[test]
+fn children_which_do_not_fit_start_a_new_run() {
+    assert_eq!(
+        lay_out(
+            wrap(ListDirection::Row),
+            BoxConstraints::loose(&size(100., 100.))
+        ),
+        (
+            size(90., 35.),
+            vec![
+                position(0., 0.),
+                position(50., 0.),
+                position(0., 25.),
+                position(50., 25.),
+            ]
+        )
+    );
+}
+
+#[test]
+fn columns_wrap_into_more_columns() {
+    assert_eq!(
+        lay_out(
+            wrap(ListDirection::Column),
+            BoxConstraints::loose(&size(100., 100.))
+        ),
+        (
+            size(35., 90.),
+            vec![
+                position(0., 0.),
+                position(0., 50.),
+                position(25., 0.),
+                position(25., 50.),
+            ]
+        )
+    );
+}
+
+#[test]
+fn child_which_exactly_fits_stays_in_the_run() {
+    let wrap = Wrap::new(
+        ListDirection::Row,
+        10.,
+        5.,
+        vec![sized(45., 10.), sized(45., 10.), sized(1., 10.)],
+    );
+    assert_eq!(
+        lay_out(wrap, BoxConstraints::loose(&size(100., 100.))).1,
+        vec![position(0., 0.), position(55., 0.), position(0., 15.)]
+    );
+}
+
+#[test]
+fn run_spacing_and_alignment_place_the_runs() {
+    let tight = BoxConstraints::tight(&size(100., 100.));
+    // The runs and the space between them are 35 thick, leaving 65
+    let wrap_at = |run_alignment| {
+        wrap(ListDirection::Row).with_alignment(
+            MainAxisAlignment::Start,
+            run_alignment,
+            CrossAxisAlignment::Start,
+        )
+    };
+    let run_starts = |placed: Vec<Position>| (placed[0].y, placed[2].y);
+    assert_eq!(
+        run_starts(lay_out(wrap_at(MainAxisAlignment::End), tight.clone()).1),
+        (65., 90.)
+    );
+    assert_eq!(
+        run_starts(lay_out(wrap_at(MainAxisAlignment::Center), tight.clone()).1),
+        (32.5, 57.5)
+    );
+    assert_eq!(
+        run_starts(lay_out(wrap_at(MainAxisAlignment::SpaceBetween), tight).1),
+        (0., 90.)
+    );
+}
+
+#[test]
+fn alignment_places_the_children_within_each_run() {
+    let placed = lay_out(
+        wrap(ListDirection::Row).with_alignment(
+            MainAxisAlignment::End,
+            MainAxisAlignment::Start,
+            CrossAxisAlignment::Start,
+        ),
+        BoxConstraints::tight(&size(100., 100.)),
+    )
+    .1;
+    assert_eq!(
+        placed,
+        vec![
+            position(10., 0.),
+            position(60., 0.),
+            position(20., 25.),
+            position(70., 25.),
+        ]
+    );
+}
+
+#[test]
+fn cross_axis_alignment_places_each_child_within_its_run() {
+    let placed = |cross_axis_alignment| {
+        lay_out(
+            wrap(ListDirection::Row).with_alignment(
+                MainAxisAlignment::Start,
+                MainAxisAlignment::Start,
+                cross_axis_alignment,
+            ),
+            BoxConstraints::loose(&size(100., 100.)),
+        )
+        .1
+    };
+    // Only the first child is thinner than its run
+    assert_eq!(placed(CrossAxisAlignment::Center)[0], position(0., 5.));
+    assert_eq!(placed(CrossAxisAlignment::End)[0], position(0., 10.));
+    assert_eq!(placed(CrossAxisAlignment::End)[1], position(50., 0.));
+    assert_eq!(placed(CrossAxisAlignment::End)[2], position(0., 25.));
+}
@@ -1,16 +1,29 @@
-use casserole_core::graphics::{Color as CasseroleColor, GraphicsLibrary, Position, Size};
+use casserole_core::event_handlers::{KeyCode, Modifiers, MouseButton, WHEEL_LINE_HEIGHT};
+use casserole_core::graphics::bitmap::Bitmap;
+use casserole_core::graphics::display_list::DisplayList;
+use casserole_core::graphics::gradient::LinearGradient;
+use casserole_core::graphics::path::{Path, StrokeStyle};
+use casserole_core::graphics::raster::{rasterize, RasterTarget};
+use casserole_core::graphics::text::{measure_bitmap_text, TextMetrics, TextStyle};
+use casserole_core::graphics::{Color as CasseroleColor, GraphicsLibrary, Position, Rect, Size};
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use sdl2::event::{Event, WindowEvent};
-use sdl2::pixels::Color;
-use sdl2::rect::Rect;
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::mouse::{MouseButton as SDL2MouseButton, MouseWheelDirection};
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::Rect as SDL2Rect;
+use sdl2::render::{BlendMode, Canvas, Texture, TextureCreator};
+use sdl2::video::{Window, WindowContext};
+use std::collections::HashMap;
 use std::thread;
 use std::time::Duration;
 
 #[derive(Clone, Debug)]
 pub enum DrawCommand {
-    SetDrawColor { color: CasseroleColor },
-
-    FillRect { position: Position, size: Size },
+    DrawDisplayList(DisplayList),
+    UploadBitmap(Bitmap),
+    PushClip(Rect),
+    PopClip,
 
     UpdateDisplay,
 }
@@ -20,6 +33,190 @@ pub enum EventData {
     Quit,
     WindowResize,
     MouseMove(Position),
+    MouseLeaveWindow,
+    MouseDown {
+        position: Position,
+        button: MouseButton,
+        modifiers: Modifiers,
+    },
+    MouseUp {
+        position: Position,
+        button: MouseButton,
+        modifiers: Modifiers,
+        clicks: u32,
+    },
+    MouseWheel {
+        position: Position,
+        delta_x: f64,
+        delta_y: f64,
+        modifiers: Modifiers,
+    },
+    KeyDown {
+        key: KeyCode,
+        modifiers: Modifiers,
+        is_repeat: bool,
+    },
+    KeyUp {
+        key: KeyCode,
+        modifiers: Modifiers,
+    },
+    TextInput(String),
+}
+
+fn modifiers_from_sdl(keymod: Mod) -> Modifiers {
+    return Modifiers {
+        shift: keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+        ctrl: keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+        alt: keymod.intersects(Mod::LALTMOD | Mod::RALTMOD),
+        meta: keymod.intersects(Mod::LGUIMOD | Mod::RGUIMOD),
+    };
+}
+
+fn mouse_button_from_sdl(button: SDL2MouseButton) -> MouseButton {
+    return match button {
+        SDL2MouseButton::Left => MouseButton::Left,
+        SDL2MouseButton::Middle => MouseButton::Middle,
+        SDL2MouseButton::Right => MouseButton::Right,
+        SDL2MouseButton::X1 => MouseButton::Other(3),
+        SDL2MouseButton::X2 => MouseButton::Other(4),
+        SDL2MouseButton::Unknown => MouseButton::Other(0),
+    };
+}
+
+fn key_code_from_sdl(keycode: Option<Keycode>) -> KeyCode {
+    let keycode = match keycode {
+        Some(x) => x,
+        None => return KeyCode::Unknown,
+    };
+    return match keycode {
+        Keycode::Tab => KeyCode::Tab,
+        Keycode::Return | Keycode::KpEnter => KeyCode::Enter,
+        Keycode::Escape => KeyCode::Escape,
+        Keycode::Backspace => KeyCode::Backspace,
+        Keycode::Delete => KeyCode::Delete,
+        Keycode::Up => KeyCode::ArrowUp,
+        Keycode::Down => KeyCode::ArrowDown,
+        Keycode::Left => KeyCode::ArrowLeft,
+        Keycode::Right => KeyCode::ArrowRight,
+        Keycode::Home => KeyCode::Home,
+        Keycode::End => KeyCode::End,
+        Keycode::PageUp => KeyCode::PageUp,
+        Keycode::PageDown => KeyCode::PageDown,
+        Keycode::LShift | Keycode::RShift => KeyCode::Shift,
+        Keycode::LCtrl | Keycode::RCtrl => KeyCode::Control,
+        Keycode::LAlt | Keycode::RAlt => KeyCode::Alt,
+        Keycode::LGui | Keycode::RGui => KeyCode::Meta,
+        Keycode::F1 => KeyCode::F(1),
+        Keycode::F2 => KeyCode::F(2),
+        Keycode::F3 => KeyCode::F(3),
+        Keycode::F4 => KeyCode::F(4),
+        Keycode::F5 => KeyCode::F(5),
+        Keycode::F6 => KeyCode::F(6),
+        Keycode::F7 => KeyCode::F(7),
+        Keycode::F8 => KeyCode::F(8),
+        Keycode::F9 => KeyCode::F(9),
+        Keycode::F10 => KeyCode::F(10),
+        Keycode::F11 => KeyCode::F(11),
+        Keycode::F12 => KeyCode::F(12),
+        // SDL keycodes for printable keys are the (lower case) character
+        // the key produces without modifiers
+        _ => match char::from_u32(keycode as i32 as u32) {
+            Some(x) if !x.is_control() => KeyCode::Char(x),
+            _ => KeyCode::Unknown,
+        },
+    };
+}
+
+struct SDL2RasterTarget<'a, 't> {
+    canvas: &'a mut Canvas<Window>,
+    texture_creator: &'t TextureCreator<WindowContext>,
+    /// Uploaded bitmaps, by id
+    textures: &'a mut HashMap<usize, Option<Texture<'t>>>,
+    released_bitmaps: &'a Sender<usize>,
+}
+
+impl<'a, 't> RasterTarget for SDL2RasterTarget<'a, 't> {
+    fn fill_rect(&mut self, rect: &Rect, color: &CasseroleColor) {
+        let sdl_rect = match sdl_rect_from_rect(rect) {
+            Some(x) => x,
+            None => return,
+        };
+        self.canvas
+            .set_draw_color(Color::RGBA(color.r, color.g, color.b, color.a));
+        self.canvas.fill_rect(sdl_rect).unwrap();
+    }
+
+    // Only the visible part of the bitmap is copied, since the canvas clip
+    // isn't updated as the display list's clips change
+    fn draw_bitmap(&mut self, bitmap: &Bitmap, rect: &Rect, clip: &Rect) {
+        let visible = rect.intersection(clip);
+        let destination = match sdl_rect_from_rect(&visible) {
+            Some(x) => x,
+            None => return,
+        };
+        let scale_x = bitmap.width() as f64 / rect.size.width;
+        let scale_y = bitmap.height() as f64 / rect.size.height;
+        let source = sdl_rect_from_rect(&Rect::new(
+            Position {
+                x: (visible.position.x - rect.position.x) * scale_x,
+                y: (visible.position.y - rect.position.y) * scale_y,
+            },
+            Size {
+                width: visible.size.width * scale_x,
+                height: visible.size.height * scale_y,
+            },
+        ));
+        let texture_creator = self.texture_creator;
+        let released_bitmaps = self.released_bitmaps;
+        if let Some(texture) = self
+            .textures
+            .entry(bitmap.id())
+            .or_insert_with(|| create_bitmap_texture(texture_creator, bitmap, released_bitmaps))
+        {
+            self.canvas.copy(texture, source, destination).unwrap();
+        }
+    }
+}
+
+/// Copies a bitmap to a texture which blends with what it's drawn over.
+/// Empty bitmaps have no texture. The bitmap's id is sent to
+/// `released_bitmaps` once it's dropped, so that the texture can be freed.
+fn create_bitmap_texture<'t>(
+    texture_creator: &'t TextureCreator<WindowContext>,
+    bitmap: &Bitmap,
+    released_bitmaps: &Sender<usize>,
+) -> Option<Texture<'t>> {
+    let released_bitmaps = released_bitmaps.clone();
+    bitmap.set_release_handler(Box::new(move |id| {
+        // Nothing needs freeing if the window has already closed
+        let _ = released_bitmaps.send(id);
+    }));
+    if bitmap.width() == 0 || bitmap.height() == 0 {
+        return None;
+    }
+    let mut texture = texture_creator
+        .create_texture_static(
+            PixelFormatEnum::RGBA32,
+            bitmap.width() as u32,
+            bitmap.height() as u32,
+        )
+        .unwrap();
+    texture
+        .update(None, bitmap.pixels(), bitmap.width() * 4)
+        .unwrap();
+    texture.set_blend_mode(BlendMode::Blend);
+    return Some(texture);
+}
+
+fn sdl_rect_from_rect(rect: &Rect) -> Option<SDL2Rect> {
+    let x0 = rect.position.x.round() as i32;
+    let y0 = rect.position.y.round() as i32;
+    let x1 = rect.right().round() as i32;
+    let y1 = rect.bottom().round() as i32;
+    if x1 <= x0 || y1 <= y0 {
+        return None;
+    }
+    return Some(SDL2Rect::new(x0, y0, (x1 - x0) as u32, (y1 - y0) as u32));
 }
 
 pub struct SDL2GraphicsLibrary {
@@ -77,6 +274,12 @@ impl SDL2GraphicsLibrary {
             let mut texture = texture_creator
                 .create_texture_target(texture_creator.default_pixel_format(), size.0, size.1)
                 .unwrap();
+            let mut bitmap_textures: HashMap<usize, Option<Texture>> = HashMap::new();
+            let (released_bitmap_sender, released_bitmap_receiver) = unbounded::<usize>();
+            // Already intersected with each other and the screen
+            let mut clips: Vec<Rect> = vec![];
+            // Wheel events don't say where the pointer is
+            let mut mouse_position = Position::origin();
 
             'running: loop {
                 for event in event_pump.poll_iter() {
@@ -95,13 +298,122 @@ impl SDL2GraphicsLibrary {
                             xrel: _xrel,
                             yrel: _yrel,
                         } => {
+                            mouse_position = Position {
+                                x: x as f64,
+                                y: y as f64,
+                            };
+                            event_sender
+                                .send(EventData::MouseMove(mouse_position.clone()))
+                                .unwrap();
+                        }
+                        Event::MouseButtonDown {
+                            timestamp: _timestamp,
+                            window_id: _window_id,
+                            which: _which,
+                            mouse_btn,
+                            clicks: _clicks,
+                            x,
+                            y,
+                        } => {
+                            event_sender
+                                .send(EventData::MouseDown {
+                                    position: Position {
+                                        x: x as f64,
+                                        y: y as f64,
+                                    },
+                                    button: mouse_button_from_sdl(mouse_btn),
+                                    modifiers: modifiers_from_sdl(
+                                        sdl_context.keyboard().mod_state(),
+                                    ),
+                                })
+                                .unwrap();
+                        }
+                        Event::MouseButtonUp {
+                            timestamp: _timestamp,
+                            window_id: _window_id,
+                            which: _which,
+                            mouse_btn,
+                            clicks,
+                            x,
+                            y,
+                        } => {
+                            event_sender
+                                .send(EventData::MouseUp {
+                                    position: Position {
+                                        x: x as f64,
+                                        y: y as f64,
+                                    },
+                                    button: mouse_button_from_sdl(mouse_btn),
+                                    modifiers: modifiers_from_sdl(
+                                        sdl_context.keyboard().mod_state(),
+                                    ),
+                                    clicks: clicks as u32,
+                                })
+                                .unwrap();
+                        }
+                        Event::MouseWheel {
+                            timestamp: _timestamp,
+                            window_id: _window_id,
+                            which: _which,
+                            x,
+                            y,
+                            direction,
+                        } => {
+                            // SDL counts notches, with positive y away from
+                            // the user, which scrolls up
+                            let sign = match direction {
+                                MouseWheelDirection::Flipped => -1.,
+                                _ => 1.,
+                            };
                             event_sender
-                                .send(EventData::MouseMove(Position {
-                                    x: x as f64,
-                                    y: y as f64,
-                                }))
+                                .send(EventData::MouseWheel {
+                                    position: mouse_position.clone(),
+                                    delta_x: sign * x as f64 * WHEEL_LINE_HEIGHT,
+                                    delta_y: -sign * y as f64 * WHEEL_LINE_HEIGHT,
+                                    modifiers: modifiers_from_sdl(
+                                        sdl_context.keyboard().mod_state(),
+                                    ),
+                                })
+                                .unwrap();
+                        }
+                        Event::KeyDown {
+                            timestamp: _timestamp,
+                            window_id: _window_id,
+                            keycode,
+                            scancode: _scancode,
+                            keymod,
+                            repeat,
+                        } => {
+                            event_sender
+                                .send(EventData::KeyDown {
+                                    key: key_code_from_sdl(keycode),
+                                    modifiers: modifiers_from_sdl(keymod),
+                                    is_repeat: repeat,
+                                })
+                                .unwrap();
+                        }
+                        Event::KeyUp {
+                            timestamp: _timestamp,
+                            window_id: _window_id,
+                            keycode,
+                            scancode: _scancode,
+                            keymod,
+                            repeat: _repeat,
+                        } => {
+                            event_sender
+                                .send(EventData::KeyUp {
+                                    key: key_code_from_sdl(keycode),
+                                    modifiers: modifiers_from_sdl(keymod),
+                                })
                                 .unwrap();
                         }
+                        Event::TextInput {
+                            timestamp: _timestamp,
+                            window_id: _window_id,
+                            text,
+                        } => {
+                            event_sender.send(EventData::TextInput(text)).unwrap();
+                        }
                         Event::Window {
                             timestamp: _timestamp,
                             window_id: _window_id,
@@ -117,6 +429,9 @@ impl SDL2GraphicsLibrary {
                                     .unwrap();
                                 event_sender.send(EventData::WindowResize).unwrap();
                             }
+                            WindowEvent::Leave => {
+                                event_sender.send(EventData::MouseLeaveWindow).unwrap();
+                            }
                             _ => (),
                         },
                         _ => (),
@@ -124,28 +439,52 @@ impl SDL2GraphicsLibrary {
                 }
 
                 for cmd in draw_receiver.try_iter() {
+                    let size: (u32, u32) = canvas.output_size().unwrap();
+                    let screen = Rect::from_size(Size {
+                        width: size.0 as f64,
+                        height: size.1 as f64,
+                    });
+                    let clip = match clips.last() {
+                        Some(x) => x.clone(),
+                        None => screen,
+                    };
                     match cmd {
-                        DrawCommand::SetDrawColor { color } => {
+                        DrawCommand::DrawDisplayList(display_list) => {
                             canvas
                                 .with_texture_canvas(&mut texture, |texture_canvas| {
-                                    texture_canvas
-                                        .set_draw_color(Color::RGB(color.r, color.g, color.b));
+                                    // Composite translucent colours over what
+                                    // is already there rather than replacing it
+                                    texture_canvas.set_blend_mode(BlendMode::Blend);
+                                    // SDL resets the clip whenever the render
+                                    // target changes, so it is set every time
+                                    texture_canvas.set_clip_rect(sdl_rect_from_rect(&clip));
+                                    rasterize(
+                                        &display_list,
+                                        &clip,
+                                        &mut SDL2RasterTarget {
+                                            canvas: texture_canvas,
+                                            texture_creator: &texture_creator,
+                                            textures: &mut bitmap_textures,
+                                            released_bitmaps: &released_bitmap_sender,
+                                        },
+                                    );
                                 })
                                 .unwrap();
                         }
-                        DrawCommand::FillRect { position, size } => {
-                            canvas
-                                .with_texture_canvas(&mut texture, |texture_canvas| {
-                                    texture_canvas
-                                        .fill_rect(Rect::new(
-                                            position.x as i32,
-                                            position.y as i32,
-                                            size.width as u32,
-                                            size.height as u32,
-                                        ))
-                                        .unwrap();
-                                })
-                                .unwrap();
+                        DrawCommand::UploadBitmap(bitmap) => {
+                            bitmap_textures.entry(bitmap.id()).or_insert_with(|| {
+                                create_bitmap_texture(
+                                    &texture_creator,
+                                    &bitmap,
+                                    &released_bitmap_sender,
+                                )
+                            });
+                        }
+                        DrawCommand::PushClip(rect) => {
+                            clips.push(rect.intersection(&clip));
+                        }
+                        DrawCommand::PopClip => {
+                            clips.pop();
                         }
                         DrawCommand::UpdateDisplay => {
                             canvas.copy(&texture, None, None).unwrap();
@@ -153,6 +492,12 @@ impl SDL2GraphicsLibrary {
                     }
                 }
 
+                // The display lists which were just drawn may have held the
+                // last clones of some bitmaps
+                for id in released_bitmap_receiver.try_iter() {
+                    bitmap_textures.remove(&id);
+                }
+
                 for _ in get_screen_dims_receiver.try_iter() {
                     let size: (u32, u32) = canvas.output_size().unwrap();
                     screen_dims_sender
@@ -173,24 +518,72 @@ impl SDL2GraphicsLibrary {
 
 impl GraphicsLibrary for SDL2GraphicsLibrary {
     fn fill(&self, color: &CasseroleColor) {
-        self.fill_rect(
-            &Position { x: 0., y: 0. },
-            &self.get_screen_dimensions(),
-            &color,
-        );
+        let mut display_list = DisplayList::new();
+        display_list.fill(color);
+        self.draw_display_list(&display_list);
     }
 
     fn fill_rect(&self, position: &Position, size: &Size, color: &CasseroleColor) {
+        let mut display_list = DisplayList::new();
+        display_list.fill_rect(position, size, color);
+        self.draw_display_list(&display_list);
+    }
+
+    fn fill_path(&self, path: &Path, color: &CasseroleColor) {
+        let mut display_list = DisplayList::new();
+        display_list.fill_path(path, color);
+        self.draw_display_list(&display_list);
+    }
+
+    fn fill_path_gradient(&self, path: &Path, gradient: &LinearGradient) {
+        let mut display_list = DisplayList::new();
+        display_list.fill_path_gradient(path, gradient);
+        self.draw_display_list(&display_list);
+    }
+
+    fn stroke_path(&self, path: &Path, style: &StrokeStyle) {
+        let mut display_list = DisplayList::new();
+        display_list.stroke_path(path, style);
+        self.draw_display_list(&display_list);
+    }
+
+    // Text is rasterised by `SDL2RasterTarget` with the embedded bitmap font,
+    // so it is measured with the same font
+    fn fill_text(&self, position: &Position, text: &str, style: &TextStyle) {
+        let mut display_list = DisplayList::new();
+        display_list.fill_text(position, text, style);
+        self.draw_display_list(&display_list);
+    }
+
+    fn measure_text(&self, text: &str, style: &TextStyle) -> TextMetrics {
+        return measure_bitmap_text(text, style);
+    }
+
+    fn upload_bitmap(&self, bitmap: &Bitmap) {
+        self.draw_sender
+            .send(DrawCommand::UploadBitmap(bitmap.clone()))
+            .unwrap();
+    }
+
+    fn draw_bitmap(&self, bitmap: &Bitmap, position: &Position, size: &Size) {
+        let mut display_list = DisplayList::new();
+        display_list.draw_bitmap(bitmap, position, size);
+        self.draw_display_list(&display_list);
+    }
+
+    fn push_clip(&self, rect: &Rect) {
         self.draw_sender
-            .send(DrawCommand::SetDrawColor {
-                color: color.clone(),
-            })
+            .send(DrawCommand::PushClip(rect.clone()))
             .unwrap();
+    }
+
+    fn pop_clip(&self) {
+        self.draw_sender.send(DrawCommand::PopClip).unwrap();
+    }
+
+    fn draw_display_list(&self, display_list: &DisplayList) {
         self.draw_sender
-            .send(DrawCommand::FillRect {
-                position: position.clone(),
-                size: size.clone(),
-            })
+            .send(DrawCommand::DrawDisplayList(display_list.clone()))
             .unwrap();
     }
 
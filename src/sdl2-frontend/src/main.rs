@@ -48,6 +48,53 @@ pub fn main() {
                 graphics::EventData::MouseMove(position) => {
                     app_runner.borrow_mut().on_mouse_move(position);
                 }
+                graphics::EventData::MouseLeaveWindow => {
+                    app_runner.borrow_mut().on_mouse_leave_window();
+                }
+                graphics::EventData::MouseDown {
+                    position,
+                    button,
+                    modifiers,
+                } => {
+                    app_runner
+                        .borrow_mut()
+                        .on_mouse_down(position, button, modifiers);
+                }
+                graphics::EventData::MouseUp {
+                    position,
+                    button,
+                    modifiers,
+                    clicks,
+                } => {
+                    app_runner
+                        .borrow_mut()
+                        .on_mouse_up(position, button, modifiers, clicks);
+                }
+                graphics::EventData::MouseWheel {
+                    position,
+                    delta_x,
+                    delta_y,
+                    modifiers,
+                } => {
+                    app_runner
+                        .borrow_mut()
+                        .on_mouse_wheel(position, delta_x, delta_y, modifiers);
+                }
+                graphics::EventData::KeyDown {
+                    key,
+                    modifiers,
+                    is_repeat,
+                } => {
+                    app_runner
+                        .borrow_mut()
+                        .on_key_down(key, modifiers, is_repeat);
+                }
+                graphics::EventData::KeyUp { key, modifiers } => {
+                    app_runner.borrow_mut().on_key_up(key, modifiers);
+                }
+                graphics::EventData::TextInput(text) => {
+                    app_runner.borrow_mut().on_text_input(text);
+                }
             }
         }
     }
@@ -1,8 +1,90 @@
 use std::{cell::RefCell, rc::Rc};
 
-use casserole_core::{graphics::Position, platform::Platform, ui_library::Widget, AppRunner};
+use casserole_core::{
+    event_handlers::{KeyCode, Modifiers, MouseButton, WHEEL_LINE_HEIGHT},
+    graphics::Position,
+    platform::Platform,
+    ui_library::Widget,
+    AppRunner,
+};
 use wasm_bindgen::{prelude::Closure, JsCast};
 
+fn modifiers_from_mouse_event(event: &web_sys::MouseEvent) -> Modifiers {
+    return Modifiers {
+        shift: event.shift_key(),
+        ctrl: event.ctrl_key(),
+        alt: event.alt_key(),
+        meta: event.meta_key(),
+    };
+}
+
+fn mouse_button_from_mouse_event(event: &web_sys::MouseEvent) -> MouseButton {
+    return match event.button() {
+        0 => MouseButton::Left,
+        1 => MouseButton::Middle,
+        2 => MouseButton::Right,
+        x => MouseButton::Other(x as u8),
+    };
+}
+
+/// Converts the wheel event's deltas to pixels, whatever unit they came in
+fn wheel_deltas_in_pixels(event: &web_sys::WheelEvent) -> (f64, f64) {
+    let scale = match event.delta_mode() {
+        web_sys::WheelEvent::DOM_DELTA_LINE => WHEEL_LINE_HEIGHT,
+        web_sys::WheelEvent::DOM_DELTA_PAGE => web_sys::window()
+            .unwrap()
+            .inner_height()
+            .unwrap()
+            .as_f64()
+            .unwrap(),
+        _ => 1.,
+    };
+    return (event.delta_x() * scale, event.delta_y() * scale);
+}
+
+fn modifiers_from_keyboard_event(event: &web_sys::KeyboardEvent) -> Modifiers {
+    return Modifiers {
+        shift: event.shift_key(),
+        ctrl: event.ctrl_key(),
+        alt: event.alt_key(),
+        meta: event.meta_key(),
+    };
+}
+
+fn key_code_from_keyboard_event(event: &web_sys::KeyboardEvent) -> KeyCode {
+    let key = event.key();
+    return match key.as_str() {
+        "Tab" => KeyCode::Tab,
+        "Enter" => KeyCode::Enter,
+        "Escape" => KeyCode::Escape,
+        "Backspace" => KeyCode::Backspace,
+        "Delete" => KeyCode::Delete,
+        "ArrowUp" => KeyCode::ArrowUp,
+        "ArrowDown" => KeyCode::ArrowDown,
+        "ArrowLeft" => KeyCode::ArrowLeft,
+        "ArrowRight" => KeyCode::ArrowRight,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Shift" => KeyCode::Shift,
+        "Control" => KeyCode::Control,
+        "Alt" => KeyCode::Alt,
+        "Meta" => KeyCode::Meta,
+        _ => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(x), None) => KeyCode::Char(x.to_lowercase().next().unwrap_or(x)),
+                (Some('F'), Some(_)) => match key[1..].parse::<u8>() {
+                    Ok(x) => KeyCode::F(x),
+                    Err(_) => KeyCode::Unknown,
+                },
+                _ => KeyCode::Unknown,
+            }
+        }
+    };
+}
+
 pub fn register_event_listeners<PlatformType: Platform + 'static, AppType: Widget + 'static>(
     app_runner: Rc<RefCell<AppRunner<PlatformType, AppType>>>,
 ) {
@@ -40,4 +122,115 @@ pub fn register_event_listeners<PlatformType: Platform + 'static, AppType: Widge
         .add_event_listener_with_callback("mousemove", mousemove_closure.as_ref().unchecked_ref())
         .unwrap();
     mousemove_closure.forget();
+
+    let mouseout_ar: Rc<RefCell<AppRunner<PlatformType, AppType>>> = app_runner.clone();
+    let mouseout_closure: Closure<dyn Fn(_)> = Closure::new(move |event: web_sys::MouseEvent| {
+        // Only react when the pointer leaves the page, not when it moves
+        // between elements
+        if event.related_target().is_none() {
+            mouseout_ar.clone().borrow_mut().on_mouse_leave_window();
+        }
+    });
+    window
+        .add_event_listener_with_callback("mouseout", mouseout_closure.as_ref().unchecked_ref())
+        .unwrap();
+    mouseout_closure.forget();
+
+    let mousedown_ar: Rc<RefCell<AppRunner<PlatformType, AppType>>> = app_runner.clone();
+    let mousedown_closure: Closure<dyn Fn(_)> = Closure::new(move |event: web_sys::MouseEvent| {
+        mousedown_ar.clone().borrow_mut().on_mouse_down(
+            Position {
+                x: event.page_x() as f64,
+                y: event.page_y() as f64,
+            },
+            mouse_button_from_mouse_event(&event),
+            modifiers_from_mouse_event(&event),
+        );
+    });
+    window
+        .add_event_listener_with_callback("mousedown", mousedown_closure.as_ref().unchecked_ref())
+        .unwrap();
+    mousedown_closure.forget();
+
+    let mouseup_ar: Rc<RefCell<AppRunner<PlatformType, AppType>>> = app_runner.clone();
+    let mouseup_closure: Closure<dyn Fn(_)> = Closure::new(move |event: web_sys::MouseEvent| {
+        mouseup_ar.clone().borrow_mut().on_mouse_up(
+            Position {
+                x: event.page_x() as f64,
+                y: event.page_y() as f64,
+            },
+            mouse_button_from_mouse_event(&event),
+            modifiers_from_mouse_event(&event),
+            event.detail() as u32,
+        );
+    });
+    window
+        .add_event_listener_with_callback("mouseup", mouseup_closure.as_ref().unchecked_ref())
+        .unwrap();
+    mouseup_closure.forget();
+
+    let wheel_ar: Rc<RefCell<AppRunner<PlatformType, AppType>>> = app_runner.clone();
+    let wheel_closure: Closure<dyn Fn(_)> = Closure::new(move |event: web_sys::WheelEvent| {
+        // Keep the browser from scrolling or zooming the page
+        event.prevent_default();
+        let (delta_x, delta_y) = wheel_deltas_in_pixels(&event);
+        wheel_ar.clone().borrow_mut().on_mouse_wheel(
+            Position {
+                x: event.page_x() as f64,
+                y: event.page_y() as f64,
+            },
+            delta_x,
+            delta_y,
+            modifiers_from_mouse_event(&event),
+        );
+    });
+    // Wheel listeners on the window are passive by default, which would make
+    // prevent_default do nothing
+    let wheel_options = web_sys::AddEventListenerOptions::new();
+    wheel_options.set_passive(false);
+    window
+        .add_event_listener_with_callback_and_add_event_listener_options(
+            "wheel",
+            wheel_closure.as_ref().unchecked_ref(),
+            &wheel_options,
+        )
+        .unwrap();
+    wheel_closure.forget();
+
+    let keydown_ar: Rc<RefCell<AppRunner<PlatformType, AppType>>> = app_runner.clone();
+    let keydown_closure: Closure<dyn Fn(_)> = Closure::new(move |event: web_sys::KeyboardEvent| {
+        let key = key_code_from_keyboard_event(&event);
+        let modifiers = modifiers_from_keyboard_event(&event);
+        // Keep Tab from moving the browser's focus away from the canvas
+        if key == KeyCode::Tab {
+            event.prevent_default();
+        }
+        // Browsers have no separate text input event for canvases, so treat
+        // any printable key pressed without a shortcut modifier as text
+        let text = event.key();
+        let is_text = text.chars().count() == 1 && !modifiers.ctrl && !modifiers.meta;
+        keydown_ar
+            .clone()
+            .borrow_mut()
+            .on_key_down(key, modifiers, event.repeat());
+        if is_text {
+            keydown_ar.clone().borrow_mut().on_text_input(text);
+        }
+    });
+    window
+        .add_event_listener_with_callback("keydown", keydown_closure.as_ref().unchecked_ref())
+        .unwrap();
+    keydown_closure.forget();
+
+    let keyup_ar: Rc<RefCell<AppRunner<PlatformType, AppType>>> = app_runner.clone();
+    let keyup_closure: Closure<dyn Fn(_)> = Closure::new(move |event: web_sys::KeyboardEvent| {
+        keyup_ar.clone().borrow_mut().on_key_up(
+            key_code_from_keyboard_event(&event),
+            modifiers_from_keyboard_event(&event),
+        );
+    });
+    window
+        .add_event_listener_with_callback("keyup", keyup_closure.as_ref().unchecked_ref())
+        .unwrap();
+    keyup_closure.forget();
 }
@@ -1,5 +1,7 @@
+use std::collections::HashMap;
+
 use wasm_bindgen::prelude::*;
-use casserole_core::graphics::{self as core_graphics, GraphicsLibrary, Position};
+use casserole_core::graphics::{self as core_graphics, bitmap::Bitmap, display_list::{DisplayList, DrawOp}, gradient::LinearGradient, path::{LineCap, LineJoin, Path, PathSegment, StrokeStyle}, text::TextStyle, GraphicsLibrary, Position, Rect, Transform};
 
 #[wasm_bindgen]
 pub struct Size {
@@ -22,6 +24,24 @@ pub fn return_size(width: f64, height: f64) -> Size {
     return Size { width, height };
 }
 
+#[wasm_bindgen]
+pub struct TextMetrics {
+    pub width: f64,
+    pub height: f64,
+    pub baseline: f64,
+}
+
+impl TextMetrics {
+    pub fn to_native(&self) -> core_graphics::text::TextMetrics {
+        return core_graphics::text::TextMetrics { width: self.width, height: self.height, baseline: self.baseline };
+    }
+}
+
+#[wasm_bindgen]
+pub fn return_text_metrics(width: f64, height: f64, baseline: f64) -> TextMetrics {
+    return TextMetrics { width, height, baseline };
+}
+
 #[wasm_bindgen]
 extern {
   #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
@@ -30,6 +50,221 @@ extern {
   pub fn setFillStyle(fillStyle: &str);
   #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
   pub fn fillRect(x: f64, y: f64, width: f64, height: f64);
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn setFont(font: &str);
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn fillText(text: &str, x: f64, y: f64);
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn measureText(text: &str) -> TextMetrics;
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn beginPath();
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn moveTo(x: f64, y: f64);
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn lineTo(x: f64, y: f64);
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn bezierCurveTo(c1x: f64, c1y: f64, c2x: f64, c2y: f64, x: f64, y: f64);
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn ellipse(x: f64, y: f64, radiusX: f64, radiusY: f64, startAngle: f64, endAngle: f64, anticlockwise: bool);
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn closePath();
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn fillPath();
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn setLinearGradientFillStyle(x0: f64, y0: f64, x1: f64, y1: f64, startColor: &str, endColor: &str);
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn setStrokeStyle(strokeStyle: &str, lineWidth: f64, lineCap: &str, lineJoin: &str);
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn strokePath();
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn pushClip(x: f64, y: f64, width: f64, height: f64);
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn popClip();
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn hasImage(id: usize) -> bool;
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn uploadImage(id: usize, width: usize, height: usize, pixels: &[u8]);
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn releaseImage(id: usize);
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn drawImage(id: usize, x: f64, y: f64, width: f64, height: f64);
+  #[wasm_bindgen(js_namespace = ["window", "library", "graphics"])]
+  pub fn drawDisplayList(commands: &[f64], strings: Vec<String>);
+}
+
+fn trace_path(path: &Path) {
+    beginPath();
+    for segment in &path.segments {
+        match segment {
+            PathSegment::MoveTo(p) => moveTo(p.x, p.y),
+            PathSegment::LineTo(p) => lineTo(p.x, p.y),
+            PathSegment::CubicTo { control_1, control_2, to } => bezierCurveTo(control_1.x, control_1.y, control_2.x, control_2.y, to.x, to.y),
+            PathSegment::Arc { center, radii, start_angle, end_angle } => ellipse(center.x, center.y, radii.width, radii.height, *start_angle, *end_angle, end_angle < start_angle),
+            PathSegment::Close => closePath(),
+        }
+    }
+}
+
+fn css_font(style: &TextStyle) -> String {
+    return format!("{}px {}", style.font_size, style.font_family);
+}
+
+fn css_line_cap(cap: &LineCap) -> &'static str {
+    return match cap {
+        LineCap::Butt => "butt",
+        LineCap::Round => "round",
+        LineCap::Square => "square",
+    };
+}
+
+fn css_line_join(join: &LineJoin) -> &'static str {
+    return match join {
+        LineJoin::Miter => "miter",
+        LineJoin::Round => "round",
+        LineJoin::Bevel => "bevel",
+    };
+}
+
+// The opcodes understood by `drawDisplayList` in graphics.js
+const FILL_RECT: f64 = 0.;
+const FILL_PATH: f64 = 1.;
+const FILL_PATH_GRADIENT: f64 = 2.;
+const STROKE_PATH: f64 = 3.;
+const FILL_TEXT: f64 = 4.;
+const DRAW_IMAGE: f64 = 5.;
+const PUSH_CLIP: f64 = 6.;
+const POP_CLIP: f64 = 7.;
+
+const MOVE_TO: f64 = 0.;
+const LINE_TO: f64 = 1.;
+const CUBIC_TO: f64 = 2.;
+const ARC: f64 = 3.;
+const CLOSE: f64 = 4.;
+
+/// Flattens a display list into numbers and strings so that it can be handed
+/// to JS in one call. Transforms are applied here, as they are by
+/// `rasterize`, so the canvas only ever sees screen coordinates. Strings,
+/// i.e. colours, fonts and text, are referred to by their index in `strings`.
+struct DisplayListEncoder {
+    commands: Vec<f64>,
+    strings: Vec<String>,
+    string_indices: HashMap<String, usize>,
+}
+
+impl DisplayListEncoder {
+    fn new() -> Self {
+        return Self { commands: vec![], strings: vec![], string_indices: HashMap::new() };
+    }
+
+    fn encode(mut self, display_list: &DisplayList, screen: &Rect) -> (Vec<f64>, Vec<String>) {
+        let mut transforms: Vec<Transform> = vec![Transform::identity()];
+        let mut clip_depth = 0;
+        for op in &display_list.ops {
+            let transform = transforms.last().unwrap().clone();
+            match op {
+                DrawOp::Fill { color } => {
+                    self.commands.push(FILL_RECT);
+                    self.push_rect(screen);
+                    self.push_string(color.to_string());
+                }
+                DrawOp::FillRect { rect, color } => {
+                    self.commands.push(FILL_RECT);
+                    self.push_rect(&transform.apply_rect(rect));
+                    self.push_string(color.to_string());
+                }
+                DrawOp::FillPath { path, color } => {
+                    self.commands.push(FILL_PATH);
+                    self.push_string(color.to_string());
+                    self.push_path(&path.transformed(&transform));
+                }
+                DrawOp::FillPathGradient { path, gradient } => {
+                    let gradient = gradient.transformed(&transform);
+                    self.commands.push(FILL_PATH_GRADIENT);
+                    self.commands.extend([gradient.start.x, gradient.start.y, gradient.end.x, gradient.end.y]);
+                    self.push_string(gradient.start_color.to_string());
+                    self.push_string(gradient.end_color.to_string());
+                    self.push_path(&path.transformed(&transform));
+                }
+                DrawOp::StrokePath { path, style } => {
+                    self.commands.push(STROKE_PATH);
+                    self.push_string(style.color.to_string());
+                    self.commands.push(transform.apply_length(style.width));
+                    self.push_string(css_line_cap(&style.cap).into());
+                    self.push_string(css_line_join(&style.join).into());
+                    self.push_path(&path.transformed(&transform));
+                }
+                DrawOp::FillText { position, text, style } => {
+                    let style = TextStyle { font_size: transform.apply_length(style.font_size), ..style.clone() };
+                    let position = transform.apply(position);
+                    self.commands.push(FILL_TEXT);
+                    self.commands.extend([position.x, position.y]);
+                    self.push_string(css_font(&style));
+                    self.push_string(style.color.to_string());
+                    self.push_string(text.clone());
+                }
+                // The canvas can't hold an empty image, so those aren't drawn
+                DrawOp::DrawBitmap { bitmap, rect } => {
+                    if bitmap.width() > 0 && bitmap.height() > 0 {
+                        self.commands.extend([DRAW_IMAGE, bitmap.id() as f64]);
+                        self.push_rect(&transform.apply_rect(rect));
+                    }
+                }
+                DrawOp::PushClip { rect } => {
+                    self.commands.push(PUSH_CLIP);
+                    self.push_rect(&transform.apply_rect(rect));
+                    clip_depth += 1;
+                }
+                DrawOp::PopClip => {
+                    if clip_depth > 0 {
+                        self.commands.push(POP_CLIP);
+                        clip_depth -= 1;
+                    }
+                }
+                DrawOp::PushTransform { transform: new_transform } => {
+                    transforms.push(new_transform.then(&transform));
+                }
+                DrawOp::PopTransform => {
+                    if transforms.len() > 1 {
+                        transforms.pop();
+                    }
+                }
+            }
+        }
+        // Leave the canvas's clip as it was found, as `rasterize` does
+        for _ in 0..clip_depth {
+            self.commands.push(POP_CLIP);
+        }
+        return (self.commands, self.strings);
+    }
+
+    fn push_rect(&mut self, rect: &Rect) {
+        self.commands.extend([rect.position.x, rect.position.y, rect.size.width, rect.size.height]);
+    }
+
+    fn push_string(&mut self, string: String) {
+        let index = match self.string_indices.get(&string) {
+            Some(x) => *x,
+            None => {
+                self.strings.push(string.clone());
+                self.string_indices.insert(string, self.strings.len() - 1);
+                self.strings.len() - 1
+            }
+        };
+        self.commands.push(index as f64);
+    }
+
+    fn push_path(&mut self, path: &Path) {
+        self.commands.push(path.segments.len() as f64);
+        for segment in &path.segments {
+            match segment {
+                PathSegment::MoveTo(p) => self.commands.extend([MOVE_TO, p.x, p.y]),
+                PathSegment::LineTo(p) => self.commands.extend([LINE_TO, p.x, p.y]),
+                PathSegment::CubicTo { control_1, control_2, to } => self.commands.extend([CUBIC_TO, control_1.x, control_1.y, control_2.x, control_2.y, to.x, to.y]),
+                PathSegment::Arc { center, radii, start_angle, end_angle } => self.commands.extend([ARC, center.x, center.y, radii.width, radii.height, *start_angle, *end_angle]),
+                PathSegment::Close => self.commands.push(CLOSE),
+            }
+        }
+    }
 }
 
 pub struct WASMGraphicsLibrary;
@@ -38,9 +273,64 @@ impl GraphicsLibrary for WASMGraphicsLibrary {
         self.fill_rect(&Position { x: 0., y: 0. }, &self.get_screen_dimensions(), &color);
     }
     fn fill_rect(&self, position: &casserole_core::graphics::Position, size: &casserole_core::graphics::Size, color: &casserole_core::graphics::Color) {
-        setFillStyle(&format!("rgb({},{},{})", color.r, color.g, color.b));
+        setFillStyle(&color.to_string());
         fillRect(position.x, position.y, size.width, size.height);
     }
+    fn fill_path(&self, path: &Path, color: &core_graphics::Color) {
+        setFillStyle(&color.to_string());
+        trace_path(path);
+        fillPath();
+    }
+    fn fill_path_gradient(&self, path: &Path, gradient: &LinearGradient) {
+        setLinearGradientFillStyle(gradient.start.x, gradient.start.y, gradient.end.x, gradient.end.y, &gradient.start_color.to_string(), &gradient.end_color.to_string());
+        trace_path(path);
+        fillPath();
+    }
+    fn stroke_path(&self, path: &Path, style: &StrokeStyle) {
+        setStrokeStyle(&style.color.to_string(), style.width, css_line_cap(&style.cap), css_line_join(&style.join));
+        trace_path(path);
+        strokePath();
+    }
+    fn fill_text(&self, position: &Position, text: &str, style: &TextStyle) {
+        setFont(&css_font(style));
+        setFillStyle(&style.color.to_string());
+        fillText(text, position.x, position.y);
+    }
+    fn measure_text(&self, text: &str, style: &TextStyle) -> core_graphics::text::TextMetrics {
+        setFont(&css_font(style));
+        return measureText(text).to_native();
+    }
+    // The canvas can't hold an empty image, so those are never uploaded.
+    // The image is forgotten again once the last clone of the bitmap is
+    // dropped.
+    fn upload_bitmap(&self, bitmap: &Bitmap) {
+        if bitmap.width() > 0 && bitmap.height() > 0 && !hasImage(bitmap.id()) {
+            uploadImage(bitmap.id(), bitmap.width(), bitmap.height(), bitmap.pixels());
+            bitmap.set_release_handler(Box::new(|id| releaseImage(id)));
+        }
+    }
+    fn draw_bitmap(&self, bitmap: &Bitmap, position: &Position, size: &core_graphics::Size) {
+        self.upload_bitmap(bitmap);
+        drawImage(bitmap.id(), position.x, position.y, size.width, size.height);
+    }
+    fn push_clip(&self, rect: &Rect) {
+        pushClip(rect.position.x, rect.position.y, rect.size.width, rect.size.height);
+    }
+    fn pop_clip(&self) {
+        popClip();
+    }
+    /// Replays the whole list in one call into JS rather than one per op.
+    /// Bitmaps are uploaded first so that the list only refers to them by id.
+    fn draw_display_list(&self, display_list: &DisplayList) {
+        for op in &display_list.ops {
+            if let DrawOp::DrawBitmap { bitmap, .. } = op {
+                self.upload_bitmap(bitmap);
+            }
+        }
+        let screen = Rect::from_size(self.get_screen_dimensions());
+        let (commands, strings) = DisplayListEncoder::new().encode(display_list, &screen);
+        drawDisplayList(&commands, strings);
+    }
     fn get_screen_dimensions(&self) -> casserole_core::graphics::Size {
         return getScreenDimensions().to_native();
     }
@@ -1,8 +1,28 @@
-import { return_size } from "../../module/casserole/casserole_wasm.js";
+import { return_size, return_text_metrics } from "../../module/casserole/casserole_wasm.js";
 
 const canvas = document.getElementById("canvas");
 const ctx = canvas.getContext("2d");
 
+// Uploaded bitmaps by id, each an ImageBitmap or, until that's ready, a
+// canvas holding the same pixels
+const images = new Map();
+
+// The opcodes written by `DisplayListEncoder` in graphics.rs
+const FILL_RECT = 0;
+const FILL_PATH = 1;
+const FILL_PATH_GRADIENT = 2;
+const STROKE_PATH = 3;
+const FILL_TEXT = 4;
+const DRAW_IMAGE = 5;
+const PUSH_CLIP = 6;
+const POP_CLIP = 7;
+
+const MOVE_TO = 0;
+const LINE_TO = 1;
+const CUBIC_TO = 2;
+const ARC = 3;
+const CLOSE = 4;
+
 
 export default {
 
@@ -17,8 +37,227 @@ export default {
         ctx.fillStyle = fillStyle;
     },
 
+    setLinearGradientFillStyle: (x0, y0, x1, y1, startColor, endColor) => {
+        const gradient = ctx.createLinearGradient(x0, y0, x1, y1);
+        gradient.addColorStop(0, startColor);
+        gradient.addColorStop(1, endColor);
+        ctx.fillStyle = gradient;
+    },
+
     fillRect: (x, y, width, height) => {
         ctx.fillRect(x, y, width, height);
+    },
+
+    setFont: (font) => {
+        ctx.font = font;
+    },
+
+    // (x, y) is the top-left corner of the line box, which starts at the
+    // font's ascent rather than at the top of the tallest glyph
+    fillText: (text, x, y) => {
+        ctx.textBaseline = "alphabetic";
+        const metrics = ctx.measureText(text);
+        ctx.fillText(text, x, y + metrics.fontBoundingBoxAscent);
+    },
+
+    beginPath: () => {
+        ctx.beginPath();
+    },
+
+    moveTo: (x, y) => {
+        ctx.moveTo(x, y);
+    },
+
+    lineTo: (x, y) => {
+        ctx.lineTo(x, y);
+    },
+
+    bezierCurveTo: (c1x, c1y, c2x, c2y, x, y) => {
+        ctx.bezierCurveTo(c1x, c1y, c2x, c2y, x, y);
+    },
+
+    ellipse: (x, y, radiusX, radiusY, startAngle, endAngle, anticlockwise) => {
+        ctx.ellipse(x, y, radiusX, radiusY, 0, startAngle, endAngle, anticlockwise);
+    },
+
+    closePath: () => {
+        ctx.closePath();
+    },
+
+    fillPath: () => {
+        ctx.fill("nonzero");
+    },
+
+    setStrokeStyle: (strokeStyle, lineWidth, lineCap, lineJoin) => {
+        ctx.strokeStyle = strokeStyle;
+        ctx.lineWidth = lineWidth;
+        ctx.lineCap = lineCap;
+        ctx.lineJoin = lineJoin;
+        // Matches the miter limit used by the software tessellator
+        ctx.miterLimit = 4;
+    },
+
+    strokePath: () => {
+        ctx.stroke();
+    },
+
+    // Clips nest because the canvas intersects each clip with the current one,
+    // and restore() pops back to the clip which was active at the save()
+    pushClip: (x, y, width, height) => {
+        ctx.save();
+        ctx.beginPath();
+        ctx.rect(x, y, width, height);
+        ctx.clip();
+    },
+
+    popClip: () => {
+        ctx.restore();
+    },
+
+    hasImage: (id) => {
+        return images.has(id);
+    },
+
+    // The pixels are copied to a canvas straight away so that the image can
+    // be drawn in this frame, and then swapped for an ImageBitmap, which is
+    // quicker to draw, once it has been created
+    uploadImage: (id, width, height, pixels) => {
+        const imageData = new ImageData(new Uint8ClampedArray(pixels), width, height);
+        const imageCanvas = document.createElement("canvas");
+        imageCanvas.width = width;
+        imageCanvas.height = height;
+        imageCanvas.getContext("2d").putImageData(imageData, 0, 0);
+        images.set(id, imageCanvas);
+        createImageBitmap(imageData).then((bitmap) => {
+            // Unless it was released in the meantime
+            if (images.get(id) === imageCanvas) {
+                images.set(id, bitmap);
+            } else {
+                bitmap.close();
+            }
+        });
+    },
+
+    // Called once the last clone of the bitmap has been dropped
+    releaseImage: (id) => {
+        const image = images.get(id);
+        if (image instanceof ImageBitmap) {
+            image.close();
+        }
+        images.delete(id);
+    },
+
+    drawImage: (id, x, y, width, height) => {
+        const image = images.get(id);
+        if (image !== undefined) {
+            ctx.drawImage(image, x, y, width, height);
+        }
+    },
+
+    // Replays a display list flattened by `DisplayListEncoder` in
+    // graphics.rs: `commands` holds opcodes and their arguments, and strings
+    // are given by their index in `strings`
+    drawDisplayList: (commands, strings) => {
+        let i = 0;
+        const next = () => commands[i++];
+        const nextString = () => strings[commands[i++]];
+        const tracePath = () => {
+            ctx.beginPath();
+            const segmentCount = next();
+            for (let j = 0; j < segmentCount; j++) {
+                switch (next()) {
+                    case MOVE_TO:
+                        ctx.moveTo(next(), next());
+                        break;
+                    case LINE_TO:
+                        ctx.lineTo(next(), next());
+                        break;
+                    case CUBIC_TO:
+                        ctx.bezierCurveTo(next(), next(), next(), next(), next(), next());
+                        break;
+                    case ARC: {
+                        const [x, y, radiusX, radiusY, startAngle, endAngle] =
+                            [next(), next(), next(), next(), next(), next()];
+                        ctx.ellipse(x, y, radiusX, radiusY, 0, startAngle, endAngle, endAngle < startAngle);
+                        break;
+                    }
+                    case CLOSE:
+                        ctx.closePath();
+                        break;
+                }
+            }
+        };
+
+        while (i < commands.length) {
+            switch (next()) {
+                case FILL_RECT: {
+                    const [x, y, width, height] = [next(), next(), next(), next()];
+                    ctx.fillStyle = nextString();
+                    ctx.fillRect(x, y, width, height);
+                    break;
+                }
+                case FILL_PATH:
+                    ctx.fillStyle = nextString();
+                    tracePath();
+                    ctx.fill("nonzero");
+                    break;
+                case FILL_PATH_GRADIENT: {
+                    const gradient = ctx.createLinearGradient(next(), next(), next(), next());
+                    gradient.addColorStop(0, nextString());
+                    gradient.addColorStop(1, nextString());
+                    ctx.fillStyle = gradient;
+                    tracePath();
+                    ctx.fill("nonzero");
+                    break;
+                }
+                case STROKE_PATH:
+                    ctx.strokeStyle = nextString();
+                    ctx.lineWidth = next();
+                    ctx.lineCap = nextString();
+                    ctx.lineJoin = nextString();
+                    ctx.miterLimit = 4;
+                    tracePath();
+                    ctx.stroke();
+                    break;
+                case FILL_TEXT: {
+                    const [x, y] = [next(), next()];
+                    ctx.font = nextString();
+                    ctx.fillStyle = nextString();
+                    ctx.textBaseline = "alphabetic";
+                    const text = nextString();
+                    const metrics = ctx.measureText(text);
+                    ctx.fillText(text, x, y + metrics.fontBoundingBoxAscent);
+                    break;
+                }
+                case DRAW_IMAGE: {
+                    const image = images.get(next());
+                    const [x, y, width, height] = [next(), next(), next(), next()];
+                    if (image !== undefined) {
+                        ctx.drawImage(image, x, y, width, height);
+                    }
+                    break;
+                }
+                case PUSH_CLIP:
+                    ctx.save();
+                    ctx.beginPath();
+                    ctx.rect(next(), next(), next(), next());
+                    ctx.clip();
+                    break;
+                case POP_CLIP:
+                    ctx.restore();
+                    break;
+            }
+        }
+    },
+
+    measureText: (text) => {
+        ctx.textBaseline = "alphabetic";
+        const metrics = ctx.measureText(text);
+        return return_text_metrics(
+            metrics.width,
+            metrics.fontBoundingBoxAscent + metrics.fontBoundingBoxDescent,
+            metrics.fontBoundingBoxAscent,
+        );
     }
 
 };
\ No newline at end of file